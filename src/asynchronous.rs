@@ -0,0 +1,159 @@
+// Async variants of parsing, conversion, output and --execute for tokio services
+// (feature "tokio"); the CPU-bound work runs on tokio's blocking thread pool
+use crate::converter::Converter;
+use crate::errors::{OutputError, Xlsx2SqlError};
+use crate::output::database::{self, DatabaseUrl, ExecuteOptions, TableCount};
+use crate::parser::data_model::{SqlStatement, WorkbookData};
+use crate::parser::ParseOptions;
+use std::path::PathBuf;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+/// Run `work` on the blocking pool, passing on its panic if it has one
+async fn blocking<T: Send + 'static>(work: impl FnOnce() -> T + Send + 'static) -> T {
+    match tokio::task::spawn_blocking(work).await {
+        Ok(value) => value,
+        Err(e) => match e.try_into_panic() {
+            Ok(panic) => std::panic::resume_unwind(panic),
+            Err(e) => panic!("blocking conversion task did not finish: {e}"),
+        },
+    }
+}
+
+/// [`crate::parse`] without blocking the runtime
+pub async fn parse(
+    path: impl Into<PathBuf>,
+    options: ParseOptions,
+) -> Result<WorkbookData, Xlsx2SqlError> {
+    let path = path.into();
+    blocking(move || crate::parse(&path, options)).await
+}
+
+/// [`crate::parse_bytes`] without blocking the runtime, e.g. for an uploaded file
+pub async fn parse_bytes(
+    name: impl Into<String>,
+    bytes: Vec<u8>,
+    options: ParseOptions,
+) -> Result<WorkbookData, Xlsx2SqlError> {
+    let name = name.into();
+    blocking(move || crate::parse_bytes(&name, &bytes, options)).await
+}
+
+/// [`Converter::convert`] without blocking the runtime
+pub async fn convert(
+    converter: &Converter,
+    path: impl Into<PathBuf>,
+) -> Result<String, Xlsx2SqlError> {
+    let (converter, path) = (converter.clone(), path.into());
+    blocking(move || converter.convert(&path)).await
+}
+
+/// [`Converter::convert_bytes`] without blocking the runtime
+pub async fn convert_bytes(
+    converter: &Converter,
+    name: impl Into<String>,
+    bytes: Vec<u8>,
+) -> Result<String, Xlsx2SqlError> {
+    let (converter, name) = (converter.clone(), name.into());
+    blocking(move || converter.convert_bytes(&name, &bytes)).await
+}
+
+/// Write `statements` formatted by `converter` to `writer`, such as a
+/// `tokio::fs::File` or a response body, one statement at a time
+pub async fn write_statements<W: AsyncWrite + Unpin>(
+    writer: &mut W,
+    converter: &Converter,
+    statements: &[SqlStatement],
+) -> Result<(), OutputError> {
+    for (idx, statement) in statements.iter().enumerate() {
+        // Formatted here rather than held across the await: generators are not Send
+        let sql = {
+            let separator = if idx == 0 { "" } else { "\n" };
+            format!(
+                "{separator}{}\n",
+                converter.generator().format_statement(statement)
+            )
+        };
+        writer.write_all(sql.as_bytes()).await?;
+    }
+    writer.flush().await?;
+    Ok(())
+}
+
+/// Insert `statements` into `database` as --execute does, on the blocking pool;
+/// returns the rows each table received
+pub async fn execute(
+    database: DatabaseUrl,
+    converter: &Converter,
+    statements: Vec<SqlStatement>,
+    dry_run: bool,
+) -> Result<Vec<TableCount>, Xlsx2SqlError> {
+    let converter = converter.clone();
+    blocking(move || {
+        let generator = converter.generator();
+        let options = converter.options();
+        database::execute(
+            &database,
+            &statements,
+            &|statement| generator.format_statement(statement),
+            ExecuteOptions {
+                schema: options.schema.as_deref(),
+                insert_mode: options.insert_mode,
+                dry_run,
+            },
+            &mut |_| {},
+        )
+        .map_err(|e| OutputError::Database(e).into())
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::Dialect;
+
+    fn runtime() -> tokio::runtime::Runtime {
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_convert_and_write() {
+        let converter = Converter::builder().dialect(Dialect::Postgres).build();
+        let bytes = b"id,item\n1,Pen\n".to_vec();
+        runtime().block_on(async {
+            let sql = convert_bytes(&converter, "orders.csv", bytes.clone())
+                .await
+                .unwrap();
+            assert_eq!(
+                sql,
+                "INSERT INTO \"orders\" (\"id\", \"item\") VALUES\n(1,'Pen');\n"
+            );
+
+            let workbook = parse_bytes("orders.csv", bytes, ParseOptions::default())
+                .await
+                .unwrap();
+            let statements = converter.statements(&workbook).unwrap();
+            let mut out = Vec::new();
+            write_statements(&mut out, &converter, &statements)
+                .await
+                .unwrap();
+            assert_eq!(String::from_utf8(out).unwrap(), sql);
+        });
+    }
+
+    #[test]
+    fn test_futures_are_send() {
+        fn assert_send<T: Send>(_: T) {}
+        let converter = Converter::builder().build();
+        assert_send(convert(&converter, "orders.xlsx"));
+        assert_send(write_statements(&mut tokio::io::sink(), &converter, &[]));
+    }
+
+    #[test]
+    fn test_missing_file() {
+        let result = runtime().block_on(parse("no-such-file.xlsx", ParseOptions::default()));
+        assert_eq!(result.unwrap_err().exit_code(), 3);
+    }
+}
@@ -0,0 +1,428 @@
+// Conversion settings for library users (Converter::builder()...build().convert(path))
+use crate::errors::Xlsx2SqlError;
+use crate::generator::column_type::ColumnType;
+use crate::generator::mask::{ColumnMask, MaskMethod};
+use crate::generator::naming::{DuplicateHeaders, IdentifierCase, ReservedWords};
+use crate::generator::row_filter::RowFilter;
+use crate::generator::row_hook::{RowAction, RowHook};
+use crate::generator::template::SchemaTemplate;
+use crate::generator::{
+    generator_for, BoolFormat, CellErrorPolicy, Dialect, EmptyStringPolicy, GeneratorOptions,
+    InsertMode, KeywordCase, NumberLocale, SqlGenerator, SqlStyle,
+};
+use crate::output::split::{split_statement, FileLimits};
+use crate::parser::data_model::{SqlStatement, SqlValue, WorkbookData};
+use crate::parser::ParseOptions;
+use encoding_rs::Encoding;
+use std::path::Path;
+
+/// Everything a conversion is configured with; see [`Converter::builder`]
+#[derive(Debug, Clone, Default)]
+pub struct Converter {
+    dialect: Dialect,
+    parse: ParseOptions,
+    options: GeneratorOptions,
+    batch_size: Option<usize>,
+}
+
+/// Sets up a [`Converter`] option by option; unset options keep the
+/// command line tool's defaults
+#[derive(Debug, Clone, Default)]
+pub struct ConverterBuilder {
+    converter: Converter,
+}
+
+impl Converter {
+    pub fn builder() -> ConverterBuilder {
+        ConverterBuilder::default()
+    }
+
+    pub fn dialect(&self) -> Dialect {
+        self.dialect
+    }
+
+    pub fn options(&self) -> &GeneratorOptions {
+        &self.options
+    }
+
+    /// Generator formatting statements and values as this converter does
+    pub fn generator(&self) -> Box<dyn SqlGenerator> {
+        generator_for(self.dialect, self.options.clone())
+    }
+
+    /// Read the workbook or CSV file at `path`
+    pub fn read(&self, path: &Path) -> Result<WorkbookData, Xlsx2SqlError> {
+        crate::parse(path, self.parse)
+    }
+
+    /// The converted rows of `workbook`, at most `batch_size` per statement
+    pub fn statements(&self, workbook: &WorkbookData) -> Result<Vec<SqlStatement>, Xlsx2SqlError> {
+        let statements = self.generator().generate(workbook)?;
+        let limits = FileLimits {
+            max_bytes: None,
+            max_rows: self.batch_size,
+        };
+        Ok(statements
+            .into_iter()
+            .flat_map(|statement| split_statement(statement, limits, 0, |_| 0))
+            .collect())
+    }
+
+    /// SQL for the workbook or CSV file at `path`
+    pub fn convert(&self, path: &Path) -> Result<String, Xlsx2SqlError> {
+        self.convert_workbook(&self.read(path)?)
+    }
+
+    /// SQL for a workbook or CSV file held in memory; `name` stands in for the
+    /// file name where sheets are named after it
+    pub fn convert_bytes(&self, name: &str, bytes: &[u8]) -> Result<String, Xlsx2SqlError> {
+        self.convert_workbook(&crate::parse_bytes(name, bytes, self.parse)?)
+    }
+
+    pub fn convert_workbook(&self, workbook: &WorkbookData) -> Result<String, Xlsx2SqlError> {
+        Ok(format_sql(
+            self.generator().as_ref(),
+            &self.statements(workbook)?,
+        ))
+    }
+}
+
+/// Statements separated by blank lines, as the command line tool writes them
+pub(crate) fn format_sql(generator: &dyn SqlGenerator, statements: &[SqlStatement]) -> String {
+    statements
+        .iter()
+        .map(|statement| generator.format_statement(statement) + "\n")
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+impl ConverterBuilder {
+    pub fn build(self) -> Converter {
+        self.converter
+    }
+
+    pub fn dialect(mut self, dialect: Dialect) -> Self {
+        self.converter.dialect = dialect;
+        self
+    }
+
+    /// Rows per INSERT statement; a sheet with more gets several
+    pub fn batch_size(mut self, rows: usize) -> Self {
+        self.converter.batch_size = Some(rows.max(1));
+        self
+    }
+
+    /// Worksheet row (1-based) holding the headers
+    pub fn header_row(mut self, row: usize) -> Self {
+        self.converter.options.skip_rows = row.saturating_sub(1);
+        self
+    }
+
+    /// Data rows converted per sheet
+    pub fn max_rows(mut self, rows: usize) -> Self {
+        self.converter.options.max_rows = Some(rows);
+        self
+    }
+
+    /// Rows such as totals at the bottom of each sheet
+    pub fn skip_footer(mut self, rows: usize) -> Self {
+        self.converter.options.skip_footer = rows;
+        self
+    }
+
+    /// Rows emitted per table, across all the sheets inserting into it
+    pub fn limit(mut self, rows: usize) -> Self {
+        self.converter.options.limit = Some(rows);
+        self
+    }
+
+    /// Convert only the sheet named `name`; call again for more sheets
+    pub fn sheet(mut self, name: impl Into<String>) -> Self {
+        self.converter.options.sheet_filter.names.push(name.into());
+        self
+    }
+
+    /// Only rows for which `filter` holds are converted
+    pub fn filter(mut self, filter: RowFilter) -> Self {
+        self.converter.options.row_filter = Some(filter);
+        self
+    }
+
+    /// Table every statement inserts into instead of the sheet name
+    pub fn table_name(mut self, table: impl Into<String>) -> Self {
+        self.converter.options.table_name = Some(table.into());
+        self
+    }
+
+    pub fn table_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.converter.options.table_prefix = prefix.into();
+        self
+    }
+
+    pub fn table_suffix(mut self, suffix: impl Into<String>) -> Self {
+        self.converter.options.table_suffix = suffix.into();
+        self
+    }
+
+    /// Schema qualifying every table name
+    pub fn schema(mut self, schema: impl Into<String>) -> Self {
+        self.converter.options.schema = Some(schema.into());
+        self
+    }
+
+    /// Combine the rows of all converted sheets into one statement
+    pub fn union_sheets(mut self, union: bool) -> Self {
+        self.converter.options.union_sheets = union;
+        self
+    }
+
+    /// Table names, column names and types of the template's sheets
+    pub fn template(mut self, template: SchemaTemplate) -> Self {
+        self.converter
+            .options
+            .table_mappings
+            .extend(template.into_mappings());
+        self
+    }
+
+    /// Insert the column headed `header` as `column`
+    pub fn rename(mut self, header: impl Into<String>, column: impl Into<String>) -> Self {
+        self.converter
+            .options
+            .column_renames
+            .push((header.into(), column.into()));
+        self
+    }
+
+    /// Convert the values of the column headed `header` to `column_type`
+    pub fn column_type(mut self, header: impl Into<String>, column_type: ColumnType) -> Self {
+        self.converter
+            .options
+            .column_types
+            .insert(header.into(), column_type);
+        self
+    }
+
+    pub fn snake_case(mut self, snake_case: bool) -> Self {
+        self.converter.options.identifiers.snake_case = snake_case;
+        self
+    }
+
+    pub fn identifier_case(mut self, case: IdentifierCase) -> Self {
+        self.converter.options.identifiers.case = case;
+        self
+    }
+
+    pub fn sanitize_identifiers(mut self, sanitize: bool) -> Self {
+        self.converter.options.identifiers.sanitize = sanitize;
+        self
+    }
+
+    pub fn reserved_words(mut self, reserved: ReservedWords) -> Self {
+        self.converter.options.identifiers.reserved = reserved;
+        self
+    }
+
+    pub fn duplicate_headers(mut self, duplicates: DuplicateHeaders) -> Self {
+        self.converter.options.identifiers.duplicates = duplicates;
+        self
+    }
+
+    /// Text cells equal to one of `tokens` become NULL
+    pub fn null_values<S: Into<String>>(mut self, tokens: impl IntoIterator<Item = S>) -> Self {
+        self.converter.options.null_values = tokens.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn empty_string(mut self, policy: EmptyStringPolicy) -> Self {
+        self.converter.options.empty_string = policy;
+        self
+    }
+
+    /// Mask the values of the column headed or named `column`
+    pub fn mask(mut self, column: impl Into<String>, method: MaskMethod) -> Self {
+        self.converter.options.masks.push(ColumnMask {
+            column: column.into(),
+            method,
+        });
+        self
+    }
+
+    /// Strip leading and trailing whitespace from text
+    pub fn trim(mut self, trim: bool) -> Self {
+        self.converter.options.trim = trim;
+        self
+    }
+
+    /// Replace runs of whitespace inside text with a single space
+    pub fn collapse_whitespace(mut self, collapse: bool) -> Self {
+        self.converter.options.collapse_whitespace = collapse;
+        self
+    }
+
+    /// Parse text cells such as "1.234,56" as numbers written in `locale`
+    pub fn number_locale(mut self, locale: NumberLocale) -> Self {
+        self.converter.options.number_locale = Some(locale);
+        self
+    }
+
+    pub fn bool_format(mut self, format: BoolFormat) -> Self {
+        self.converter.options.bool_format = Some(format);
+        self
+    }
+
+    /// Fractional second digits kept in datetimes (0-6)
+    pub fn datetime_precision(mut self, digits: usize) -> Self {
+        self.converter.options.datetime_precision = digits.min(6);
+        self
+    }
+
+    pub fn on_cell_error(mut self, policy: CellErrorPolicy) -> Self {
+        self.converter.options.on_cell_error = policy;
+        self
+    }
+
+    /// Skip rows and sheets that fail to convert instead of stopping
+    pub fn keep_going(mut self, keep_going: bool) -> Self {
+        self.converter.options.keep_going = keep_going;
+        self
+    }
+
+    /// INSERT, INSERT IGNORE or upsert, with the conflict target of upserts
+    pub fn insert_mode(mut self, mode: InsertMode, key: Option<&str>) -> Self {
+        self.converter.options.insert_mode = mode;
+        self.converter.options.upsert_key = key.map(str::to_string);
+        self
+    }
+
+    pub fn style(mut self, style: SqlStyle) -> Self {
+        self.converter.options.layout.style = style;
+        self
+    }
+
+    pub fn keyword_case(mut self, case: KeywordCase) -> Self {
+        self.converter.options.layout.keyword_case = case;
+        self
+    }
+
+    /// Field delimiter of CSV input; tab for .tsv files and comma otherwise
+    pub fn delimiter(mut self, delimiter: u8) -> Self {
+        self.converter.parse.csv.delimiter = Some(delimiter);
+        self
+    }
+
+    /// Character encoding of CSV input; a BOM takes precedence
+    pub fn encoding(mut self, encoding: &'static Encoding) -> Self {
+        self.converter.parse.csv.encoding = encoding;
+        self
+    }
+
+    /// Copy the value of merged cells into every cell they cover
+    pub fn fill_merged(mut self, fill: bool) -> Self {
+        self.converter.parse.fill_merged = fill;
+        self
+    }
+
+    /// Convert the Excel Tables of sheets that have any instead of whole sheets
+    pub fn tables(mut self, tables: bool) -> Self {
+        self.converter.parse.tables = tables;
+        self.converter.options.tables = tables;
+        self
+    }
+
+    /// Run `hook` on every converted row, after filters and before `limit`,
+    /// to change its values in place, skip it or split it into several rows;
+    /// the statements of [`SqlGenerator::statements`] run it too
+    pub fn row_hook(
+        mut self,
+        hook: impl FnMut(&mut Vec<SqlValue>, &[String]) -> RowAction + Send + 'static,
+    ) -> Self {
+        self.converter.options.row_hook = Some(RowHook::new(hook));
+        self
+    }
+
+    /// Any other setting, on the options the command line flags map to
+    pub fn configure(mut self, configure: impl FnOnce(&mut GeneratorOptions)) -> Self {
+        configure(&mut self.converter.options);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CSV: &[u8] = b"Orders export\nOrder ID,Item\n1,Pen\n2,Ink\n3,Pad\n";
+
+    #[test]
+    fn test_builder() {
+        let converter = Converter::builder()
+            .dialect(Dialect::Postgres)
+            .header_row(2)
+            .batch_size(2)
+            .snake_case(true)
+            .table_name("orders")
+            .build();
+        assert_eq!(converter.dialect(), Dialect::Postgres);
+        assert_eq!(
+            converter.convert_bytes("export.csv", CSV).unwrap(),
+            concat!(
+                "INSERT INTO \"orders\" (\"order_id\", \"item\") VALUES\n(1,'Pen'),\n(2,'Ink');\n",
+                "\n",
+                "INSERT INTO \"orders\" (\"order_id\", \"item\") VALUES\n(3,'Pad');\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_configure() {
+        let converter = Converter::builder()
+            .header_row(2)
+            .configure(|options| options.max_rows = Some(1))
+            .build();
+        let workbook = crate::parse_bytes("export.csv", CSV, ParseOptions::default()).unwrap();
+        let statements = converter.statements(&workbook).unwrap();
+        assert_eq!(statements[0].values.len(), 1);
+        assert_eq!(statements[0].table_name, "export");
+    }
+
+    #[test]
+    fn test_row_hook() {
+        let converter = Converter::builder()
+            .header_row(2)
+            .row_hook(|row, columns| {
+                let item = columns.iter().position(|c| c == "Item").unwrap();
+                match &mut row[item] {
+                    SqlValue::Text(text) if text == "Ink" => RowAction::Skip,
+                    SqlValue::Text(text) => {
+                        text.make_ascii_uppercase();
+                        RowAction::Keep
+                    }
+                    _ => RowAction::Keep,
+                }
+            })
+            .build();
+        assert_eq!(
+            converter.convert_bytes("export.csv", CSV).unwrap(),
+            "INSERT INTO `export` (`Order ID`, `Item`) VALUES\n(1,'PEN'),\n(3,'PAD');\n"
+        );
+    }
+
+    #[test]
+    fn test_row_hook_streaming() {
+        // Each row becomes two, and `limit` counts the split rows
+        let converter = Converter::builder()
+            .header_row(2)
+            .limit(3)
+            .row_hook(|row, _| RowAction::Split(vec![row.clone(), row.clone()]))
+            .build();
+        let workbook = crate::parse_bytes("export.csv", CSV, ParseOptions::default()).unwrap();
+        let generator = converter.generator();
+        let statements: Vec<SqlStatement> = generator
+            .statements(&workbook)
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(statements[0].values.len(), 3);
+        assert_eq!(statements[0].stats.limited_rows, 3);
+    }
+}
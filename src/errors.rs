@@ -16,8 +16,52 @@ pub enum Xlsx2SqlError {
 
     #[error("Output error: {0}")]
     Output(#[from] OutputError),
+
+    #[error("{failed} of {total} file(s) failed to convert")]
+    Batch { failed: usize, total: usize },
+
+    #[error("{0} problem(s) skipped with --keep-going")]
+    Skipped(usize),
+
+    #[error("{0} finding(s); the workbook breaks its rules")]
+    Invalid(usize),
 }
 
+impl Xlsx2SqlError {
+    /// Process exit status for this failure, so scripts can tell its class apart:
+    /// 3 input not found, 4 unreadable input, 5 no data, 6 output failed,
+    /// 7 some files of a batch failed, 8 --keep-going skipped rows or sheets,
+    /// 9 validate found rule violations,
+    /// 1 anything else (2 is a usage error)
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            Xlsx2SqlError::Input(InputError::FileNotFound(_)) => 3,
+            Xlsx2SqlError::Input(InputError::Io(e)) if e.kind() == std::io::ErrorKind::NotFound => {
+                3
+            }
+            Xlsx2SqlError::Input(InputError::InvalidFormat) => 4,
+            Xlsx2SqlError::Parse(e) | Xlsx2SqlError::Generator(GeneratorError::Parse(e)) => match e
+            {
+                ParseError::EmptySheet | ParseError::MissingHeaders => 5,
+                _ => 4,
+            },
+            Xlsx2SqlError::Calamine(_) => 4,
+            Xlsx2SqlError::Generator(GeneratorError::NoData) => 5,
+            Xlsx2SqlError::Output(_) => 6,
+            Xlsx2SqlError::Batch { .. } => 7,
+            Xlsx2SqlError::Skipped(_) => 8,
+            Xlsx2SqlError::Invalid(_) => 9,
+            _ => 1,
+        }
+    }
+}
+
+/// An input or option that needs a Cargo feature this build was compiled
+/// without; the command line checks for these before converting
+#[derive(Debug, Error)]
+#[error("This build has no {0} support; rebuild with `--features {0}`")]
+pub struct FeatureDisabled(pub &'static str);
+
 #[derive(Debug, Error)]
 pub enum InputError {
     #[error("File not found: {0}")]
@@ -26,6 +70,34 @@ pub enum InputError {
     #[error("Invalid file format")]
     InvalidFormat,
 
+    #[error("Session file error: {0}")]
+    Session(String),
+
+    #[error("Config file error: {0}")]
+    Config(String),
+
+    #[error("{0}; prompts are off (--no-interactive, or not run in a terminal)")]
+    NotInteractive(String),
+
+    #[error("Invalid file pattern {0}")]
+    Pattern(String),
+
+    #[error("Invalid archive {0}")]
+    Archive(String),
+
+    #[error("{first} and {second} would both be written to {output}")]
+    OutputCollision {
+        output: String,
+        first: String,
+        second: String,
+    },
+
+    #[error("Download failed: {0}")]
+    Download(String),
+
+    #[error("S3 error: {0}")]
+    S3(#[from] S3Error),
+
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 }
@@ -43,6 +115,12 @@ pub enum ParseError {
 
     #[error("Calamine error: {0}")]
     CalamineError(#[from] calamine::Error),
+
+    #[error("CSV error: {0}")]
+    Csv(#[from] csv::Error),
+
+    #[error("File is not valid {0}")]
+    Encoding(String),
 }
 
 #[derive(Debug, Error)]
@@ -50,8 +128,75 @@ pub enum GeneratorError {
     #[error("No data to generate SQL from")]
     NoData,
 
+    #[error("Sheet(s) not found: {0}")]
+    UnknownSheets(String),
+
+    #[error("Named range(s) not found: {0}")]
+    UnknownNamedRanges(String),
+
+    #[error("Named range {name} does not refer to a single cell range: {reference}")]
+    InvalidNamedRange { name: String, reference: String },
+
     #[error("Parse error: {0}")]
     Parse(#[from] ParseError),
+
+    #[error("Cell {cell} is {size} bytes, exceeding the limit of {limit}")]
+    OversizedCell {
+        cell: String,
+        size: usize,
+        limit: usize,
+    },
+
+    #[error("{count} cell(s) contain Excel errors: {cells}")]
+    CellErrors { count: usize, cells: String },
+
+    #[error("Sheet {sheet} has no tenant column '{column}'")]
+    MissingTenantColumn { sheet: String, column: String },
+
+    #[error("{count} row(s) do not belong to tenant {expected}: {cells}")]
+    TenantMismatch {
+        expected: String,
+        count: usize,
+        cells: String,
+    },
+
+    #[error("Sheet {sheet} has columns ({columns}) but {first} has ({expected}); --union-sheets needs identical headers")]
+    UnionColumns {
+        sheet: String,
+        columns: String,
+        first: String,
+        expected: String,
+    },
+
+    #[error("Sheet {sheet} uses {dialect} reserved word(s) as names: {names}; rename them with --rename or pass --reserved-words quote or rename")]
+    ReservedWords {
+        sheet: String,
+        dialect: &'static str,
+        names: String,
+    },
+
+    #[error("Sheet {sheet} has duplicate column names: {columns}; rename one with --rename '{column}:{column}=NAME' or pass --duplicate-headers suffix")]
+    DuplicateColumns {
+        sheet: String,
+        columns: String,
+        /// Letters of the second column sharing the first repeated name
+        column: String,
+    },
+
+    #[error("Filter column '{column}' not found in sheet {sheet}")]
+    FilterColumn { sheet: String, column: String },
+
+    #[error(
+        "Row hook left a row of table {table} with {found} value(s) for its {expected} column(s)"
+    )]
+    RowHookColumns {
+        table: String,
+        expected: usize,
+        found: usize,
+    },
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
 }
 
 #[derive(Debug, Error)]
@@ -59,6 +204,80 @@ pub enum OutputError {
     #[error("Failed to write output: {0}")]
     WriteError(String),
 
+    #[error("S3 error: {0}")]
+    S3(#[from] S3Error),
+
+    #[error("Table {table} has no key column {column}")]
+    KeyColumn { table: String, column: String },
+
+    #[error("Table {table} has key {key} on more than one row")]
+    DuplicateKey { table: String, key: String },
+
+    #[error("Database error: {0}")]
+    Database(#[from] DatabaseError),
+
+    #[error(transparent)]
+    FeatureDisabled(#[from] FeatureDisabled),
+
+    #[error("Template error: {0}")]
+    Template(String),
+
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 }
+
+#[derive(Debug, Error)]
+#[cfg_attr(
+    not(any(feature = "mysql", feature = "postgres", feature = "sqlite")),
+    allow(dead_code)
+)]
+pub enum DatabaseError {
+    #[error(transparent)]
+    FeatureDisabled(#[from] FeatureDisabled),
+
+    #[error("Could not connect: {0}")]
+    Connect(String),
+
+    #[error("Inserting into {table} failed; nothing was committed: {reason}")]
+    Statement { table: String, reason: String },
+}
+
+#[derive(Debug, Error)]
+pub enum S3Error {
+    #[error("AWS_ACCESS_KEY_ID and AWS_SECRET_ACCESS_KEY must be set")]
+    Credentials,
+
+    #[error("{0}")]
+    Request(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exit_code() {
+        let not_found = std::io::Error::from(std::io::ErrorKind::NotFound);
+        for (error, code) in [
+            (InputError::FileNotFound("a.xlsx".to_string()).into(), 3),
+            (InputError::Io(not_found).into(), 3),
+            (InputError::InvalidFormat.into(), 4),
+            (ParseError::InvalidFormat.into(), 4),
+            (GeneratorError::Parse(ParseError::MissingHeaders).into(), 5),
+            (GeneratorError::NoData.into(), 5),
+            (OutputError::WriteError("disk full".to_string()).into(), 6),
+            (
+                Xlsx2SqlError::Batch {
+                    failed: 1,
+                    total: 3,
+                },
+                7,
+            ),
+            (Xlsx2SqlError::Skipped(2), 8),
+            (Xlsx2SqlError::Invalid(3), 9),
+            (InputError::Config("bad".to_string()).into(), 1),
+        ] {
+            assert_eq!(error.exit_code(), code, "{error}");
+        }
+    }
+}
@@ -0,0 +1,101 @@
+// Selection of the columns to convert (--columns, --skip-columns)
+use crate::parser::data_model::column_index;
+
+/// One `--columns` or `--skip-columns` item
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ColumnSpec {
+    /// Worksheet columns `A:F`, as zero-based indices (inclusive)
+    Range(usize, usize),
+    /// A header, matched case-insensitively
+    Name(String),
+}
+
+impl ColumnSpec {
+    fn matches(&self, col: usize, header: &str) -> bool {
+        match self {
+            ColumnSpec::Range(start, end) => (*start..=*end).contains(&col),
+            ColumnSpec::Name(name) => name.trim().eq_ignore_ascii_case(header.trim()),
+        }
+    }
+}
+
+/// Columns to convert: those inside any of the ranges or whose header is
+/// listed in `specs` (every column when empty), less those matching `skip`
+#[derive(Debug, Clone, Default)]
+pub struct ColumnFilter {
+    pub specs: Vec<ColumnSpec>,
+    pub skip: Vec<ColumnSpec>,
+}
+
+impl ColumnFilter {
+    /// Whether the worksheet column `col` (zero-based) with `header` is kept
+    pub fn keeps(&self, col: usize, header: &str) -> bool {
+        (self.specs.is_empty() || self.specs.iter().any(|spec| spec.matches(col, header)))
+            && !self.skip.iter().any(|spec| spec.matches(col, header))
+    }
+}
+
+/// Parse a `--columns` or `--skip-columns` item: a letter range such as `A:F` (`C:C` for a
+/// single column) or a header name
+pub fn parse_column_spec(arg: &str) -> Result<ColumnSpec, String> {
+    let arg = arg.trim();
+    if arg.is_empty() {
+        return Err("empty column".to_string());
+    }
+    let range = arg
+        .split_once(':')
+        .and_then(|(start, end)| Some((column_index(start.trim())?, column_index(end.trim())?)));
+    Ok(match range {
+        Some((start, end)) if start <= end => ColumnSpec::Range(start, end),
+        Some((start, end)) => ColumnSpec::Range(end, start),
+        None => ColumnSpec::Name(arg.to_string()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_column_spec() {
+        assert_eq!(parse_column_spec("A:F"), Ok(ColumnSpec::Range(0, 5)));
+        assert_eq!(parse_column_spec("c:c"), Ok(ColumnSpec::Range(2, 2)));
+        assert_eq!(parse_column_spec("F:A"), Ok(ColumnSpec::Range(0, 5)));
+        assert_eq!(
+            parse_column_spec(" Order ID "),
+            Ok(ColumnSpec::Name("Order ID".to_string()))
+        );
+        assert_eq!(
+            parse_column_spec("Time: start"),
+            Ok(ColumnSpec::Name("Time: start".to_string()))
+        );
+        assert!(parse_column_spec("").is_err());
+    }
+
+    #[test]
+    fn test_keeps() {
+        let filter = ColumnFilter {
+            specs: vec![
+                ColumnSpec::Range(0, 1),
+                ColumnSpec::Name("total".to_string()),
+            ],
+            ..ColumnFilter::default()
+        };
+        assert!(filter.keeps(1, "name"));
+        assert!(filter.keeps(4, "Total"));
+        assert!(!filter.keeps(2, "scratch"));
+        assert!(ColumnFilter::default().keeps(30, "anything"));
+
+        let filter = ColumnFilter {
+            specs: vec![ColumnSpec::Range(0, 3)],
+            skip: vec![
+                ColumnSpec::Name("Notes".to_string()),
+                ColumnSpec::Range(3, 3),
+            ],
+        };
+        assert!(filter.keeps(0, "id"));
+        assert!(!filter.keeps(1, "notes"));
+        assert!(!filter.keeps(3, "internal_flag"));
+        assert!(!filter.keeps(5, "other"));
+    }
+}
@@ -0,0 +1,467 @@
+// Per-column value types declared with --column-type
+use crate::generator::locale::{parse_localized_number, NumberLocale};
+use crate::parser::data_model::SqlValue;
+use base64::Engine;
+use serde::{Deserialize, Deserializer};
+use std::fmt;
+use std::str::FromStr;
+
+const CURRENCY_SYMBOLS: &[char] = &['$', '€', '£', '¥', '￥', '₩', '₹', '₽', '₺', '₫', '฿', '¢'];
+const CURRENCY_CODES: &[&str] = &[
+    "USD", "EUR", "GBP", "JPY", "CNY", "KRW", "INR", "CHF", "CAD", "AUD",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColumnType {
+    /// Monetary amounts such as "$1,299.00" or "¥10,000", emitted as numbers
+    Currency,
+    /// Percentages (0.15 or "15%"), see `PercentScale`
+    Percent(PercentScale),
+    /// Booleans, also recognizing text such as "yes"/"no" or "true"/"false"
+    Boolean,
+    /// Japanese era dates such as "令和6年1月5日", emitted as ISO dates
+    EraDate,
+    /// JSON documents, emitted as JSON literals
+    Json,
+    /// Delimited lists such as "red,green,blue" split into arrays
+    Array { separator: String },
+    /// Hex or base64 encoded bytes, emitted as binary literals
+    Binary(BinaryEncoding),
+    /// Codes such as postal codes emitted as text; numeric cells are
+    /// zero-padded to `width` digits ("123" -> "00123" with `text(5)`)
+    Text { width: Option<usize> },
+    /// UUIDs, validated and normalized per `--uuid-format`
+    Uuid,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BinaryEncoding {
+    /// `binary(hex)`: "DEADBEEF", optionally prefixed with 0x
+    Hex,
+    /// `binary(base64)`: "3q2+7w=="
+    Base64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PercentScale {
+    /// `percent(fraction)`: 0.15
+    Fraction,
+    /// `percent` / `percent(N)`: 15, or 15.00 with N = 2 decimal places
+    Points { decimals: Option<usize> },
+}
+
+impl FromStr for ColumnType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (name, arg) = match s.split_once('(') {
+            Some((name, rest)) => {
+                let arg = rest
+                    .strip_suffix(')')
+                    .ok_or_else(|| format!("missing ')' in column type '{s}'"))?;
+                (name.trim(), Some(arg))
+            }
+            None => (s, None),
+        };
+
+        match (name.to_ascii_lowercase().as_str(), arg) {
+            ("currency", None) => Ok(ColumnType::Currency),
+            ("boolean" | "bool", None) => Ok(ColumnType::Boolean),
+            ("era-date" | "wareki", None) => Ok(ColumnType::EraDate),
+            ("json", None) => Ok(ColumnType::Json),
+            ("uuid", None) => Ok(ColumnType::Uuid),
+            ("text", None) => Ok(ColumnType::Text { width: None }),
+            ("text", Some(arg)) => arg
+                .trim()
+                .parse()
+                .map(|width| ColumnType::Text { width: Some(width) })
+                .map_err(|_| format!("invalid text width '{arg}'")),
+            ("array", None) => Ok(ColumnType::Array {
+                separator: ",".to_string(),
+            }),
+            ("array", Some(separator)) if !separator.is_empty() => Ok(ColumnType::Array {
+                separator: separator.to_string(),
+            }),
+            ("binary", Some(arg)) => match arg.trim().to_ascii_lowercase().as_str() {
+                "hex" => Ok(ColumnType::Binary(BinaryEncoding::Hex)),
+                "base64" => Ok(ColumnType::Binary(BinaryEncoding::Base64)),
+                _ => Err(format!("invalid binary encoding '{arg}'")),
+            },
+            ("percent", None) => Ok(ColumnType::Percent(PercentScale::Points { decimals: None })),
+            ("percent", Some(arg)) if arg.trim() == "fraction" => {
+                Ok(ColumnType::Percent(PercentScale::Fraction))
+            }
+            ("percent", Some(arg)) => arg
+                .trim()
+                .parse()
+                .map(|decimals| {
+                    ColumnType::Percent(PercentScale::Points {
+                        decimals: Some(decimals),
+                    })
+                })
+                .map_err(|_| format!("invalid percent scale '{arg}'")),
+            _ => Err(format!("unknown column type '{s}'")),
+        }
+    }
+}
+
+/// Spelled the way `--column-type` parses it
+impl fmt::Display for ColumnType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ColumnType::Currency => write!(f, "currency"),
+            ColumnType::Percent(PercentScale::Fraction) => write!(f, "percent(fraction)"),
+            ColumnType::Percent(PercentScale::Points { decimals: None }) => write!(f, "percent"),
+            ColumnType::Percent(PercentScale::Points {
+                decimals: Some(decimals),
+            }) => write!(f, "percent({decimals})"),
+            ColumnType::Boolean => write!(f, "boolean"),
+            ColumnType::EraDate => write!(f, "era-date"),
+            ColumnType::Json => write!(f, "json"),
+            ColumnType::Array { separator } => write!(f, "array({separator})"),
+            ColumnType::Binary(BinaryEncoding::Hex) => write!(f, "binary(hex)"),
+            ColumnType::Binary(BinaryEncoding::Base64) => write!(f, "binary(base64)"),
+            ColumnType::Text { width: None } => write!(f, "text"),
+            ColumnType::Text { width: Some(width) } => write!(f, "text({width})"),
+            ColumnType::Uuid => write!(f, "uuid"),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ColumnType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/// Parse a `COLUMN=TYPE` argument
+pub fn parse_column_type_arg(arg: &str) -> Result<(String, ColumnType), String> {
+    let (column, column_type) = arg
+        .rsplit_once('=')
+        .ok_or_else(|| format!("expected COLUMN=TYPE, got '{arg}'"))?;
+    Ok((column.to_string(), column_type.parse()?))
+}
+
+/// Parse a `--text-columns` entry, `COLUMN` or `COLUMN=WIDTH`
+pub fn parse_text_column(arg: &str) -> Result<(String, ColumnType), String> {
+    match arg.rsplit_once('=') {
+        Some((column, width)) => width
+            .trim()
+            .parse()
+            .map(|width| {
+                (
+                    column.trim().to_string(),
+                    ColumnType::Text { width: Some(width) },
+                )
+            })
+            .map_err(|_| format!("invalid text width in '{arg}'")),
+        None => Ok((arg.trim().to_string(), ColumnType::Text { width: None })),
+    }
+}
+
+/// Render a cell as text, restoring leading zeros Excel dropped from numeric codes
+pub fn format_text(value: &SqlValue, width: Option<usize>) -> Option<String> {
+    let text = match value {
+        SqlValue::Text(text) => text.clone(),
+        SqlValue::Integer(i) => i.to_string(),
+        SqlValue::Number(f) if f.fract() == 0.0 && f.abs() < i64::MAX as f64 => {
+            (*f as i64).to_string()
+        }
+        SqlValue::Number(f) => f.to_string(),
+        SqlValue::Decimal(d) => d.clone(),
+        _ => return None,
+    };
+    Some(match width {
+        Some(width) if !text.is_empty() && text.chars().all(|c| c.is_ascii_digit()) => {
+            format!("{text:0>width$}")
+        }
+        _ => text,
+    })
+}
+
+/// Strip currency symbols, codes and thousands separators and parse the
+/// remainder; accounting-style "(1,000)" is read as negative.
+pub fn parse_currency(text: &str, locale: Option<NumberLocale>) -> Option<SqlValue> {
+    let mut amount = text.trim().to_string();
+    let mut negative = false;
+    if amount.starts_with('(') && amount.ends_with(')') {
+        negative = true;
+        amount = amount[1..amount.len() - 1].to_string();
+    }
+    for code in CURRENCY_CODES {
+        amount = amount.replace(code, "");
+    }
+    amount.retain(|c| !CURRENCY_SYMBOLS.contains(&c));
+    let amount = amount.trim();
+    let (negative, amount) = match amount.strip_prefix('-') {
+        Some(rest) => (!negative, rest.trim()),
+        None => (negative, amount),
+    };
+
+    let value = parse_localized_number(amount, locale.unwrap_or(NumberLocale::En))?;
+    Some(match (value, negative) {
+        (SqlValue::Integer(i), true) => SqlValue::Integer(-i),
+        (SqlValue::Number(f), true) => SqlValue::Number(-f),
+        (value, _) => value,
+    })
+}
+
+/// Scale a percentage held as a fraction (0.15) or as text in points
+/// ("15%", or "15" as pasted over a percentage cell).
+pub fn scale_percent(value: &SqlValue, scale: PercentScale) -> Option<SqlValue> {
+    let fraction = match value {
+        SqlValue::Number(f) => *f,
+        SqlValue::Integer(i) => *i as f64,
+        SqlValue::Text(text) => {
+            let text = text.trim();
+            let points = text.strip_suffix('%').unwrap_or(text);
+            points.trim().parse::<f64>().ok()? / 100.0
+        }
+        _ => return None,
+    };
+
+    Some(match scale {
+        PercentScale::Fraction => SqlValue::Number(fraction),
+        PercentScale::Points { decimals: None } => {
+            // Round away binary noise such as 0.07 * 100 = 7.000000000000001
+            let points = (fraction * 100.0 * 1e9).round() / 1e9;
+            if points.fract() == 0.0 && points.abs() < i64::MAX as f64 {
+                SqlValue::Integer(points as i64)
+            } else {
+                SqlValue::Number(points)
+            }
+        }
+        PercentScale::Points {
+            decimals: Some(decimals),
+        } => SqlValue::Decimal(format!("{:.*}", decimals, fraction * 100.0)),
+    })
+}
+
+/// Decode hex or base64 text; whitespace between digits is ignored
+pub fn decode_binary(text: &str, encoding: BinaryEncoding) -> Option<Vec<u8>> {
+    let compact: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+    match encoding {
+        BinaryEncoding::Hex => {
+            let digits = compact
+                .strip_prefix("0x")
+                .or_else(|| compact.strip_prefix("0X"))
+                .or_else(|| compact.strip_prefix("\\x"))
+                .unwrap_or(&compact);
+            if !digits.len().is_multiple_of(2) || !digits.is_ascii() {
+                return None;
+            }
+            (0..digits.len())
+                .step_by(2)
+                .map(|i| u8::from_str_radix(&digits[i..i + 2], 16).ok())
+                .collect()
+        }
+        BinaryEncoding::Base64 => base64::engine::general_purpose::STANDARD
+            .decode(compact)
+            .ok(),
+    }
+}
+
+/// Interpret a cell as a boolean: booleans, 1/0, and common yes/no words
+pub fn parse_boolean(value: &SqlValue) -> Option<bool> {
+    match value {
+        SqlValue::Boolean(b) => Some(*b),
+        SqlValue::Integer(1) => Some(true),
+        SqlValue::Integer(0) => Some(false),
+        SqlValue::Number(f) if *f == 1.0 => Some(true),
+        SqlValue::Number(f) if *f == 0.0 => Some(false),
+        SqlValue::Text(text) => match text.trim().to_lowercase().as_str() {
+            "true" | "t" | "yes" | "y" | "on" | "1" | "はい" => Some(true),
+            "false" | "f" | "no" | "n" | "off" | "0" | "いいえ" => Some(false),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_column_type_arg() {
+        assert_eq!(
+            parse_column_type_arg("Unit Price=currency"),
+            Ok(("Unit Price".to_string(), ColumnType::Currency))
+        );
+        assert_eq!(
+            "percent(2)".parse(),
+            Ok(ColumnType::Percent(PercentScale::Points {
+                decimals: Some(2)
+            }))
+        );
+        assert_eq!(
+            "percent(fraction)".parse(),
+            Ok(ColumnType::Percent(PercentScale::Fraction))
+        );
+        assert!("percent(x)".parse::<ColumnType>().is_err());
+        assert_eq!(
+            "array(;)".parse(),
+            Ok(ColumnType::Array {
+                separator: ";".to_string()
+            })
+        );
+        assert!("array()".parse::<ColumnType>().is_err());
+        assert_eq!(
+            "binary(base64)".parse(),
+            Ok(ColumnType::Binary(BinaryEncoding::Base64))
+        );
+        assert!("binary".parse::<ColumnType>().is_err());
+        assert_eq!("UUID".parse(), Ok(ColumnType::Uuid));
+        assert!("binary(utf8)".parse::<ColumnType>().is_err());
+        assert!(parse_column_type_arg("price").is_err());
+        assert!(parse_column_type_arg("price=banana").is_err());
+        for spelling in [
+            "percent(2)",
+            "array(;)",
+            "binary(hex)",
+            "text(5)",
+            "era-date",
+        ] {
+            assert_eq!(
+                spelling.parse::<ColumnType>().unwrap().to_string(),
+                spelling
+            );
+        }
+    }
+
+    #[test]
+    fn test_text_columns() {
+        assert_eq!(
+            parse_text_column("zip=5"),
+            Ok(("zip".to_string(), ColumnType::Text { width: Some(5) }))
+        );
+        assert_eq!(
+            parse_text_column("sku"),
+            Ok(("sku".to_string(), ColumnType::Text { width: None }))
+        );
+        assert!(parse_text_column("zip=five").is_err());
+        assert_eq!(
+            format_text(&SqlValue::Number(123.0), Some(5)),
+            Some("00123".to_string())
+        );
+        assert_eq!(
+            format_text(&SqlValue::Text("AB-1".to_string()), Some(5)),
+            Some("AB-1".to_string())
+        );
+        assert_eq!(
+            format_text(&SqlValue::Integer(42), None),
+            Some("42".to_string())
+        );
+        assert_eq!(format_text(&SqlValue::Null, Some(5)), None);
+    }
+
+    #[test]
+    fn test_parse_currency() {
+        assert_eq!(
+            parse_currency("$1,299.00", None),
+            Some(SqlValue::Number(1299.0))
+        );
+        assert_eq!(
+            parse_currency("¥10,000", None),
+            Some(SqlValue::Integer(10000))
+        );
+        assert_eq!(parse_currency("-$5.50", None), Some(SqlValue::Number(-5.5)));
+        assert_eq!(
+            parse_currency("(1,000)", None),
+            Some(SqlValue::Integer(-1000))
+        );
+        assert_eq!(parse_currency("USD 12", None), Some(SqlValue::Integer(12)));
+        assert_eq!(
+            parse_currency("1.299,00 €", Some(NumberLocale::De)),
+            Some(SqlValue::Number(1299.0))
+        );
+        assert_eq!(parse_currency("free", None), None);
+    }
+
+    #[test]
+    fn test_decode_binary() {
+        let bytes = vec![0xde, 0xad, 0xbe, 0xef];
+        assert_eq!(
+            decode_binary("DEADBEEF", BinaryEncoding::Hex),
+            Some(bytes.clone())
+        );
+        assert_eq!(
+            decode_binary("0xde ad be ef", BinaryEncoding::Hex),
+            Some(bytes.clone())
+        );
+        assert_eq!(
+            decode_binary("3q2+7w==", BinaryEncoding::Base64),
+            Some(bytes)
+        );
+        assert_eq!(decode_binary("ABC", BinaryEncoding::Hex), None);
+        assert_eq!(decode_binary("zz", BinaryEncoding::Hex), None);
+        assert_eq!(decode_binary("not base64!", BinaryEncoding::Base64), None);
+    }
+
+    #[test]
+    fn test_parse_boolean() {
+        assert_eq!(
+            parse_boolean(&SqlValue::Text("Yes".to_string())),
+            Some(true)
+        );
+        assert_eq!(
+            parse_boolean(&SqlValue::Text(" false ".to_string())),
+            Some(false)
+        );
+        assert_eq!(parse_boolean(&SqlValue::Integer(0)), Some(false));
+        assert_eq!(parse_boolean(&SqlValue::Boolean(true)), Some(true));
+        assert_eq!(parse_boolean(&SqlValue::Text("maybe".to_string())), None);
+        assert_eq!(parse_boolean(&SqlValue::Integer(2)), None);
+    }
+
+    #[test]
+    fn test_scale_percent() {
+        let points = PercentScale::Points { decimals: None };
+        assert_eq!(
+            scale_percent(&SqlValue::Number(0.15), points),
+            Some(SqlValue::Integer(15))
+        );
+        assert_eq!(
+            scale_percent(&SqlValue::Number(0.07), points),
+            Some(SqlValue::Integer(7))
+        );
+        assert_eq!(
+            scale_percent(&SqlValue::Number(0.125), points),
+            Some(SqlValue::Number(12.5))
+        );
+        assert_eq!(
+            scale_percent(
+                &SqlValue::Number(0.15),
+                PercentScale::Points { decimals: Some(2) }
+            ),
+            Some(SqlValue::Decimal("15.00".to_string()))
+        );
+        assert_eq!(
+            scale_percent(&SqlValue::Text("15%".to_string()), PercentScale::Fraction),
+            Some(SqlValue::Number(0.15))
+        );
+        // Text typed without the sign is in points too
+        assert_eq!(
+            scale_percent(&SqlValue::Text("15".to_string()), points),
+            Some(SqlValue::Integer(15))
+        );
+        assert_eq!(
+            scale_percent(
+                &SqlValue::Text("15".to_string()),
+                PercentScale::Points { decimals: Some(2) }
+            ),
+            Some(SqlValue::Decimal("15.00".to_string()))
+        );
+        assert_eq!(
+            scale_percent(&SqlValue::Text("15".to_string()), PercentScale::Fraction),
+            Some(SqlValue::Number(0.15))
+        );
+        assert_eq!(
+            scale_percent(&SqlValue::Text("n/a".to_string()), points),
+            None
+        );
+    }
+}
@@ -0,0 +1,125 @@
+// SQL dialect specific syntax
+use crate::generator::formatter::SqlFormatter;
+use crate::generator::options::BoolFormat;
+use clap::ValueEnum;
+
+/// MySQL 8 reserved words (keywords marked (R) in the manual)
+const MYSQL_RESERVED: &str =
+    "accessible add all alter analyze and as asc asensitive before between bigint binary blob \
+    both by call cascade case change char character check collate column condition constraint \
+    continue convert create cross cube cume_dist current_date current_time current_timestamp \
+    current_user cursor database databases day_hour day_microsecond day_minute day_second dec \
+    decimal declare default delayed delete dense_rank desc describe deterministic distinct \
+    distinctrow div double drop dual each else elseif empty enclosed escaped except exists \
+    exit explain false fetch first_value float float4 float8 for force foreign from fulltext \
+    function generated get grant group grouping groups having high_priority hour_microsecond \
+    hour_minute hour_second if ignore in index infile inner inout insensitive insert int int1 \
+    int2 int3 int4 int8 integer intersect interval into io_after_gtids io_before_gtids is \
+    iterate join json_table key keys kill lag last_value lateral lead leading leave left like \
+    limit linear lines load localtime localtimestamp lock long longblob longtext loop \
+    low_priority master_bind master_ssl_verify_server_cert match maxvalue mediumblob mediumint \
+    mediumtext middleint minute_microsecond minute_second mod modifies natural \
+    no_write_to_binlog not nth_value ntile null numeric of on optimize optimizer_costs option \
+    optionally or order out outer outfile over partition percent_rank precision primary \
+    procedure purge range rank read read_write reads real recursive references regexp release \
+    rename repeat replace require resignal restrict return revoke right rlike row row_number \
+    rows schema schemas second_microsecond select sensitive separator set show signal smallint \
+    spatial specific sql sql_big_result sql_calc_found_rows sql_small_result sqlexception \
+    sqlstate sqlwarning ssl starting stored straight_join system table terminated then \
+    tinyblob tinyint tinytext to trailing trigger true undo union unique unlock unsigned \
+    update usage use using utc_date utc_time utc_timestamp values varbinary varchar \
+    varcharacter varying virtual when where while window with write xor year_month zerofill";
+
+/// Words PostgreSQL lists as reserved, including those allowed as function or
+/// type names
+const POSTGRES_RESERVED: &str =
+    "all analyse analyze and any array as asc asymmetric authorization binary both case cast \
+    check collate collation column concurrently constraint create cross current_catalog \
+    current_date current_role current_schema current_time current_timestamp current_user \
+    default deferrable desc distinct do else end except false fetch for foreign freeze from \
+    full grant group having ilike in initially inner intersect into is isnull join lateral \
+    leading left like limit localtime localtimestamp natural not notnull null offset on only \
+    or order outer overlaps placing primary references returning right select session_user \
+    similar some symmetric system_user table tablesample then to trailing true union unique \
+    user using variadic verbose when where window with";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum Dialect {
+    #[default]
+    Mysql,
+    Postgres,
+}
+
+impl Dialect {
+    pub fn quote_identifier(self, name: &str) -> String {
+        match self {
+            Dialect::Mysql => SqlFormatter::format_identifier(name),
+            Dialect::Postgres => SqlFormatter::format_quoted_identifier(name),
+        }
+    }
+
+    /// `table`, qualified by `schema` when there is one
+    pub fn quote_table(self, schema: Option<&str>, table: &str) -> String {
+        match schema {
+            Some(schema) => format!(
+                "{}.{}",
+                self.quote_identifier(schema),
+                self.quote_identifier(table)
+            ),
+            None => self.quote_identifier(table),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Dialect::Mysql => "MySQL",
+            Dialect::Postgres => "PostgreSQL",
+        }
+    }
+
+    /// Whether `name` is a reserved word, usable as an identifier only when quoted
+    pub fn is_reserved(self, name: &str) -> bool {
+        let words = match self {
+            Dialect::Mysql => MYSQL_RESERVED,
+            Dialect::Postgres => POSTGRES_RESERVED,
+        };
+        let name = name.to_lowercase();
+        words.split_whitespace().any(|word| word == name)
+    }
+
+    /// Boolean literal style used when --bool-format is not given
+    pub fn default_bool_format(self) -> BoolFormat {
+        match self {
+            Dialect::Mysql => BoolFormat::Numeric,
+            Dialect::Postgres => BoolFormat::Keyword,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quote_identifier() {
+        assert_eq!(Dialect::Mysql.quote_identifier("order"), "`order`");
+        assert_eq!(Dialect::Postgres.quote_identifier("order"), "\"order\"");
+        assert_eq!(Dialect::Postgres.quote_identifier("a\"b"), "\"a\"\"b\"");
+        assert_eq!(
+            Dialect::Postgres.quote_table(Some("app"), "orders"),
+            "\"app\".\"orders\""
+        );
+        assert_eq!(Dialect::Mysql.quote_table(None, "orders"), "`orders`");
+    }
+
+    #[test]
+    fn test_is_reserved() {
+        assert!(Dialect::Mysql.is_reserved("Order"));
+        assert!(Dialect::Postgres.is_reserved("GROUP"));
+        assert!(Dialect::Mysql.is_reserved("rank"));
+        assert!(!Dialect::Postgres.is_reserved("rank"));
+        assert!(Dialect::Postgres.is_reserved("user"));
+        assert!(!Dialect::Mysql.is_reserved("user"));
+        assert!(!Dialect::Mysql.is_reserved("order_id"));
+    }
+}
@@ -0,0 +1,108 @@
+// ISO 8601 durations ("PT10H30M", "P1DT2H") and Excel [h]:mm:ss cells
+use chrono::Duration;
+
+/// Parse an ISO 8601 duration with day, hour, minute and (fractional) second
+/// parts; years, months and weeks have no fixed length and are rejected.
+pub fn parse_iso_duration(text: &str) -> Option<Duration> {
+    let text = text.trim();
+    let (negative, text) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text),
+    };
+    let body = text.strip_prefix('P')?;
+    let (date_part, time_part) = match body.split_once('T') {
+        Some((date, time)) => (date, Some(time)),
+        None => (body, None),
+    };
+    if body.is_empty() || time_part == Some("") {
+        return None;
+    }
+
+    let mut millis = 0f64;
+    let mut number = String::new();
+    for c in date_part.chars() {
+        match c {
+            '0'..='9' | '.' => number.push(c),
+            'D' => millis += take_number(&mut number)? * 86_400_000.0,
+            _ => return None,
+        }
+    }
+    if !number.is_empty() {
+        return None;
+    }
+    for c in time_part.unwrap_or_default().chars() {
+        match c {
+            '0'..='9' | '.' => number.push(c),
+            'H' => millis += take_number(&mut number)? * 3_600_000.0,
+            'M' => millis += take_number(&mut number)? * 60_000.0,
+            'S' => millis += take_number(&mut number)? * 1_000.0,
+            _ => return None,
+        }
+    }
+    if !number.is_empty() {
+        return None;
+    }
+
+    let duration = Duration::milliseconds(millis.round() as i64);
+    Some(if negative { -duration } else { duration })
+}
+
+fn take_number(number: &mut String) -> Option<f64> {
+    let value = number.parse().ok();
+    number.clear();
+    value
+}
+
+/// Render as `[-]H:MM:SS[.fff]`; hours are not wrapped at 24
+pub fn format_duration(duration: &Duration) -> String {
+    let sign = if *duration < Duration::zero() {
+        "-"
+    } else {
+        ""
+    };
+    let total_millis = duration.num_milliseconds().abs();
+    let seconds = total_millis / 1000;
+    let millis = total_millis % 1000;
+    let mut text = format!(
+        "{sign}{}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    );
+    if millis != 0 {
+        text.push_str(&format!(".{millis:03}"));
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_iso_duration() {
+        assert_eq!(
+            parse_iso_duration("PT10H30M15S"),
+            Some(Duration::seconds(10 * 3600 + 30 * 60 + 15))
+        );
+        assert_eq!(parse_iso_duration("P1DT2H"), Some(Duration::hours(26)));
+        assert_eq!(
+            parse_iso_duration("PT1.5S"),
+            Some(Duration::milliseconds(1500))
+        );
+        assert_eq!(parse_iso_duration("-PT5M"), Some(Duration::minutes(-5)));
+        assert_eq!(parse_iso_duration("P1M"), None);
+        assert_eq!(parse_iso_duration("PT"), None);
+        assert_eq!(parse_iso_duration("10:30"), None);
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(&Duration::hours(26)), "26:00:00");
+        assert_eq!(
+            format_duration(&Duration::milliseconds(61_250)),
+            "0:01:01.250"
+        );
+        assert_eq!(format_duration(&Duration::minutes(-5)), "-0:05:00");
+    }
+}
@@ -0,0 +1,90 @@
+// Japanese era (wareki) date parsing, e.g. "令和6年1月5日" or "R6.1.5"
+use chrono::NaiveDate;
+
+/// (kanji name, romaji initial, first Gregorian year, first day of the era)
+const ERAS: &[(&str, char, i32, (u32, u32))] = &[
+    ("令和", 'R', 2019, (5, 1)),
+    ("平成", 'H', 1989, (1, 8)),
+    ("昭和", 'S', 1926, (12, 25)),
+    ("大正", 'T', 1912, (7, 30)),
+    ("明治", 'M', 1868, (1, 25)),
+];
+
+/// Parse an era date into a Gregorian date, rejecting dates before the era began
+pub fn parse_era_date(text: &str) -> Option<NaiveDate> {
+    // Normalize full-width digits and punctuation
+    let text: String = text
+        .trim()
+        .chars()
+        .map(|c| match c {
+            '０'..='９' => char::from_u32(c as u32 - '０' as u32 + '0' as u32).unwrap(),
+            '．' => '.',
+            '／' => '/',
+            '－' => '-',
+            c => c,
+        })
+        .collect();
+
+    let (base_year, era_start, rest) = ERAS.iter().find_map(|(name, initial, year, start)| {
+        let rest = text
+            .strip_prefix(name)
+            .or_else(|| text.strip_prefix(*initial))
+            .or_else(|| text.strip_prefix(initial.to_ascii_lowercase()))?;
+        Some((*year, *start, rest))
+    })?;
+
+    let rest = rest.trim_start().replace("元", "1");
+    let parts: Vec<&str> = rest
+        .split(['年', '月', '日', '.', '/', '-'])
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .collect();
+    let [era_year, month, day] = parts.as_slice() else {
+        return None;
+    };
+
+    let era_year: i32 = era_year.parse().ok()?;
+    if era_year < 1 {
+        return None;
+    }
+    let date = NaiveDate::from_ymd_opt(
+        base_year + era_year - 1,
+        month.parse().ok()?,
+        day.parse().ok()?,
+    )?;
+    let start = NaiveDate::from_ymd_opt(base_year, era_start.0, era_start.1)?;
+    (date >= start).then_some(date)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ymd(y: i32, m: u32, d: u32) -> Option<NaiveDate> {
+        NaiveDate::from_ymd_opt(y, m, d)
+    }
+
+    #[test]
+    fn test_kanji_era_dates() {
+        assert_eq!(parse_era_date("令和6年1月5日"), ymd(2024, 1, 5));
+        assert_eq!(parse_era_date("平成31年4月30日"), ymd(2019, 4, 30));
+        assert_eq!(parse_era_date("昭和64年1月7日"), ymd(1989, 1, 7));
+        assert_eq!(parse_era_date("令和元年5月1日"), ymd(2019, 5, 1));
+        assert_eq!(parse_era_date("令和６年１２月３１日"), ymd(2024, 12, 31));
+    }
+
+    #[test]
+    fn test_abbreviated_era_dates() {
+        assert_eq!(parse_era_date("R6.1.5"), ymd(2024, 1, 5));
+        assert_eq!(parse_era_date("H1/1/8"), ymd(1989, 1, 8));
+        assert_eq!(parse_era_date("s50-10-1"), ymd(1975, 10, 1));
+    }
+
+    #[test]
+    fn test_invalid_era_dates() {
+        assert_eq!(parse_era_date("令和元年4月30日"), None);
+        assert_eq!(parse_era_date("令和6年2月30日"), None);
+        assert_eq!(parse_era_date("2024年1月5日"), None);
+        assert_eq!(parse_era_date("令和6年"), None);
+    }
+}
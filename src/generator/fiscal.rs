@@ -0,0 +1,169 @@
+// Fiscal period columns derived from a date column (--derive-fiscal)
+use crate::parser::data_model::SqlValue;
+use chrono::{Datelike, NaiveDate};
+use std::str::FromStr;
+
+const MONTHS: [&str; 12] = [
+    "january",
+    "february",
+    "march",
+    "april",
+    "may",
+    "june",
+    "july",
+    "august",
+    "september",
+    "october",
+    "november",
+    "december",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FiscalDerivation {
+    pub source: String,
+    pub year_column: String,
+    pub quarter_column: Option<String>,
+    /// First month of the fiscal year, 1-12
+    pub start_month: u32,
+    /// Name fiscal years after the calendar year they end in instead of the one they start in
+    pub year_from_end: bool,
+}
+
+impl FromStr for FiscalDerivation {
+    type Err = String;
+
+    /// `SOURCE->YEAR_COL[,QUARTER_COL] [start=MONTH] [year=start|end]`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split_whitespace();
+        let mapping = parts.next().ok_or("empty fiscal derivation")?;
+        let (source, targets) = mapping
+            .split_once("->")
+            .ok_or_else(|| format!("expected SOURCE->YEAR[,QUARTER], got '{mapping}'"))?;
+        let mut targets = targets.split(',').map(str::trim).filter(|t| !t.is_empty());
+        let year_column = targets.next().ok_or("missing fiscal year column")?;
+        let quarter_column = targets.next().map(str::to_string);
+        if targets.next().is_some() {
+            return Err("at most a year and a quarter column can be derived".to_string());
+        }
+
+        let mut derivation = FiscalDerivation {
+            source: source.trim().to_string(),
+            year_column: year_column.to_string(),
+            quarter_column,
+            start_month: 1,
+            year_from_end: false,
+        };
+        for option in parts {
+            match option.split_once('=') {
+                Some(("start", month)) => derivation.start_month = parse_month(month)?,
+                Some(("year", "start")) => derivation.year_from_end = false,
+                Some(("year", "end")) => derivation.year_from_end = true,
+                _ => return Err(format!("unknown fiscal option '{option}'")),
+            }
+        }
+        Ok(derivation)
+    }
+}
+
+fn parse_month(month: &str) -> Result<u32, String> {
+    if let Ok(number) = month.parse::<u32>() {
+        if (1..=12).contains(&number) {
+            return Ok(number);
+        }
+    }
+    let lower = month.to_lowercase();
+    MONTHS
+        .iter()
+        .position(|name| lower.len() >= 3 && name.starts_with(&lower))
+        .map(|index| index as u32 + 1)
+        .ok_or_else(|| format!("invalid month '{month}'"))
+}
+
+impl FiscalDerivation {
+    pub fn columns(&self) -> Vec<String> {
+        std::iter::once(self.year_column.clone())
+            .chain(self.quarter_column.clone())
+            .collect()
+    }
+
+    /// Fiscal year and quarter values for a date cell; NULLs when it is not a date
+    pub fn derive(&self, value: &SqlValue) -> Vec<SqlValue> {
+        let date = match value {
+            SqlValue::DateTime(text) | SqlValue::Text(text) => text
+                .get(..10)
+                .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok()),
+            _ => None,
+        };
+        let Some(date) = date else {
+            return vec![SqlValue::Null; self.columns().len()];
+        };
+
+        let months_in = (date.month() + 12 - self.start_month) % 12;
+        let mut year = if date.month() >= self.start_month {
+            date.year()
+        } else {
+            date.year() - 1
+        };
+        if self.year_from_end && self.start_month != 1 {
+            year += 1;
+        }
+
+        let mut values = vec![SqlValue::Integer(year as i64)];
+        if self.quarter_column.is_some() {
+            values.push(SqlValue::Integer((months_in / 3 + 1) as i64));
+        }
+        values
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn april() -> FiscalDerivation {
+        "order_date->fiscal_year,fiscal_quarter start=April"
+            .parse()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_parse_derivation() {
+        let derivation = april();
+        assert_eq!(derivation.source, "order_date");
+        assert_eq!(derivation.columns(), ["fiscal_year", "fiscal_quarter"]);
+        assert_eq!(derivation.start_month, 4);
+        assert!("order_date".parse::<FiscalDerivation>().is_err());
+        assert!("a->b start=Smarch".parse::<FiscalDerivation>().is_err());
+    }
+
+    #[test]
+    fn test_derive_april_start() {
+        let derivation = april();
+        let date = |d: &str| SqlValue::DateTime(format!("{d} 00:00:00"));
+        assert_eq!(
+            derivation.derive(&date("2024-04-01")),
+            [SqlValue::Integer(2024), SqlValue::Integer(1)]
+        );
+        assert_eq!(
+            derivation.derive(&date("2025-03-31")),
+            [SqlValue::Integer(2024), SqlValue::Integer(4)]
+        );
+        assert_eq!(
+            derivation.derive(&date("2024-12-15")),
+            [SqlValue::Integer(2024), SqlValue::Integer(3)]
+        );
+        assert_eq!(
+            derivation.derive(&SqlValue::Null),
+            [SqlValue::Null, SqlValue::Null]
+        );
+    }
+
+    #[test]
+    fn test_year_named_after_end() {
+        let derivation: FiscalDerivation = "d->fy start=10 year=end".parse().unwrap();
+        assert_eq!(
+            derivation.derive(&SqlValue::Text("2024-10-01".to_string())),
+            [SqlValue::Integer(2025)]
+        );
+    }
+}
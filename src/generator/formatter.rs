@@ -1,12 +1,145 @@
 // SQL formatting utilities for future enhancements
+use crate::generator::options::{KeywordCase, SqlStyle, StatementLayout};
 
 pub struct SqlFormatter;
 
+/// Handling of rows whose key already exists, in a dialect's syntax;
+/// identifiers come quoted
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Conflict<'a> {
+    #[default]
+    Fail,
+    /// `INSERT IGNORE INTO` (MySQL)
+    MysqlIgnore,
+    /// `ON DUPLICATE KEY UPDATE` of every column but `key` (MySQL)
+    MysqlUpdate { key: &'a str },
+    /// `ON CONFLICT DO NOTHING` (PostgreSQL)
+    PostgresIgnore,
+    /// `ON CONFLICT (key) DO UPDATE` of every other column (PostgreSQL)
+    PostgresUpdate { key: &'a str },
+}
+
 impl SqlFormatter {
+    /// `INSERT INTO <table> (<columns>) VALUES <tuples>;` laid out by
+    /// `layout`, plus the clause `conflict` calls for; identifiers and
+    /// values come formatted
+    pub fn format_insert(
+        table: &str,
+        columns: &[String],
+        rows: &[Vec<String>],
+        layout: &StatementLayout,
+        conflict: Conflict,
+    ) -> String {
+        let keyword = |keyword: &str| match layout.keyword_case {
+            KeywordCase::Upper => keyword.to_string(),
+            KeywordCase::Lower => keyword.to_lowercase(),
+        };
+        let insert = match conflict {
+            Conflict::MysqlIgnore => keyword("INSERT IGNORE INTO"),
+            _ => keyword("INSERT INTO"),
+        };
+        let conflict_key = match conflict {
+            Conflict::MysqlUpdate { key } | Conflict::PostgresUpdate { key } => Some(key),
+            _ => None,
+        };
+        let others = || {
+            columns
+                .iter()
+                .filter(move |column| Some(column.as_str()) != conflict_key)
+        };
+        let clause = match conflict {
+            Conflict::Fail | Conflict::MysqlIgnore => None,
+            Conflict::MysqlUpdate { key } => {
+                let mut updates: Vec<String> = others()
+                    .map(|column| format!("{column} = {}({column})", keyword("VALUES")))
+                    .collect();
+                if updates.is_empty() {
+                    updates.push(format!("{key} = {key}"));
+                }
+                Some(format!(
+                    "{} {}",
+                    keyword("ON DUPLICATE KEY UPDATE"),
+                    updates.join(", ")
+                ))
+            }
+            Conflict::PostgresIgnore => Some(keyword("ON CONFLICT DO NOTHING")),
+            Conflict::PostgresUpdate { key } => {
+                let updates: Vec<String> = others()
+                    .map(|column| format!("{column} = {}.{column}", keyword("EXCLUDED")))
+                    .collect();
+                Some(match updates.is_empty() {
+                    true => format!(
+                        "{} ({key}) {}",
+                        keyword("ON CONFLICT"),
+                        keyword("DO NOTHING")
+                    ),
+                    false => format!(
+                        "{} ({key}) {} {}",
+                        keyword("ON CONFLICT"),
+                        keyword("DO UPDATE SET"),
+                        updates.join(", ")
+                    ),
+                })
+            }
+        };
+        let values = keyword("VALUES");
+        let separator = match layout.style {
+            SqlStyle::Compact | SqlStyle::Default => ",",
+            SqlStyle::Pretty => ", ",
+        };
+        let tuples: Vec<String> = rows
+            .iter()
+            .map(|row| format!("({})", row.join(separator)))
+            .collect();
+        if layout.style == SqlStyle::Compact {
+            let clause = clause
+                .map(|clause| format!(" {clause}"))
+                .unwrap_or_default();
+            return format!(
+                "{insert} {table} ({}) {values} {}{clause};",
+                columns.join(","),
+                tuples.join(",")
+            );
+        }
+        let indent = " ".repeat(layout.indent.unwrap_or(match layout.style {
+            SqlStyle::Pretty => 2,
+            _ => 0,
+        }));
+        let mut lines: Vec<String> = Vec::new();
+        for tuple in tuples {
+            match (lines.last_mut(), layout.wrap) {
+                (Some(line), Some(width))
+                    if line.chars().count() + separator.len() + tuple.chars().count() <= width =>
+                {
+                    line.push_str(separator);
+                    line.push_str(&tuple);
+                }
+                _ => lines.push(format!("{indent}{tuple}")),
+            }
+        }
+        let values_break = match layout.style {
+            SqlStyle::Pretty => "\n",
+            _ => " ",
+        };
+        let clause = clause
+            .map(|clause| format!("\n{clause}"))
+            .unwrap_or_default();
+        format!(
+            "{insert} {table} ({}){values_break}{values}\n{}{clause};",
+            columns.join(", "),
+            lines.join(",\n")
+        )
+    }
+
     pub fn format_identifier(name: &str) -> String {
         format!("`{name}`")
     }
 
+    /// ANSI double-quoted identifier, as used by PostgreSQL
+    pub fn format_quoted_identifier(name: &str) -> String {
+        format!("\"{}\"", name.replace('"', "\"\""))
+    }
+
     pub fn escape_string(s: &str) -> String {
         s.replace("'", "''")
     }
@@ -14,6 +147,39 @@ impl SqlFormatter {
     pub fn format_string_literal(s: &str) -> String {
         format!("'{}'", Self::escape_string(s))
     }
+
+    /// PostgreSQL array constructor: ARRAY['a','b']; an empty array is
+    /// written '{}', as ARRAY[] has no type PostgreSQL could infer
+    pub fn format_array_constructor(items: &[String]) -> String {
+        if items.is_empty() {
+            return "'{}'".to_string();
+        }
+        let items: Vec<String> = items
+            .iter()
+            .map(|i| Self::format_string_literal(i))
+            .collect();
+        format!("ARRAY[{}]", items.join(","))
+    }
+
+    /// PostgreSQL array literal: '{a,"b c"}', quoting elements where required
+    pub fn format_array_literal(items: &[String]) -> String {
+        let items: Vec<String> = items
+            .iter()
+            .map(|item| {
+                let needs_quotes = item.is_empty()
+                    || item.eq_ignore_ascii_case("null")
+                    || item
+                        .chars()
+                        .any(|c| matches!(c, ',' | '{' | '}' | '"' | '\\') || c.is_whitespace());
+                if needs_quotes {
+                    format!("\"{}\"", item.replace('\\', "\\\\").replace('"', "\\\""))
+                } else {
+                    item.clone()
+                }
+            })
+            .collect();
+        Self::format_string_literal(&format!("{{{}}}", items.join(",")))
+    }
 }
 
 #[cfg(test)]
@@ -36,6 +202,103 @@ mod tests {
         assert_eq!(SqlFormatter::escape_string("'quoted'"), "''quoted''");
     }
 
+    #[test]
+    fn test_format_arrays() {
+        let items = vec!["red".to_string(), "it's".to_string(), "a b".to_string()];
+        assert_eq!(
+            SqlFormatter::format_array_constructor(&items),
+            "ARRAY['red','it''s','a b']"
+        );
+        assert_eq!(SqlFormatter::format_array_constructor(&[]), "'{}'");
+        assert_eq!(
+            SqlFormatter::format_array_literal(&items),
+            "'{red,it''s,\"a b\"}'"
+        );
+        assert_eq!(
+            SqlFormatter::format_array_literal(&["x\"y".to_string()]),
+            "'{\"x\\\"y\"}'"
+        );
+    }
+
+    #[test]
+    fn test_format_insert() {
+        let columns = vec!["id".to_string(), "name".to_string()];
+        let rows: Vec<Vec<String>> = (1..=3)
+            .map(|i| vec![i.to_string(), format!("'n{i}'")])
+            .collect();
+        let insert = |layout: StatementLayout| {
+            SqlFormatter::format_insert("t", &columns, &rows, &layout, Conflict::Fail)
+        };
+        assert_eq!(
+            insert(StatementLayout::default()),
+            "INSERT INTO t (id, name) VALUES\n(1,'n1'),\n(2,'n2'),\n(3,'n3');"
+        );
+        assert_eq!(
+            insert(StatementLayout {
+                style: SqlStyle::Compact,
+                keyword_case: KeywordCase::Lower,
+                ..StatementLayout::default()
+            }),
+            "insert into t (id,name) values (1,'n1'),(2,'n2'),(3,'n3');"
+        );
+        assert_eq!(
+            insert(StatementLayout {
+                style: SqlStyle::Pretty,
+                ..StatementLayout::default()
+            }),
+            "INSERT INTO t (id, name)\nVALUES\n  (1, 'n1'),\n  (2, 'n2'),\n  (3, 'n3');"
+        );
+        assert_eq!(
+            insert(StatementLayout {
+                style: SqlStyle::Pretty,
+                indent: Some(4),
+                wrap: Some(30),
+                ..StatementLayout::default()
+            }),
+            "INSERT INTO t (id, name)\nVALUES\n    (1, 'n1'), (2, 'n2'),\n    (3, 'n3');"
+        );
+    }
+
+    #[test]
+    fn test_format_insert_conflict() {
+        let columns = vec!["id".to_string(), "name".to_string()];
+        let rows = vec![vec!["1".to_string(), "'a'".to_string()]];
+        let insert = |conflict| {
+            SqlFormatter::format_insert("t", &columns, &rows, &StatementLayout::default(), conflict)
+        };
+        assert_eq!(
+            insert(Conflict::MysqlIgnore),
+            "INSERT IGNORE INTO t (id, name) VALUES\n(1,'a');"
+        );
+        assert_eq!(
+            insert(Conflict::MysqlUpdate { key: "id" }),
+            "INSERT INTO t (id, name) VALUES\n(1,'a')\nON DUPLICATE KEY UPDATE name = VALUES(name);"
+        );
+        assert_eq!(
+            insert(Conflict::PostgresIgnore),
+            "INSERT INTO t (id, name) VALUES\n(1,'a')\nON CONFLICT DO NOTHING;"
+        );
+        assert_eq!(
+            insert(Conflict::PostgresUpdate { key: "id" }),
+            "INSERT INTO t (id, name) VALUES\n(1,'a')\nON CONFLICT (id) DO UPDATE SET name = EXCLUDED.name;"
+        );
+        let compact = StatementLayout {
+            style: SqlStyle::Compact,
+            keyword_case: KeywordCase::Lower,
+            ..StatementLayout::default()
+        };
+        assert_eq!(
+            SqlFormatter::format_insert(
+                "t",
+                &columns[..1],
+                &[vec!["1".to_string()]],
+                &compact,
+                Conflict::PostgresUpdate { key: "id" }
+            ),
+            "insert into t (id) values (1) on conflict (id) do nothing;"
+        );
+    }
+
     #[test]
     fn test_format_string_literal() {
         assert_eq!(SqlFormatter::format_string_literal("test"), "'test'");
@@ -0,0 +1,127 @@
+// Locale-aware parsing of numbers stored as text
+use crate::parser::data_model::SqlValue;
+use clap::ValueEnum;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum NumberLocale {
+    /// 1,234.56
+    En,
+    /// 1.234,56 (German, Spanish, Italian, Dutch, ...)
+    De,
+    /// 1 234,56 (French, Nordic; regular or non-breaking spaces)
+    Fr,
+    /// 1'234.56 (Swiss)
+    Ch,
+}
+
+impl NumberLocale {
+    fn decimal_separator(self) -> char {
+        match self {
+            NumberLocale::En | NumberLocale::Ch => '.',
+            NumberLocale::De | NumberLocale::Fr => ',',
+        }
+    }
+
+    fn is_group_separator(self, c: char) -> bool {
+        match self {
+            NumberLocale::En => c == ',',
+            NumberLocale::De => c == '.',
+            NumberLocale::Fr => matches!(c, ' ' | '\u{a0}' | '\u{202f}'),
+            NumberLocale::Ch => matches!(c, '\'' | '\u{2019}'),
+        }
+    }
+}
+
+/// Parse `text` as a number written in `locale`, returning `None` unless the
+/// whole string is a well-formed number (groups of three digits, one decimal separator).
+pub fn parse_localized_number(text: &str, locale: NumberLocale) -> Option<SqlValue> {
+    let text = text.trim();
+    let (negative, unsigned) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text.strip_prefix('+').unwrap_or(text)),
+    };
+
+    let (integer_part, fraction_part) = match unsigned.split_once(locale.decimal_separator()) {
+        Some((integer, fraction)) => (integer, Some(fraction)),
+        None => (unsigned, None),
+    };
+
+    let groups: Vec<&str> = integer_part
+        .split(|c| locale.is_group_separator(c))
+        .collect();
+    let first = groups.first()?;
+    let valid_groups = if groups.len() == 1 {
+        !first.is_empty() && first.chars().all(|c| c.is_ascii_digit())
+    } else {
+        (1..=3).contains(&first.len())
+            && groups.iter().all(|g| g.chars().all(|c| c.is_ascii_digit()))
+            && groups[1..].iter().all(|g| g.len() == 3)
+    };
+    if !valid_groups {
+        return None;
+    }
+    if let Some(fraction) = fraction_part {
+        if fraction.is_empty() || !fraction.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+    }
+
+    let digits: String = groups.concat();
+    let sign = if negative { "-" } else { "" };
+    match fraction_part {
+        None => match format!("{sign}{digits}").parse::<i64>() {
+            Ok(i) => Some(SqlValue::Integer(i)),
+            Err(_) => format!("{sign}{digits}").parse().ok().map(SqlValue::Number),
+        },
+        Some(fraction) => format!("{sign}{digits}.{fraction}")
+            .parse()
+            .ok()
+            .map(SqlValue::Number),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_german_numbers() {
+        assert_eq!(
+            parse_localized_number("1.234,56", NumberLocale::De),
+            Some(SqlValue::Number(1234.56))
+        );
+        assert_eq!(
+            parse_localized_number("-1.234.567", NumberLocale::De),
+            Some(SqlValue::Integer(-1234567))
+        );
+        assert_eq!(
+            parse_localized_number("0,5", NumberLocale::De),
+            Some(SqlValue::Number(0.5))
+        );
+    }
+
+    #[test]
+    fn test_parse_other_locales() {
+        assert_eq!(
+            parse_localized_number("1,234.5", NumberLocale::En),
+            Some(SqlValue::Number(1234.5))
+        );
+        assert_eq!(
+            parse_localized_number("1\u{a0}234,5", NumberLocale::Fr),
+            Some(SqlValue::Number(1234.5))
+        );
+        assert_eq!(
+            parse_localized_number("1'234", NumberLocale::Ch),
+            Some(SqlValue::Integer(1234))
+        );
+    }
+
+    #[test]
+    fn test_rejects_malformed_numbers() {
+        assert_eq!(parse_localized_number("1.23,4", NumberLocale::En), None);
+        assert_eq!(parse_localized_number("12.34.5", NumberLocale::De), None);
+        assert_eq!(parse_localized_number("1.234,", NumberLocale::De), None);
+        assert_eq!(parse_localized_number("abc", NumberLocale::De), None);
+        assert_eq!(parse_localized_number("", NumberLocale::De), None);
+    }
+}
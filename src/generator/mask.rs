@@ -0,0 +1,101 @@
+// Masking of sensitive columns before their values are written (--mask)
+use crate::parser::data_model::SqlValue;
+use clap::ValueEnum;
+use sha2::{Digest, Sha256};
+
+/// What a masked column's values become; NULL stays NULL
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum MaskMethod {
+    /// The text '***'
+    #[default]
+    Redact,
+    /// The SHA-256 of the value in hex, so equal values stay equal
+    Hash,
+    /// NULL
+    Null,
+}
+
+/// One `--mask` item
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnMask {
+    /// A header or target column name, matched case-insensitively
+    pub column: String,
+    pub method: MaskMethod,
+}
+
+impl ColumnMask {
+    pub fn applies_to(&self, header: &str, column: &str) -> bool {
+        let name = self.column.trim();
+        header.trim().eq_ignore_ascii_case(name) || column.eq_ignore_ascii_case(name)
+    }
+}
+
+impl MaskMethod {
+    pub fn apply(self, value: &SqlValue) -> SqlValue {
+        let text = match value {
+            SqlValue::Null => return SqlValue::Null,
+            SqlValue::Text(s)
+            | SqlValue::DateTime(s)
+            | SqlValue::Json(s)
+            | SqlValue::Uuid(s)
+            | SqlValue::Decimal(s) => s.clone(),
+            other => other.to_string(),
+        };
+        match self {
+            MaskMethod::Redact => SqlValue::Text("***".to_string()),
+            MaskMethod::Hash => SqlValue::Text(
+                Sha256::digest(text.as_bytes())
+                    .iter()
+                    .map(|byte| format!("{byte:02x}"))
+                    .collect(),
+            ),
+            MaskMethod::Null => SqlValue::Null,
+        }
+    }
+}
+
+/// Parse a `--mask` item: `COLUMN`, redacted, or `COLUMN=METHOD`
+pub fn parse_mask(arg: &str) -> Result<ColumnMask, String> {
+    let (column, method) = match arg.rsplit_once('=') {
+        Some((column, method)) => (column, MaskMethod::from_str(method.trim(), true)?),
+        None => (arg, MaskMethod::Redact),
+    };
+    match column.trim() {
+        "" => Err(format!("no column in '{arg}'")),
+        column => Ok(ColumnMask {
+            column: column.to_string(),
+            method,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_mask() {
+        let mask = parse_mask("E-mail=hash").unwrap();
+        assert_eq!(mask.method, MaskMethod::Hash);
+        assert!(mask.applies_to(" e-mail ", "email"));
+        assert_eq!(parse_mask("phone").unwrap().method, MaskMethod::Redact);
+        assert!(parse_mask("phone=shuffle").is_err());
+        assert!(parse_mask("=null").is_err());
+    }
+
+    #[test]
+    fn test_apply() {
+        let email = SqlValue::Text("a@example.com".to_string());
+        assert_eq!(
+            MaskMethod::Redact.apply(&email),
+            SqlValue::Text("***".to_string())
+        );
+        let SqlValue::Text(hash) = MaskMethod::Hash.apply(&email) else {
+            panic!("hash is text");
+        };
+        assert_eq!(hash.len(), 64);
+        assert_eq!(MaskMethod::Hash.apply(&email), SqlValue::Text(hash));
+        assert_eq!(MaskMethod::Null.apply(&email), SqlValue::Null);
+        assert_eq!(MaskMethod::Redact.apply(&SqlValue::Null), SqlValue::Null);
+    }
+}
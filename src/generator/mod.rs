@@ -1,4 +1,25 @@
+pub mod column_filter;
+pub mod column_type;
+pub mod dialect;
+pub mod duration;
+pub mod era_date;
+pub mod fiscal;
 pub mod formatter;
+pub mod locale;
+pub mod mask;
+pub mod naming;
+pub mod options;
+pub mod row_filter;
+pub mod row_hook;
+pub mod sheet_filter;
 pub mod sql_generator;
+pub mod template;
+pub mod uuid;
+pub mod value_converter;
 
+pub use column_type::ColumnType;
+pub use dialect::Dialect;
+pub use locale::NumberLocale;
+pub use options::*;
+pub use row_hook::{RowAction, RowHook};
 pub use sql_generator::*;
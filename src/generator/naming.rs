@@ -0,0 +1,333 @@
+// Table and column names derived from workbook and header text
+use crate::generator::column_filter::{parse_column_spec, ColumnSpec};
+use clap::ValueEnum;
+
+/// Names spreadsheet programs give new sheets: "Sheet1", "Sheet 2", and their
+/// German, French, Spanish, Italian, Portuguese, Dutch and Russian versions
+pub fn is_default_sheet_name(name: &str) -> bool {
+    const STEMS: [&str; 8] = [
+        "sheet", "tabelle", "feuil", "hoja", "foglio", "planilha", "blad", "лист",
+    ];
+    let name = name.trim().to_lowercase();
+    STEMS.iter().any(|stem| {
+        name.strip_prefix(stem)
+            .is_some_and(|rest| rest.trim_start().chars().all(|c| c.is_ascii_digit()))
+    })
+}
+
+/// Table name for a file stem: lowercase, with every run of spaces and
+/// punctuation turned into a single underscore ("Customer List (2024)" ->
+/// "customer_list_2024")
+pub fn table_name_from_file(stem: &str) -> String {
+    let mut name = String::new();
+    for c in stem.chars() {
+        if c.is_alphanumeric() {
+            name.extend(c.to_lowercase());
+        } else if !name.is_empty() && !name.ends_with('_') {
+            name.push('_');
+        }
+    }
+    let name = name.trim_end_matches('_');
+    match name.is_empty() {
+        true => "data".to_string(),
+        false => name.to_string(),
+    }
+}
+
+/// How table and column names are rewritten before they are quoted
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IdentifierStyle {
+    /// Convert names to snake_case (--snake-case)
+    pub snake_case: bool,
+    pub case: IdentifierCase,
+    /// Keep only characters allowed in unquoted identifiers and make the
+    /// resulting names unique (--sanitize-identifiers)
+    pub sanitize: bool,
+    /// What becomes of names that are reserved words of the target dialect
+    pub reserved: ReservedWords,
+    /// What happens when two columns of a sheet end up with the same name
+    pub duplicates: DuplicateHeaders,
+}
+
+/// Handling of column names occurring twice in a sheet, which no INSERT accepts
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum DuplicateHeaders {
+    /// Number the later ones: amount, amount_2
+    #[default]
+    Suffix,
+    /// Abort the conversion, naming them and their columns
+    Error,
+}
+
+/// Handling of table and column names such as `order` or `group`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum ReservedWords {
+    /// Keep them; every identifier is quoted anyway
+    #[default]
+    Quote,
+    /// Append an underscore: order_
+    Rename,
+    /// Abort the conversion, naming them
+    Error,
+}
+
+/// Letter case of table and column names
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum IdentifierCase {
+    /// Keep names as written
+    #[default]
+    Preserve,
+    /// order_date
+    Lower,
+    /// ORDER_DATE, Oracle's default
+    Upper,
+}
+
+impl IdentifierStyle {
+    pub fn apply(&self, name: &str) -> String {
+        let name = match self.snake_case {
+            true => snake_case(name),
+            false => name.to_string(),
+        };
+        match self.case {
+            IdentifierCase::Preserve => name,
+            IdentifierCase::Lower => name.to_lowercase(),
+            IdentifierCase::Upper => name.to_uppercase(),
+        }
+    }
+}
+
+/// "Order Date" -> "order_date", "CustomerID" -> "customer_id",
+/// "HTTPStatus" -> "http_status"; a name without letters or digits is kept
+pub fn snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut out = String::new();
+    for (idx, &c) in chars.iter().enumerate() {
+        if !c.is_alphanumeric() {
+            if !out.is_empty() && !out.ends_with('_') {
+                out.push('_');
+            }
+            continue;
+        }
+        if c.is_uppercase() && idx > 0 && !out.is_empty() && !out.ends_with('_') {
+            let prev = chars[idx - 1];
+            // A new word starts after a lowercase letter or digit, and at the
+            // last capital of an acronym followed by a lowercase word
+            let acronym_end =
+                prev.is_uppercase() && chars.get(idx + 1).is_some_and(|next| next.is_lowercase());
+            if prev.is_lowercase() || prev.is_ascii_digit() || acronym_end {
+                out.push('_');
+            }
+        }
+        out.extend(c.to_lowercase());
+    }
+    match out.trim_end_matches('_') {
+        "" => name.to_string(),
+        snake => snake.to_string(),
+    }
+}
+
+/// `name` with each run of characters other than ASCII letters, digits and
+/// `_` turned into one underscore, dropped at either end, and `_` before a
+/// leading digit; `None` when nothing is left
+pub fn sanitize_identifier(name: &str) -> Option<String> {
+    let mut out = String::new();
+    let mut gap = false;
+    for c in name.chars() {
+        if !(c.is_ascii_alphanumeric() || c == '_') {
+            gap = true;
+            continue;
+        }
+        if gap && !out.is_empty() && !out.ends_with('_') && c != '_' {
+            out.push('_');
+        }
+        gap = false;
+        out.push(c);
+    }
+    match out.chars().next() {
+        None => None,
+        Some(c) if c.is_ascii_digit() => Some(format!("_{out}")),
+        Some(_) => Some(out),
+    }
+}
+
+/// Suffix repeated names (compared case-insensitively) with `_2`, `_3`, ...
+/// returning the positions of the renamed ones
+pub fn dedupe(names: &mut [String]) -> Vec<usize> {
+    let mut renamed = Vec::new();
+    for idx in 1..names.len() {
+        let taken =
+            |name: &str, others: &[String]| others.iter().any(|n| n.eq_ignore_ascii_case(name));
+        if !taken(&names[idx], &names[..idx]) {
+            continue;
+        }
+        // Suffixed names stay clear of the names after them too
+        let unique = (2..)
+            .map(|n| format!("{}_{n}", names[idx]))
+            .find(|name| !taken(name, &names[..idx]) && !taken(name, &names[idx + 1..]))
+            .expect("unbounded suffixes");
+        names[idx] = unique;
+        renamed.push(idx);
+    }
+    renamed
+}
+
+/// Parse a `--rename HEADER=COLUMN` argument
+pub fn parse_rename(arg: &str) -> Result<(String, String), String> {
+    let (header, column) = arg
+        .rsplit_once('=')
+        .ok_or_else(|| format!("expected HEADER=COLUMN, got '{arg}'"))?;
+    match (header.trim(), column.trim()) {
+        ("", _) => Err(format!("no header before '=' in '{arg}'")),
+        (_, "") => Err(format!("no column name after '=' in '{arg}'")),
+        (header, column) => Ok((header.to_string(), column.to_string())),
+    }
+}
+
+/// Positions of names occurring more than once (compared case-insensitively),
+/// grouped in order of their first occurrence
+pub fn duplicates(names: &[String]) -> Vec<Vec<usize>> {
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    for (idx, name) in names.iter().enumerate() {
+        match groups
+            .iter_mut()
+            .find(|group| names[group[0]].eq_ignore_ascii_case(name))
+        {
+            Some(group) => group.push(idx),
+            None => groups.push(vec![idx]),
+        }
+    }
+    groups.retain(|group| group.len() > 1);
+    groups
+}
+
+/// Column the header in worksheet column `col` (zero-based) is renamed to.
+/// Headers match case-insensitively; an entry keyed by the column's letters,
+/// `F:F`, wins and tells apart columns sharing a header
+pub fn renamed<'a>(renames: &'a [(String, String)], col: usize, header: &str) -> Option<&'a str> {
+    let header = header.trim();
+    renames
+        .iter()
+        .find(|(from, _)| parse_column_spec(from) == Ok(ColumnSpec::Range(col, col)))
+        .or_else(|| {
+            renames
+                .iter()
+                .find(|(from, _)| from.eq_ignore_ascii_case(header))
+        })
+        .map(|(_, to)| to.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_default_sheet_name() {
+        for name in ["Sheet1", "sheet 12", "Sheet", "Tabelle1", "Feuil2", "Лист1"] {
+            assert!(is_default_sheet_name(name), "{name}");
+        }
+        for name in ["Orders", "Sheet1 (2)", "Sheets", "2024"] {
+            assert!(!is_default_sheet_name(name), "{name}");
+        }
+    }
+
+    #[test]
+    fn test_table_name_from_file() {
+        assert_eq!(table_name_from_file("customers"), "customers");
+        assert_eq!(
+            table_name_from_file("Customer List (2024)"),
+            "customer_list_2024"
+        );
+        assert_eq!(
+            table_name_from_file(" -- New-Hires.final "),
+            "new_hires_final"
+        );
+        assert_eq!(table_name_from_file("顧客 一覧"), "顧客_一覧");
+        assert_eq!(table_name_from_file("!!!"), "data");
+    }
+
+    #[test]
+    fn test_snake_case() {
+        for (name, expected) in [
+            ("Order Date", "order_date"),
+            ("CustomerID", "customer_id"),
+            ("customerId", "customer_id"),
+            ("HTTPStatus", "http_status"),
+            ("E-mail", "e_mail"),
+            ("  Unit Price (USD) ", "unit_price_usd"),
+            ("address2", "address2"),
+            ("Line2Total", "line2_total"),
+            ("already_snake", "already_snake"),
+            ("__id", "id"),
+            ("X_ユーザーID?", "x_ユーザーid"),
+            ("???", "???"),
+        ] {
+            assert_eq!(snake_case(name), expected, "{name}");
+        }
+    }
+
+    #[test]
+    fn test_identifier_style() {
+        let mut style = IdentifierStyle::default();
+        assert_eq!(style.apply("Order Date"), "Order Date");
+        style.case = IdentifierCase::Upper;
+        assert_eq!(style.apply("Order Date"), "ORDER DATE");
+        style.snake_case = true;
+        assert_eq!(style.apply("CustomerID"), "CUSTOMER_ID");
+        style.case = IdentifierCase::Lower;
+        style.snake_case = false;
+        assert_eq!(style.apply("CustomerID"), "customerid");
+    }
+
+    #[test]
+    fn test_sanitize_identifier() {
+        for (name, expected) in [
+            ("Order Date", Some("Order_Date")),
+            ("E-mail", Some("E_mail")),
+            ("unit price ($)", Some("unit_price")),
+            ("_internal", Some("_internal")),
+            ("a__b", Some("a__b")),
+            ("2024 total", Some("_2024_total")),
+            ("X_ユーザーID?", Some("X_ID")),
+            ("顧客", None),
+        ] {
+            assert_eq!(sanitize_identifier(name).as_deref(), expected, "{name}");
+        }
+    }
+
+    #[test]
+    fn test_dedupe() {
+        let mut names: Vec<String> = ["amount", "Amount", "amount_2", "id", "amount"]
+            .map(String::from)
+            .to_vec();
+        assert_eq!(dedupe(&mut names), [1, 4]);
+        assert_eq!(names, ["amount", "Amount_3", "amount_2", "id", "amount_4"]);
+    }
+
+    #[test]
+    fn test_duplicates() {
+        let names: Vec<String> = ["amount", "id", "Amount", "note", "id", "amount"]
+            .map(String::from)
+            .to_vec();
+        assert_eq!(duplicates(&names), [vec![0, 2, 5], vec![1, 4]]);
+        assert!(duplicates(&names[..2]).is_empty());
+    }
+
+    #[test]
+    fn test_rename() {
+        let renames = vec![
+            parse_rename("Customer Name=customer_name").unwrap(),
+            parse_rename(" E-mail = email ").unwrap(),
+            parse_rename("a=b=c").unwrap(),
+            parse_rename("F:F=amount_tax").unwrap(),
+        ];
+        assert_eq!(renamed(&renames, 0, "customer name"), Some("customer_name"));
+        assert_eq!(renamed(&renames, 1, "E-MAIL "), Some("email"));
+        assert_eq!(renamed(&renames, 2, "a=b"), Some("c"));
+        assert_eq!(renamed(&renames, 3, "Phone"), None);
+        assert_eq!(renamed(&renames, 5, "E-mail"), Some("amount_tax"));
+        assert!(parse_rename("email").is_err());
+        assert!(parse_rename("=email").is_err());
+        assert!(parse_rename("E-mail=").is_err());
+    }
+}
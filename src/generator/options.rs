@@ -0,0 +1,279 @@
+// Options controlling how cell values are turned into SQL values
+use crate::generator::column_filter::ColumnFilter;
+use crate::generator::column_type::ColumnType;
+use crate::generator::fiscal::FiscalDerivation;
+use crate::generator::locale::NumberLocale;
+use crate::generator::mask::ColumnMask;
+use crate::generator::naming::IdentifierStyle;
+use crate::generator::row_filter::RowFilter;
+use crate::generator::row_hook::RowHook;
+use crate::generator::sheet_filter::SheetFilter;
+use clap::ValueEnum;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Default)]
+pub struct GeneratorOptions {
+    /// Cells whose text exceeds this many bytes are handled by `oversized_cell_policy`
+    pub max_cell_size: Option<usize>,
+    pub oversized_cell_policy: OversizedCellPolicy,
+    /// Directory receiving externalized cell contents
+    pub externalize_dir: Option<PathBuf>,
+    /// Convert for a preview: report where oversized cells would be
+    /// externalized without writing them
+    pub preview: bool,
+    /// Parse text cells such as "1.234,56" as numbers written in this locale
+    pub number_locale: Option<NumberLocale>,
+    /// Declared value types keyed by column header
+    pub column_types: HashMap<String, ColumnType>,
+    /// Boolean literal style; the dialect's default when unset
+    pub bool_format: Option<BoolFormat>,
+    /// Text cells equal to one of these tokens become NULL
+    pub null_values: Vec<String>,
+    pub empty_string: EmptyStringPolicy,
+    /// Fiscal period columns appended to sheets containing the source column
+    pub fiscal_derivations: Vec<FiscalDerivation>,
+    /// Strip leading/trailing whitespace (including non-breaking spaces) from text
+    pub trim: bool,
+    /// Replace runs of whitespace inside text with a single space
+    pub collapse_whitespace: bool,
+    /// Require every row to belong to a single tenant
+    pub tenant: Option<TenantCheck>,
+    /// PostgreSQL array syntax
+    pub array_style: ArrayStyle,
+    /// Target table names, column names and types for specific sheets
+    pub table_mappings: Vec<TableMapping>,
+    /// Treat UUID-shaped text in undeclared columns as UUIDs
+    pub detect_uuids: bool,
+    pub uuid_format: UuidFormat,
+    /// What happens to rows containing Excel error cells such as #N/A
+    pub on_cell_error: CellErrorPolicy,
+    /// Emit durations as text instead of TIME / INTERVAL literals
+    pub durations_as_text: bool,
+    /// Fractional second digits kept in datetimes (0-6)
+    pub datetime_precision: usize,
+    /// Sheets to convert
+    pub sheet_filter: SheetFilter,
+    /// Defined names converted instead of whole sheets
+    pub named_ranges: Vec<String>,
+    /// Convert the Excel Tables of sheets that have any instead of the whole sheet
+    pub tables: bool,
+    /// Worksheet rows above the header row
+    pub skip_rows: usize,
+    /// Data rows converted per sheet
+    pub max_rows: Option<usize>,
+    /// Rows such as totals at the bottom of each sheet
+    pub skip_footer: usize,
+    /// Ignore everything from the first fully blank data row on
+    pub stop_at_blank_row: bool,
+    /// Columns to convert
+    pub column_filter: ColumnFilter,
+    /// Target column names keyed by header in every sheet (matched
+    /// case-insensitively), taking precedence over template column maps
+    pub column_renames: Vec<(String, String)>,
+    /// Headers of columns whose empty cells take the value from the row above
+    pub fill_down: Vec<String>,
+    /// Columns whose values are masked after filtering and derivations
+    pub masks: Vec<ColumnMask>,
+    /// Only rows for which this expression holds are converted
+    pub row_filter: Option<RowFilter>,
+    /// Table every statement inserts into instead of the sheet name
+    pub table_name: Option<String>,
+    /// Prepended to every table name, e.g. `stg_`
+    pub table_prefix: String,
+    /// Appended to every table name, e.g. `_import`
+    pub table_suffix: String,
+    /// Rewriting of every table and column name, after renames
+    pub identifiers: IdentifierStyle,
+    /// Table for sheets with default names such as Sheet1, derived from the
+    /// workbook's file name
+    pub file_table_name: Option<String>,
+    /// Combine the rows of all converted sheets into one statement
+    pub union_sheets: bool,
+    /// Stop each sheet after this many INSERTed rows, counted after filtering
+    pub sample: Option<usize>,
+    /// Rows emitted per table, across all the sheets inserting into it
+    pub limit: Option<usize>,
+    /// Callback run on each sheet's converted rows, before `limit`
+    pub row_hook: Option<RowHook>,
+    pub layout: StatementLayout,
+    /// Schema qualifying every table name in INSERT statements
+    pub schema: Option<String>,
+    pub insert_mode: InsertMode,
+    /// Conflict target of upsert mode; `id` when unset
+    pub upsert_key: Option<String>,
+    /// Skip rows and sheets that would fail the conversion, collecting their
+    /// problems, instead of stopping at the first
+    pub keep_going: bool,
+}
+
+impl GeneratorOptions {
+    pub fn mapping_for(&self, sheet: &str) -> Option<&TableMapping> {
+        self.table_mappings.iter().find(|m| m.applies_to(sheet))
+    }
+}
+
+/// How one sheet maps onto its target table
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct TableMapping {
+    /// Sheet names this mapping applies to (matched case-insensitively)
+    #[serde(default)]
+    pub sheets: Vec<String>,
+    pub table: Option<String>,
+    /// Target column names keyed by sheet header (matched case-insensitively)
+    #[serde(default)]
+    pub columns: HashMap<String, String>,
+    /// Column types keyed by header or target column name
+    #[serde(default)]
+    pub types: HashMap<String, ColumnType>,
+    /// Headers of columns left out of the table (matched case-insensitively)
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    /// Worksheet row (1-based) holding the headers, instead of --skip-rows
+    pub header_row: Option<usize>,
+    /// Only rows for which this expression holds are converted, in addition to --filter
+    pub filter: Option<RowFilter>,
+}
+
+impl TableMapping {
+    pub fn applies_to(&self, sheet: &str) -> bool {
+        self.sheets
+            .iter()
+            .any(|s| s.eq_ignore_ascii_case(sheet.trim()))
+    }
+
+    pub fn excludes(&self, header: &str) -> bool {
+        self.exclude
+            .iter()
+            .any(|excluded| excluded.trim().eq_ignore_ascii_case(header.trim()))
+    }
+
+    pub fn column_name(&self, header: &str) -> String {
+        self.columns
+            .get(header)
+            .or_else(|| {
+                let header = header.trim();
+                self.columns
+                    .iter()
+                    .find(|(from, _)| from.eq_ignore_ascii_case(header))
+                    .map(|(_, to)| to)
+            })
+            .cloned()
+            .unwrap_or_else(|| header.to_string())
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum ArrayStyle {
+    /// ARRAY['red','green']
+    #[default]
+    Constructor,
+    /// '{red,green}'
+    Literal,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum CellErrorPolicy {
+    /// Emit the error cell as NULL
+    #[default]
+    Null,
+    /// Leave out every row containing an error cell
+    SkipRow,
+    /// Abort the conversion
+    Fail,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum UuidFormat {
+    /// Keep UUIDs as written
+    #[default]
+    Preserve,
+    /// Lowercase 8-4-4-4-12 form
+    Lower,
+    /// Uppercase 8-4-4-4-12 form
+    Upper,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TenantCheck {
+    pub column: String,
+    pub expected: String,
+}
+
+/// What a text cell containing `""` becomes; truly empty cells are always NULL
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum EmptyStringPolicy {
+    /// NULL, like an empty cell
+    Null,
+    /// An empty string literal ''
+    #[default]
+    Empty,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum BoolFormat {
+    /// 1 / 0
+    #[default]
+    Numeric,
+    /// TRUE / FALSE
+    Keyword,
+    /// 'Y' / 'N'
+    Yn,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum SqlStyle {
+    /// Column list on the INSERT line, then one tuple per line
+    #[default]
+    Default,
+    /// The whole statement on one line, without spaces after commas
+    Compact,
+    /// VALUES on a line of its own and indented tuples with spaces after commas
+    Pretty,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum KeywordCase {
+    /// INSERT INTO ... VALUES
+    #[default]
+    Upper,
+    /// insert into ... values
+    Lower,
+}
+
+/// What INSERT statements do with rows whose key already exists
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum InsertMode {
+    /// Plain INSERT; a duplicate key fails the statement
+    #[default]
+    Insert,
+    /// Skip such rows (INSERT IGNORE / ON CONFLICT DO NOTHING)
+    Ignore,
+    /// Overwrite the existing row's other columns (ON DUPLICATE KEY UPDATE /
+    /// ON CONFLICT DO UPDATE)
+    Upsert,
+}
+
+/// Layout of INSERT statements
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StatementLayout {
+    pub style: SqlStyle,
+    /// Spaces before each line of tuples; 2 for pretty, else 0 when unset
+    pub indent: Option<usize>,
+    pub keyword_case: KeywordCase,
+    /// Pack tuples onto lines of up to this many characters instead of one
+    /// per line
+    pub wrap: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OversizedCellPolicy {
+    /// Keep the first bytes of the cell and append a marker
+    #[default]
+    Truncate,
+    /// Write the cell contents to a separate file and emit its path instead
+    Externalize,
+    /// Abort the conversion
+    Fail,
+}
@@ -0,0 +1,447 @@
+// Row filter expressions (--filter 'status != "deleted" && amount > 0')
+use crate::parser::data_model::SqlValue;
+use serde::{Deserialize, Deserializer};
+use std::cmp::Ordering;
+
+/// A parsed `--filter` expression; rows for which it is false are left out
+#[derive(Debug, Clone, PartialEq)]
+pub struct RowFilter {
+    expr: Expr<String>,
+}
+
+/// Filters in templates are written as expression strings
+impl<'de> Deserialize<'de> for RowFilter {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let source = String::deserialize(deserializer)?;
+        parse_row_filter(&source).map_err(serde::de::Error::custom)
+    }
+}
+
+/// A filter whose column names are resolved to positions in one sheet's rows
+#[derive(Debug, Clone)]
+pub struct BoundRowFilter {
+    expr: Expr<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr<C> {
+    Literal(Literal),
+    Column(C),
+    Not(Box<Expr<C>>),
+    And(Box<Expr<C>>, Box<Expr<C>>),
+    Or(Box<Expr<C>>, Box<Expr<C>>),
+    Compare(Box<Expr<C>>, CompareOp, Box<Expr<C>>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Literal {
+    Null,
+    Bool(bool),
+    Number(f64),
+    Text(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl RowFilter {
+    /// Resolve column names with `position`, returning the first unknown name
+    pub fn bind(&self, position: impl Fn(&str) -> Option<usize>) -> Result<BoundRowFilter, String> {
+        Ok(BoundRowFilter {
+            expr: self.expr.bind(&position)?,
+        })
+    }
+}
+
+impl BoundRowFilter {
+    pub fn matches(&self, row: &[SqlValue]) -> bool {
+        self.expr.eval(row).truthy()
+    }
+
+    /// Positions of the columns the filter reads
+    pub fn columns(&self) -> Vec<usize> {
+        let mut columns = Vec::new();
+        self.expr.columns(&mut columns);
+        columns
+    }
+}
+
+impl Expr<String> {
+    fn bind(&self, position: &impl Fn(&str) -> Option<usize>) -> Result<Expr<usize>, String> {
+        let bind = |expr: &Expr<String>| expr.bind(position).map(Box::new);
+        Ok(match self {
+            Expr::Literal(literal) => Expr::Literal(literal.clone()),
+            Expr::Column(name) => Expr::Column(position(name).ok_or_else(|| name.clone())?),
+            Expr::Not(inner) => Expr::Not(bind(inner)?),
+            Expr::And(lhs, rhs) => Expr::And(bind(lhs)?, bind(rhs)?),
+            Expr::Or(lhs, rhs) => Expr::Or(bind(lhs)?, bind(rhs)?),
+            Expr::Compare(lhs, op, rhs) => Expr::Compare(bind(lhs)?, *op, bind(rhs)?),
+        })
+    }
+}
+
+/// Value of a sub-expression while evaluating a row
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Null,
+    Bool(bool),
+    Number(f64),
+    Text(String),
+}
+
+impl Value {
+    fn truthy(&self) -> bool {
+        match self {
+            Value::Null => false,
+            Value::Bool(b) => *b,
+            Value::Number(n) => *n != 0.0,
+            Value::Text(text) => !text.is_empty(),
+        }
+    }
+}
+
+impl From<&SqlValue> for Value {
+    fn from(value: &SqlValue) -> Self {
+        match value {
+            SqlValue::Null => Value::Null,
+            SqlValue::Boolean(b) => Value::Bool(*b),
+            SqlValue::Integer(i) => Value::Number(*i as f64),
+            SqlValue::Number(n) => Value::Number(*n),
+            SqlValue::Decimal(d) => d
+                .parse()
+                .map_or_else(|_| Value::Text(d.clone()), Value::Number),
+            SqlValue::Text(s) | SqlValue::DateTime(s) | SqlValue::Json(s) | SqlValue::Uuid(s) => {
+                Value::Text(s.clone())
+            }
+            SqlValue::Duration(d) => Value::Number(d.num_milliseconds() as f64 / 1000.0),
+            SqlValue::Array(_) | SqlValue::Binary(_) => Value::Text(value.to_string()),
+        }
+    }
+}
+
+impl Expr<usize> {
+    fn columns(&self, columns: &mut Vec<usize>) {
+        match self {
+            Expr::Literal(_) => {}
+            Expr::Column(idx) => columns.push(*idx),
+            Expr::Not(inner) => inner.columns(columns),
+            Expr::And(lhs, rhs) | Expr::Or(lhs, rhs) | Expr::Compare(lhs, _, rhs) => {
+                lhs.columns(columns);
+                rhs.columns(columns);
+            }
+        }
+    }
+
+    fn eval(&self, row: &[SqlValue]) -> Value {
+        match self {
+            Expr::Literal(Literal::Null) => Value::Null,
+            Expr::Literal(Literal::Bool(b)) => Value::Bool(*b),
+            Expr::Literal(Literal::Number(n)) => Value::Number(*n),
+            Expr::Literal(Literal::Text(text)) => Value::Text(text.clone()),
+            Expr::Column(idx) => row.get(*idx).map_or(Value::Null, Value::from),
+            Expr::Not(inner) => Value::Bool(!inner.eval(row).truthy()),
+            Expr::And(lhs, rhs) => Value::Bool(lhs.eval(row).truthy() && rhs.eval(row).truthy()),
+            Expr::Or(lhs, rhs) => Value::Bool(lhs.eval(row).truthy() || rhs.eval(row).truthy()),
+            Expr::Compare(lhs, op, rhs) => {
+                Value::Bool(compare(&lhs.eval(row), *op, &rhs.eval(row)))
+            }
+        }
+    }
+}
+
+/// NULL equals only NULL and is never ordered; numbers compare with text
+/// that parses as a number, and everything else of different kinds is unequal
+fn compare(lhs: &Value, op: CompareOp, rhs: &Value) -> bool {
+    let ordering = match (lhs, rhs) {
+        (Value::Null, Value::Null) => Some(Ordering::Equal),
+        (Value::Null, _) | (_, Value::Null) => None,
+        (Value::Number(a), Value::Number(b)) => a.partial_cmp(b),
+        (Value::Number(a), Value::Text(b)) => {
+            b.trim().parse::<f64>().ok().and_then(|b| a.partial_cmp(&b))
+        }
+        (Value::Text(a), Value::Number(b)) => {
+            a.trim().parse::<f64>().ok().and_then(|a| a.partial_cmp(b))
+        }
+        (Value::Text(a), Value::Text(b)) => Some(a.cmp(b)),
+        (Value::Bool(a), Value::Bool(b)) => Some(a.cmp(b)),
+        _ => None,
+    };
+    match (op, ordering) {
+        (CompareOp::Ne, None) => true,
+        (_, None) => false,
+        (CompareOp::Eq, Some(o)) => o == Ordering::Equal,
+        (CompareOp::Ne, Some(o)) => o != Ordering::Equal,
+        (CompareOp::Lt, Some(o)) => o == Ordering::Less,
+        (CompareOp::Le, Some(o)) => o != Ordering::Greater,
+        (CompareOp::Gt, Some(o)) => o == Ordering::Greater,
+        (CompareOp::Ge, Some(o)) => o != Ordering::Less,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Text(String),
+    Number(f64),
+    Symbol(&'static str),
+}
+
+const SYMBOLS: &[&str] = &[
+    "==", "!=", "<=", ">=", "&&", "||", "<", ">", "=", "!", "(", ")",
+];
+
+fn tokenize(source: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '"' || c == '\'' || c == '`' {
+            // Quoted text; backticks quote a column name such as `Order ID`
+            let mut text = String::new();
+            let start = i;
+            i += 1;
+            loop {
+                match chars.get(i) {
+                    None => return Err(format!("unterminated {c} at position {start}")),
+                    Some('\\') if chars.get(i + 1).is_some() => {
+                        text.push(chars[i + 1]);
+                        i += 2;
+                    }
+                    Some(&q) if q == c => {
+                        i += 1;
+                        break;
+                    }
+                    Some(&other) => {
+                        text.push(other);
+                        i += 1;
+                    }
+                }
+            }
+            tokens.push(if c == '`' {
+                Token::Ident(text)
+            } else {
+                Token::Text(text)
+            });
+        } else if c.is_ascii_digit()
+            || (c == '-' && chars.get(i + 1).is_some_and(|d| d.is_ascii_digit()))
+        {
+            let start = i;
+            i += 1;
+            while chars
+                .get(i)
+                .is_some_and(|d| d.is_ascii_digit() || *d == '.')
+            {
+                i += 1;
+            }
+            let number: String = chars[start..i].iter().collect();
+            let number = number
+                .parse()
+                .map_err(|_| format!("invalid number '{number}' at position {start}"))?;
+            tokens.push(Token::Number(number));
+        } else if c.is_alphabetic() || c == '_' {
+            let start = i;
+            while chars
+                .get(i)
+                .is_some_and(|d| d.is_alphanumeric() || *d == '_' || *d == '.')
+            {
+                i += 1;
+            }
+            tokens.push(Token::Ident(chars[start..i].iter().collect()));
+        } else {
+            let rest: String = chars[i..chars.len().min(i + 2)].iter().collect();
+            let symbol = SYMBOLS
+                .iter()
+                .find(|s| rest.starts_with(**s))
+                .ok_or_else(|| format!("unexpected '{c}' at position {i}"))?;
+            tokens.push(Token::Symbol(symbol));
+            i += symbol.len();
+        }
+    }
+    Ok(tokens)
+}
+
+/// Recursive-descent parser; `&&` binds tighter than `||`, and comparisons
+/// tighter than both
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek_symbol(&self, symbol: &str) -> bool {
+        matches!(self.tokens.get(self.pos), Some(Token::Symbol(s)) if *s == symbol)
+    }
+
+    fn eat_symbol(&mut self, symbol: &str) -> bool {
+        let found = self.peek_symbol(symbol);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn or(&mut self) -> Result<Expr<String>, String> {
+        let mut expr = self.and()?;
+        while self.eat_symbol("||") {
+            expr = Expr::Or(Box::new(expr), Box::new(self.and()?));
+        }
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<Expr<String>, String> {
+        let mut expr = self.not()?;
+        while self.eat_symbol("&&") {
+            expr = Expr::And(Box::new(expr), Box::new(self.not()?));
+        }
+        Ok(expr)
+    }
+
+    fn not(&mut self) -> Result<Expr<String>, String> {
+        if self.eat_symbol("!") {
+            return Ok(Expr::Not(Box::new(self.not()?)));
+        }
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> Result<Expr<String>, String> {
+        let lhs = self.operand()?;
+        let op = match self.tokens.get(self.pos) {
+            Some(Token::Symbol("==" | "=")) => CompareOp::Eq,
+            Some(Token::Symbol("!=")) => CompareOp::Ne,
+            Some(Token::Symbol("<")) => CompareOp::Lt,
+            Some(Token::Symbol("<=")) => CompareOp::Le,
+            Some(Token::Symbol(">")) => CompareOp::Gt,
+            Some(Token::Symbol(">=")) => CompareOp::Ge,
+            _ => return Ok(lhs),
+        };
+        self.pos += 1;
+        Ok(Expr::Compare(Box::new(lhs), op, Box::new(self.operand()?)))
+    }
+
+    fn operand(&mut self) -> Result<Expr<String>, String> {
+        let token = self
+            .tokens
+            .get(self.pos)
+            .cloned()
+            .ok_or_else(|| "unexpected end of expression".to_string())?;
+        self.pos += 1;
+        Ok(match token {
+            Token::Symbol("(") => {
+                let expr = self.or()?;
+                if !self.eat_symbol(")") {
+                    return Err("missing ')'".to_string());
+                }
+                expr
+            }
+            Token::Symbol(symbol) => return Err(format!("unexpected '{symbol}'")),
+            Token::Number(n) => Expr::Literal(Literal::Number(n)),
+            Token::Text(text) => Expr::Literal(Literal::Text(text)),
+            Token::Ident(name) => match name.as_str() {
+                "null" | "NULL" => Expr::Literal(Literal::Null),
+                "true" | "TRUE" => Expr::Literal(Literal::Bool(true)),
+                "false" | "FALSE" => Expr::Literal(Literal::Bool(false)),
+                _ => Expr::Column(name),
+            },
+        })
+    }
+}
+
+/// Parse a `--filter` expression: comparisons (`== != < <= > >=`) of column
+/// names, quoted strings, numbers, `true`, `false` and `null`, combined with
+/// `&&`, `||`, `!` and parentheses
+pub fn parse_row_filter(source: &str) -> Result<RowFilter, String> {
+    let mut parser = Parser {
+        tokens: tokenize(source)?,
+        pos: 0,
+    };
+    let expr = parser.or()?;
+    if let Some(token) = parser.tokens.get(parser.pos) {
+        let token = match token {
+            Token::Ident(text) | Token::Text(text) => text.clone(),
+            Token::Number(n) => n.to_string(),
+            Token::Symbol(symbol) => symbol.to_string(),
+        };
+        return Err(format!("unexpected '{token}' after expression"));
+    }
+    Ok(RowFilter { expr })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bound(source: &str, columns: &[&str]) -> BoundRowFilter {
+        parse_row_filter(source)
+            .unwrap()
+            .bind(|name| columns.iter().position(|c| *c == name))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_filter_rows() {
+        let filter = bound(
+            r#"status != "deleted" && amount > 0"#,
+            &["status", "amount"],
+        );
+        let row = |status: &str, amount: i64| {
+            [
+                SqlValue::Text(status.to_string()),
+                SqlValue::Integer(amount),
+            ]
+        };
+        assert!(filter.matches(&row("open", 5)));
+        assert!(!filter.matches(&row("deleted", 5)));
+        assert!(!filter.matches(&row("open", 0)));
+        assert!(!filter.matches(&[SqlValue::Null, SqlValue::Null]));
+    }
+
+    #[test]
+    fn test_precedence_and_quoting() {
+        let filter = bound(
+            "!(`Order ID` == 1) || flag && total >= '2.5'",
+            &["Order ID", "flag", "total"],
+        );
+        let row = |id: i64, flag: bool, total: f64| {
+            [
+                SqlValue::Integer(id),
+                SqlValue::Boolean(flag),
+                SqlValue::Number(total),
+            ]
+        };
+        assert!(filter.matches(&row(2, false, 0.0)));
+        assert!(filter.matches(&row(1, true, 2.5)));
+        assert!(!filter.matches(&row(1, true, 2.0)));
+        assert!(!filter.matches(&row(1, false, 9.0)));
+    }
+
+    #[test]
+    fn test_null_comparisons() {
+        let filter = bound("note == null", &["note"]);
+        assert!(filter.matches(&[SqlValue::Null]));
+        assert!(!filter.matches(&[SqlValue::Text(String::new())]));
+        let filter = bound("note != 'x'", &["note"]);
+        assert!(filter.matches(&[SqlValue::Null]));
+        assert!(!bound("n < 3", &["n"]).matches(&[SqlValue::Null]));
+    }
+
+    #[test]
+    fn test_invalid_filters() {
+        assert!(parse_row_filter("a ==").is_err());
+        assert!(parse_row_filter("(a == 1").is_err());
+        assert!(parse_row_filter("a == 'x").is_err());
+        assert!(parse_row_filter("a # 1").is_err());
+        assert!(parse_row_filter("a == 1 b").is_err());
+        let filter = parse_row_filter("missing > 1").unwrap();
+        assert_eq!(filter.bind(|_| None).unwrap_err(), "missing");
+    }
+}
@@ -0,0 +1,133 @@
+// Per-row callbacks of library users (Converter::builder().row_hook(...))
+use crate::errors::GeneratorError;
+use crate::parser::data_model::{SqlStatement, SqlValue};
+use std::fmt;
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// What becomes of a row once the hook has seen it
+#[derive(Debug, Clone, PartialEq)]
+pub enum RowAction {
+    /// Insert the row, with any changes the hook made to it
+    Keep,
+    /// Leave the row out; it counts as filtered
+    Skip,
+    /// Insert these rows in its place
+    Split(Vec<Vec<SqlValue>>),
+}
+
+type HookFn = dyn FnMut(&mut Vec<SqlValue>, &[String]) -> RowAction + Send;
+
+/// A callback run on every converted row with the row's values and the
+/// statement's column names; clones share the one callback
+#[derive(Clone)]
+pub struct RowHook(Arc<Mutex<HookFn>>);
+
+impl RowHook {
+    pub fn new(
+        hook: impl FnMut(&mut Vec<SqlValue>, &[String]) -> RowAction + Send + 'static,
+    ) -> Self {
+        Self(Arc::new(Mutex::new(hook)))
+    }
+
+    /// Run the hook on every row of `statement`, in order
+    pub fn apply(&self, statement: &mut SqlStatement) -> Result<(), GeneratorError> {
+        let mut hook = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut values = Vec::with_capacity(statement.values.len());
+        for mut row in std::mem::take(&mut statement.values) {
+            match hook(&mut row, &statement.columns) {
+                RowAction::Keep => values.push(row),
+                RowAction::Skip => statement.stats.filtered_rows += 1,
+                RowAction::Split(rows) => values.extend(rows),
+            }
+        }
+        if let Some(row) = values
+            .iter()
+            .find(|row| row.len() != statement.columns.len())
+        {
+            return Err(GeneratorError::RowHookColumns {
+                table: statement.table_name.clone(),
+                expected: statement.columns.len(),
+                found: row.len(),
+            });
+        }
+        statement.values = values;
+        Ok(())
+    }
+}
+
+impl fmt::Debug for RowHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("RowHook(..)")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn statement() -> SqlStatement {
+        SqlStatement {
+            table_name: "orders".to_string(),
+            columns: vec!["id".to_string(), "items".to_string()],
+            values: (1..=3)
+                .map(|id| vec![SqlValue::Integer(id), SqlValue::Text("Pen;Ink".to_string())])
+                .collect(),
+            warnings: Vec::new(),
+            stats: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_apply() {
+        let hook = RowHook::new(|row, columns| {
+            assert_eq!(columns, ["id", "items"]);
+            match row[0] {
+                SqlValue::Integer(1) => RowAction::Skip,
+                SqlValue::Integer(2) => {
+                    let SqlValue::Text(items) = &row[1] else {
+                        unreachable!()
+                    };
+                    RowAction::Split(
+                        items
+                            .split(';')
+                            .map(|item| vec![row[0].clone(), SqlValue::Text(item.to_string())])
+                            .collect(),
+                    )
+                }
+                _ => {
+                    row[1] = SqlValue::Null;
+                    RowAction::Keep
+                }
+            }
+        });
+        let mut statement = statement();
+        hook.apply(&mut statement).unwrap();
+        let text = |s: &str| SqlValue::Text(s.to_string());
+        assert_eq!(
+            statement.values,
+            [
+                vec![SqlValue::Integer(2), text("Pen")],
+                vec![SqlValue::Integer(2), text("Ink")],
+                vec![SqlValue::Integer(3), SqlValue::Null],
+            ]
+        );
+        assert_eq!(statement.stats.filtered_rows, 1);
+    }
+
+    #[test]
+    fn test_row_length_changed() {
+        let hook = RowHook::new(|row, _| {
+            row.pop();
+            RowAction::Keep
+        });
+        let err = hook.apply(&mut statement()).unwrap_err();
+        assert!(matches!(
+            err,
+            GeneratorError::RowHookColumns {
+                expected: 2,
+                found: 1,
+                ..
+            }
+        ));
+    }
+}
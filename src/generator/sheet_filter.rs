@@ -0,0 +1,140 @@
+// Selection of the sheets to convert (--sheet, --sheet-index, --include/--exclude-sheets)
+use crate::parser::data_model::{SheetData, WorkbookData};
+use regex::Regex;
+
+/// Sheets to convert: those selected by name, position or the include
+/// pattern (every sheet when none is given), minus those the exclude pattern matches
+#[derive(Debug, Clone, Default)]
+pub struct SheetFilter {
+    /// Sheet names, matched case-insensitively
+    pub names: Vec<String>,
+    /// Zero-based sheet positions
+    pub indices: Vec<usize>,
+    pub include: Option<Regex>,
+    pub exclude: Option<Regex>,
+}
+
+impl SheetFilter {
+    pub fn matches(&self, sheet: &SheetData) -> bool {
+        if self
+            .exclude
+            .as_ref()
+            .is_some_and(|exclude| exclude.is_match(&sheet.name))
+        {
+            return false;
+        }
+        if self.names.is_empty() && self.indices.is_empty() && self.include.is_none() {
+            return true;
+        }
+        self.names.iter().any(|name| same_name(name, &sheet.name))
+            || self.indices.contains(&sheet.index)
+            || self
+                .include
+                .as_ref()
+                .is_some_and(|include| include.is_match(&sheet.name))
+    }
+
+    /// Requested sheets the workbook does not contain
+    pub fn missing(&self, data: &WorkbookData) -> Vec<String> {
+        let names = self
+            .names
+            .iter()
+            .filter(|name| !data.sheets.iter().any(|s| same_name(name, &s.name)))
+            .cloned();
+        let indices = self
+            .indices
+            .iter()
+            .filter(|index| !data.sheets.iter().any(|s| s.index == **index))
+            .map(|index| format!("#{index}"));
+        names.chain(indices).collect()
+    }
+}
+
+fn same_name(requested: &str, sheet: &str) -> bool {
+    requested.trim().eq_ignore_ascii_case(sheet.trim())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use calamine::Range;
+
+    fn workbook(names: &[&str]) -> WorkbookData {
+        WorkbookData::new(
+            names
+                .iter()
+                .enumerate()
+                .map(|(index, name)| {
+                    let mut sheet = SheetData::new(name.to_string(), Range::empty());
+                    sheet.index = index;
+                    sheet
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_sheet_names() {
+        let data = workbook(&["Orders", "Customers", "README"]);
+        let filter = SheetFilter {
+            names: vec!["orders".to_string(), "Customers".to_string()],
+            ..Default::default()
+        };
+        let selected: Vec<&str> = data
+            .sheets
+            .iter()
+            .filter(|s| filter.matches(s))
+            .map(|s| s.name.as_str())
+            .collect();
+        assert_eq!(selected, ["Orders", "Customers"]);
+        assert!(filter.missing(&data).is_empty());
+
+        let filter = SheetFilter {
+            names: vec!["Invoices".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(filter.missing(&data), ["Invoices"]);
+        assert!(SheetFilter::default().matches(&data.sheets[2]));
+    }
+
+    #[test]
+    fn test_sheet_indices() {
+        let data = workbook(&["Sheet1", "Sheet2", "Sheet3"]);
+        let filter = SheetFilter {
+            names: vec!["Sheet1".to_string()],
+            indices: vec![2, 5],
+            ..Default::default()
+        };
+        let selected: Vec<usize> = data
+            .sheets
+            .iter()
+            .filter(|s| filter.matches(s))
+            .map(|s| s.index)
+            .collect();
+        assert_eq!(selected, [0, 2]);
+        assert_eq!(filter.missing(&data), ["#5"]);
+    }
+
+    #[test]
+    fn test_sheet_patterns() {
+        let data = workbook(&["tbl_orders", "tbl_users", "README", "Notes"]);
+        let selected = |filter: &SheetFilter| -> Vec<String> {
+            data.sheets
+                .iter()
+                .filter(|s| filter.matches(s))
+                .map(|s| s.name.clone())
+                .collect()
+        };
+        let filter = SheetFilter {
+            include: Some(Regex::new("^tbl_").unwrap()),
+            exclude: Some(Regex::new("users").unwrap()),
+            ..Default::default()
+        };
+        assert_eq!(selected(&filter), ["tbl_orders"]);
+        let filter = SheetFilter {
+            exclude: Some(Regex::new("(?i)readme|notes").unwrap()),
+            ..Default::default()
+        };
+        assert_eq!(selected(&filter), ["tbl_orders", "tbl_users"]);
+    }
+}
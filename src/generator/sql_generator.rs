@@ -1,95 +1,954 @@
 use crate::errors::GeneratorError;
-use crate::generator::formatter::SqlFormatter;
-use crate::parser::data_model::{SqlStatement, SqlValue, WorkbookData};
+use crate::generator::column_type::ColumnType;
+use crate::generator::dialect::Dialect;
+use crate::generator::duration::format_duration;
+use crate::generator::fiscal::FiscalDerivation;
+use crate::generator::formatter::{Conflict, SqlFormatter};
+use crate::generator::mask::MaskMethod;
+use crate::generator::naming::{
+    dedupe, duplicates, is_default_sheet_name, renamed, sanitize_identifier, DuplicateHeaders,
+    ReservedWords,
+};
+use crate::generator::options::{
+    ArrayStyle, BoolFormat, CellErrorPolicy, GeneratorOptions, InsertMode, TenantCheck,
+};
+use crate::generator::value_converter::ValueConverter;
+use crate::parser::data_model::{
+    column_letter, SheetData, SqlStatement, SqlValue, StatementStats, WorkbookData,
+};
+use calamine::Data;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::time::Instant;
 
 pub trait SqlGenerator {
-    fn generate(&self, data: &WorkbookData) -> Result<Vec<SqlStatement>, GeneratorError>;
+    fn generate(&self, data: &WorkbookData) -> Result<Vec<SqlStatement>, GeneratorError> {
+        self.generate_all(data).map(|(statements, _)| statements)
+    }
+    /// The statements, and the problems of the rows and sheets left out under
+    /// `keep_going` instead of failing
+    fn generate_all(
+        &self,
+        data: &WorkbookData,
+    ) -> Result<(Vec<SqlStatement>, Vec<String>), GeneratorError> {
+        let mut statements = self.statements(data);
+        let collected = statements.by_ref().collect::<Result<Vec<_>, _>>()?;
+        Ok((collected, statements.into_problems()))
+    }
+    /// The statements one sheet at a time, for workbooks too large to hold
+    /// all their converted rows at once
+    fn statements<'a>(&'a self, data: &'a WorkbookData) -> Statements<'a>;
     fn format_statement(&self, statement: &SqlStatement) -> String;
+    fn format_sql_value(&self, value: &SqlValue) -> String;
 }
 
-pub struct MySqlGenerator;
+#[derive(Default)]
+pub struct MySqlGenerator {
+    pub options: GeneratorOptions,
+}
+
+#[derive(Default)]
+pub struct PostgresGenerator {
+    pub options: GeneratorOptions,
+}
 
 impl SqlGenerator for MySqlGenerator {
-    fn generate(&self, data: &WorkbookData) -> Result<Vec<SqlStatement>, GeneratorError> {
-        let mut statements = Vec::new();
+    fn statements<'a>(&'a self, data: &'a WorkbookData) -> Statements<'a> {
+        Statements::new(&self.options, Dialect::Mysql, data)
+    }
 
-        for sheet in &data.sheets {
-            let columns = sheet.get_columns()?;
-            if columns.is_empty() {
-                continue;
-            }
+    fn format_statement(&self, statement: &SqlStatement) -> String {
+        format_insert(statement, Dialect::Mysql, &self.options, |value| {
+            self.format_sql_value(value)
+        })
+    }
+
+    fn format_sql_value(&self, value: &SqlValue) -> String {
+        format_value(value, Dialect::Mysql, &self.options)
+    }
+}
+
+impl SqlGenerator for PostgresGenerator {
+    fn statements<'a>(&'a self, data: &'a WorkbookData) -> Statements<'a> {
+        Statements::new(&self.options, Dialect::Postgres, data)
+    }
+
+    fn format_statement(&self, statement: &SqlStatement) -> String {
+        format_insert(statement, Dialect::Postgres, &self.options, |value| {
+            self.format_sql_value(value)
+        })
+    }
+
+    fn format_sql_value(&self, value: &SqlValue) -> String {
+        format_value(value, Dialect::Postgres, &self.options)
+    }
+}
 
-            let mut values = Vec::new();
-            for row in sheet.get_data_rows() {
-                let row_values: Vec<SqlValue> = row.iter().map(SqlValue::from).collect();
-                values.push(row_values);
+impl MySqlGenerator {
+    pub fn new(options: GeneratorOptions) -> Self {
+        Self { options }
+    }
+}
+
+impl PostgresGenerator {
+    pub fn new(options: GeneratorOptions) -> Self {
+        Self { options }
+    }
+}
+
+/// Create the generator for `dialect`
+pub fn generator_for(dialect: Dialect, options: GeneratorOptions) -> Box<dyn SqlGenerator> {
+    match dialect {
+        Dialect::Mysql => Box::new(MySqlGenerator::new(options)),
+        Dialect::Postgres => Box::new(PostgresGenerator::new(options)),
+    }
+}
+
+/// The statements of a workbook, converted one sheet at a time as the
+/// iterator advances (all at once under `union_sheets`). Iteration ends after
+/// the first error; problems of sheets and rows left out under `keep_going`
+/// collect in [`Statements::problems`].
+pub struct Statements<'a> {
+    options: &'a GeneratorOptions,
+    dialect: Dialect,
+    converter: ValueConverter<'a>,
+    sources: std::vec::IntoIter<Cow<'a, SheetData>>,
+    /// Error found before any sheet was converted
+    pending: Option<GeneratorError>,
+    problems: Vec<String>,
+    default_named: usize,
+    default_seen: usize,
+    /// Rows emitted per table, for `limit`
+    emitted: HashMap<String, usize>,
+    converted: bool,
+    done: bool,
+}
+
+impl<'a> Statements<'a> {
+    pub fn new(options: &'a GeneratorOptions, dialect: Dialect, data: &'a WorkbookData) -> Self {
+        let (sources, pending) = match sources(options, data) {
+            Ok(sources) => (sources, None),
+            Err(e) => (Vec::new(), Some(e)),
+        };
+        // Sheets named like Sheet1 take the file's name, numbered when there are several
+        let default_named = sources
+            .iter()
+            .filter(|sheet| is_default_sheet_name(&sheet.name))
+            .count();
+        Self {
+            options,
+            dialect,
+            converter: ValueConverter::new(options),
+            sources: sources.into_iter(),
+            pending,
+            problems: Vec::new(),
+            default_named,
+            default_seen: 0,
+            emitted: HashMap::new(),
+            converted: false,
+            done: false,
+        }
+    }
+
+    /// Problems of the rows and sheets skipped so far under `keep_going`
+    pub fn problems(&self) -> &[String] {
+        &self.problems
+    }
+
+    pub fn into_problems(self) -> Vec<String> {
+        self.problems
+    }
+
+    /// The statement of the next sheet that has one
+    fn next_sheet(&mut self) -> Option<Result<SqlStatement, GeneratorError>> {
+        for sheet in self.sources.by_ref() {
+            let file_table = match &self.options.file_table_name {
+                Some(name) if is_default_sheet_name(&sheet.name) => {
+                    self.default_seen += 1;
+                    Some(match self.default_named {
+                        1 => name.clone(),
+                        _ => format!("{name}_{}", self.default_seen),
+                    })
+                }
+                _ => None,
+            };
+            match generate_sheet(
+                self.options,
+                self.dialect,
+                &sheet,
+                file_table,
+                &self.converter,
+                &mut self.problems,
+            ) {
+                Ok(Some(mut statement)) => {
+                    self.converted = true;
+                    let Some(hook) = &self.options.row_hook else {
+                        return Some(Ok(statement));
+                    };
+                    match hook.apply(&mut statement) {
+                        Ok(()) if statement.values.is_empty() => {}
+                        Ok(()) => return Some(Ok(statement)),
+                        Err(e) if self.options.keep_going => {
+                            self.problems
+                                .push(format!("{}: {e}; sheet skipped", sheet.name));
+                        }
+                        Err(e) => return Some(Err(e)),
+                    }
+                }
+                Ok(None) => {}
+                Err(e) if self.options.keep_going => {
+                    self.problems
+                        .push(format!("{}: {e}; sheet skipped", sheet.name));
+                }
+                Err(e) => return Some(Err(e)),
             }
+        }
+        None
+    }
 
-            if !values.is_empty() {
-                statements.push(SqlStatement {
-                    table_name: sheet.name.clone(),
-                    columns,
-                    values,
-                });
+    /// The next statement with rows left after `limit`
+    fn next_limited(&mut self) -> Option<Result<SqlStatement, GeneratorError>> {
+        loop {
+            let mut statement = match self.next_sheet()? {
+                Ok(statement) => statement,
+                Err(e) => return Some(Err(e)),
+            };
+            let Some(limit) = self.options.limit else {
+                return Some(Ok(statement));
+            };
+            let count = self
+                .emitted
+                .entry(statement.table_name.clone())
+                .or_default();
+            let rows = statement.values.len();
+            statement.values.truncate(limit.saturating_sub(*count));
+            *count += statement.values.len();
+            statement.stats.limited_rows = rows - statement.values.len();
+            if !statement.values.is_empty() {
+                return Some(Ok(statement));
             }
         }
+    }
 
-        // Check if no data was found
+    /// Every statement combined into one
+    fn union(&mut self) -> Option<Result<SqlStatement, GeneratorError>> {
+        let mut statements = Vec::new();
+        while let Some(statement) = self.next_sheet() {
+            match statement {
+                Ok(statement) => statements.push(statement),
+                Err(e) => return Some(Err(e)),
+            }
+        }
         if statements.is_empty() {
-            return Err(GeneratorError::NoData);
+            return None;
+        }
+        let mut union = match union_statements(statements) {
+            Ok(union) => union,
+            Err(e) => return Some(Err(e)),
+        };
+        if let Some(limit) = self.options.limit {
+            union.stats.limited_rows = union.values.len().saturating_sub(limit);
+            union.values.truncate(limit);
+        }
+        (!union.values.is_empty()).then_some(Ok(union))
+    }
+}
+
+impl Iterator for Statements<'_> {
+    type Item = Result<SqlStatement, GeneratorError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        if let Some(e) = self.pending.take() {
+            self.done = true;
+            return Some(Err(e));
+        }
+        let item = match self.options.union_sheets {
+            true => {
+                self.done = true;
+                self.union()
+            }
+            false => self.next_limited(),
+        };
+        match &item {
+            // Nothing to insert at all, rather than everything skipped
+            None if !self.converted && self.problems.is_empty() => {
+                self.done = true;
+                return Some(Err(GeneratorError::NoData));
+            }
+            None | Some(Err(_)) => self.done = true,
+            Some(Ok(_)) => {}
         }
+        item
+    }
+}
 
-        Ok(statements)
+/// The sheets, named ranges or Excel Tables the options select for conversion
+fn sources<'a>(
+    options: &GeneratorOptions,
+    data: &'a WorkbookData,
+) -> Result<Vec<Cow<'a, SheetData>>, GeneratorError> {
+    let missing = options.sheet_filter.missing(data);
+    if !missing.is_empty() {
+        return Err(GeneratorError::UnknownSheets(missing.join(", ")));
     }
+    if !options.named_ranges.is_empty() {
+        return Ok(named_ranges(options, data)?
+            .into_iter()
+            .map(Cow::Owned)
+            .collect());
+    }
+    let sheets = data
+        .sheets
+        .iter()
+        .filter(|s| options.sheet_filter.matches(s));
+    Ok(match options.tables {
+        true => sheets
+            .flat_map(|sheet| {
+                let tables: Vec<Cow<SheetData>> =
+                    data.tables_in(&sheet.name).map(Cow::Owned).collect();
+                if tables.is_empty() {
+                    vec![Cow::Borrowed(sheet)]
+                } else {
+                    tables
+                }
+            })
+            .collect(),
+        false => sheets.map(Cow::Borrowed).collect(),
+    })
+}
 
-    fn format_statement(&self, statement: &SqlStatement) -> String {
-        let table_name = SqlFormatter::format_identifier(&statement.table_name);
-        let columns = statement
-            .columns
-            .iter()
-            .map(|col| SqlFormatter::format_identifier(col))
-            .collect::<Vec<_>>()
-            .join(", ");
+/// Concatenate the rows of statements with identical columns into the first
+fn union_statements(statements: Vec<SqlStatement>) -> Result<SqlStatement, GeneratorError> {
+    let mut statements = statements.into_iter();
+    let mut union = statements.next().ok_or(GeneratorError::NoData)?;
+    for statement in statements {
+        if statement.columns != union.columns {
+            return Err(GeneratorError::UnionColumns {
+                sheet: statement.stats.sheet,
+                columns: statement.columns.join(", "),
+                first: union.stats.sheet,
+                expected: union.columns.join(", "),
+            });
+        }
+        union.values.extend(statement.values);
+        union.warnings.extend(statement.warnings);
+        union.stats.convert_time += statement.stats.convert_time;
+        union.stats.filtered_rows += statement.stats.filtered_rows;
+        union.stats.error_rows += statement.stats.error_rows;
+        union.stats.failed_rows += statement.stats.failed_rows;
+    }
+    Ok(union)
+}
 
-        let values_str = statement
-            .values
+fn is_blank(cell: &Data) -> bool {
+    match cell {
+        Data::Empty => true,
+        Data::String(text) => text.trim().is_empty(),
+        _ => false,
+    }
+}
+
+/// Resolve `options.named_ranges` against the workbook's defined names
+fn named_ranges(
+    options: &GeneratorOptions,
+    data: &WorkbookData,
+) -> Result<Vec<SheetData>, GeneratorError> {
+    let missing: Vec<&str> = options
+        .named_ranges
+        .iter()
+        .filter(|name| data.defined_name(name).is_none())
+        .map(String::as_str)
+        .collect();
+    if !missing.is_empty() {
+        return Err(GeneratorError::UnknownNamedRanges(missing.join(", ")));
+    }
+    options
+        .named_ranges
+        .iter()
+        .map(|name| {
+            data.named_range(name)
+                .ok_or_else(|| GeneratorError::InvalidNamedRange {
+                    name: name.clone(),
+                    reference: data.defined_name(name).unwrap_or_default().to_string(),
+                })
+        })
+        .collect()
+}
+
+/// The header and data rows of `sheet` the options convert: below
+/// `skip_rows` or the sheet's mapped header row, above `skip_footer`
+pub fn data_window(options: &GeneratorOptions, sheet: &SheetData) -> SheetData {
+    let skip_rows = options
+        .mapping_for(&sheet.name)
+        .and_then(|m| m.header_row)
+        .map_or(options.skip_rows, |row| row.saturating_sub(1));
+    sheet.window(skip_rows, options.max_rows, options.skip_footer)
+}
+
+fn generate_sheet(
+    options: &GeneratorOptions,
+    dialect: Dialect,
+    sheet: &SheetData,
+    file_table: Option<String>,
+    converter: &ValueConverter,
+    problems: &mut Vec<String>,
+) -> Result<Option<SqlStatement>, GeneratorError> {
+    let mapping = options.mapping_for(&sheet.name);
+    let sheet = &data_window(options, sheet);
+    let (_, start_col) = sheet.range.start().unwrap_or((0, 0));
+    let (kept, headers): (Vec<usize>, Vec<String>) = sheet
+        .get_columns()?
+        .into_iter()
+        .enumerate()
+        .filter(|(col_idx, header)| {
+            options
+                .column_filter
+                .keeps(start_col as usize + col_idx, header)
+                && !mapping.is_some_and(|m| m.excludes(header))
+        })
+        .unzip();
+    if headers.is_empty() {
+        return Ok(None);
+    }
+
+    let table_name = options
+        .table_name
+        .clone()
+        .or_else(|| mapping.and_then(|m| m.table.clone()))
+        .or(file_table)
+        .unwrap_or_else(|| sheet.name.clone());
+    let mut table_name = options.identifiers.apply(&format!(
+        "{}{table_name}{}",
+        options.table_prefix, options.table_suffix
+    ));
+    let mut columns: Vec<String> = headers
+        .iter()
+        .zip(&kept)
+        .map(
+            |(h, &col)| match renamed(&options.column_renames, start_col as usize + col, h) {
+                Some(column) => column.to_string(),
+                None => mapping.map_or_else(|| h.clone(), |m| m.column_name(h)),
+            },
+        )
+        .map(|column| options.identifiers.apply(&column))
+        .collect();
+    let mut warnings = Vec::new();
+    if options.identifiers.sanitize {
+        let mut changes = Vec::new();
+        let table = sanitize_identifier(&table_name).unwrap_or_else(|| "data".to_string());
+        if table != table_name {
+            changes.push(format!("table '{table_name}' -> {table}"));
+            table_name = table;
+        }
+        let original = columns.clone();
+        for (idx, column) in columns.iter_mut().enumerate() {
+            *column = sanitize_identifier(column).unwrap_or_else(|| format!("column_{}", idx + 1));
+        }
+        dedupe(&mut columns);
+        changes.extend(
+            original
+                .iter()
+                .zip(&columns)
+                .filter(|(from, to)| from != to)
+                .map(|(from, to)| format!("'{from}' -> {to}")),
+        );
+        if !changes.is_empty() {
+            warnings.push(format!(
+                "{}: sanitized identifiers: {}",
+                sheet.name,
+                changes.join(", ")
+            ));
+        }
+    }
+    let reserved: Vec<String> = std::iter::once(&table_name)
+        .chain(&columns)
+        .filter(|name| dialect.is_reserved(name))
+        .cloned()
+        .collect();
+    if !reserved.is_empty() {
+        match options.identifiers.reserved {
+            ReservedWords::Quote => {}
+            ReservedWords::Rename => {
+                for name in std::iter::once(&mut table_name).chain(&mut columns) {
+                    if dialect.is_reserved(name) {
+                        name.push('_');
+                    }
+                }
+                dedupe(&mut columns);
+                warnings.push(format!(
+                    "{}: renamed {} reserved word(s): {}",
+                    sheet.name,
+                    dialect.name(),
+                    reserved
+                        .iter()
+                        .map(|name| format!("{name} -> {name}_"))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ));
+            }
+            ReservedWords::Error => {
+                return Err(GeneratorError::ReservedWords {
+                    sheet: sheet.name.clone(),
+                    dialect: dialect.name(),
+                    names: reserved.join(", "),
+                })
+            }
+        }
+    }
+    let repeated = duplicates(&columns);
+    if !repeated.is_empty() {
+        let letter = |idx: usize| column_letter(start_col as usize + kept[idx]);
+        let names: Vec<String> = repeated
             .iter()
-            .map(|row| {
-                let row_str = row
+            .map(|group| {
+                let letters: Vec<String> = group.iter().map(|&idx| letter(idx)).collect();
+                format!("{} ({})", columns[group[0]], letters.join(", "))
+            })
+            .collect();
+        match options.identifiers.duplicates {
+            DuplicateHeaders::Suffix => {
+                dedupe(&mut columns);
+                let renamed: Vec<String> = repeated
+                    .iter()
+                    .map(|group| {
+                        let names: Vec<&str> =
+                            group.iter().map(|&idx| columns[idx].as_str()).collect();
+                        names.join(", ")
+                    })
+                    .collect();
+                warnings.push(format!(
+                    "{}: duplicate column name(s) {} inserted as {}",
+                    sheet.name,
+                    names.join(", "),
+                    renamed.join("; ")
+                ));
+            }
+            DuplicateHeaders::Error => {
+                return Err(GeneratorError::DuplicateColumns {
+                    sheet: sheet.name.clone(),
+                    columns: names.join(", "),
+                    column: letter(repeated[0][1]),
+                })
+            }
+        }
+    }
+    let column_types: Vec<Option<&ColumnType>> = headers
+        .iter()
+        .zip(&columns)
+        .map(|(header, column)| {
+            options
+                .column_types
+                .get(header)
+                .or_else(|| options.column_types.get(column))
+                .or_else(|| mapping.and_then(|m| m.types.get(header).or(m.types.get(column))))
+        })
+        .collect();
+
+    // Derivations whose source column exists in this sheet
+    let derivations: Vec<(usize, &FiscalDerivation)> = options
+        .fiscal_derivations
+        .iter()
+        .filter_map(|d| columns.iter().position(|c| *c == d.source).map(|i| (i, d)))
+        .collect();
+
+    let row_filters = options
+        .row_filter
+        .iter()
+        .chain(mapping.and_then(|m| m.filter.as_ref()))
+        .map(|filter| {
+            filter.bind(|name| {
+                let name = name.trim();
+                headers
                     .iter()
-                    .map(|val| self.format_sql_value(val))
-                    .collect::<Vec<_>>()
-                    .join(",");
-                format!("({row_str})")
+                    .position(|h| h.trim().eq_ignore_ascii_case(name))
+                    .or_else(|| columns.iter().position(|c| c.eq_ignore_ascii_case(name)))
             })
-            .collect::<Vec<_>>()
-            .join(",\n");
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|column| GeneratorError::FilterColumn {
+            sheet: sheet.name.clone(),
+            column,
+        })?;
 
-        format!("INSERT INTO {table_name} ({columns}) VALUES\n{values_str};")
+    let started = Instant::now();
+    // Columns whose empty cells repeat the value above them
+    let fill_down: Vec<bool> = headers
+        .iter()
+        .zip(&columns)
+        .map(|(header, column)| {
+            options.fill_down.iter().any(|name| {
+                let name = name.trim();
+                header.trim().eq_ignore_ascii_case(name) || column.eq_ignore_ascii_case(name)
+            })
+        })
+        .collect();
+    let mut last_values: Vec<SqlValue> = vec![SqlValue::Null; headers.len()];
+    let masks: Vec<(usize, MaskMethod)> = headers
+        .iter()
+        .zip(&columns)
+        .enumerate()
+        .filter_map(|(idx, (header, column))| {
+            let mask = options
+                .masks
+                .iter()
+                .find(|m| m.applies_to(header, column))?;
+            Some((idx, mask.method))
+        })
+        .collect();
+
+    // Columns converted to test a row against the filters before converting
+    // it for good; fill-down columns keep carrying values past left-out rows
+    let filter_columns: Vec<usize> = row_filters.iter().flat_map(|f| f.columns()).collect();
+    let mut probed = filter_columns.clone();
+    if !probed.is_empty() {
+        probed.extend((0..fill_down.len()).filter(|&i| fill_down[i]));
+    }
+    probed.sort_unstable();
+    probed.dedup();
+    let probe = converter.probe();
+
+    let mut values = Vec::new();
+    // Data row of the sheet each of `values` came from
+    let mut source_rows = Vec::new();
+    let mut error_cells = Vec::new();
+    let (mut filtered_rows, mut error_rows, mut failed_rows) = (0, 0, 0);
+    // --keep-going turns failing on error cells into skipping their rows
+    let skip_error_rows = match options.on_cell_error {
+        CellErrorPolicy::SkipRow => true,
+        CellErrorPolicy::Fail => options.keep_going,
+        CellErrorPolicy::Null => false,
+    };
+    for (row_idx, row) in sheet.get_data_rows().enumerate() {
+        if options.sample.is_some_and(|rows| values.len() >= rows) {
+            break;
+        }
+        if options.stop_at_blank_row && row.iter().all(is_blank) {
+            break;
+        }
+        let row_errors: Vec<String> = kept
+            .iter()
+            .map(|&col_idx| (col_idx, &row[col_idx]))
+            .filter_map(|(col_idx, cell)| match cell {
+                Data::Error(error) => Some(format!(
+                    "{} {error}",
+                    sheet.cell_reference(row_idx, col_idx)
+                )),
+                _ => None,
+            })
+            .collect();
+        if !row_errors.is_empty() && skip_error_rows {
+            error_cells.extend(row_errors);
+            error_rows += 1;
+            continue;
+        }
+
+        // Rows the filters leave out write no file and fail nothing
+        if !probed.is_empty() {
+            let mut probe_values = vec![SqlValue::Null; kept.len()];
+            let mut testable = true;
+            for &i in &probed {
+                let cell_ref = sheet.cell_reference(row_idx, kept[i]);
+                let column_type = column_types.get(i).copied().flatten();
+                match probe.convert(&row[kept[i]], column_type, &cell_ref, &mut Vec::new()) {
+                    Ok(value) => probe_values[i] = value,
+                    // The conversion below reports it, unless the filter
+                    // needs the value to leave the row out
+                    Err(_) => testable &= !filter_columns.contains(&i),
+                }
+                if fill_down[i] && probe_values[i] == SqlValue::Null {
+                    probe_values[i].clone_from(&last_values[i]);
+                }
+            }
+            if testable
+                && !row_filters
+                    .iter()
+                    .all(|filter| filter.matches(&probe_values))
+            {
+                for i in (0..kept.len()).filter(|&i| fill_down[i]) {
+                    last_values[i].clone_from(&probe_values[i]);
+                }
+                filtered_rows += 1;
+                continue;
+            }
+        }
+
+        let mut row_values = Vec::with_capacity(kept.len());
+        let mut row_problem = None;
+        for (i, &col_idx) in kept.iter().enumerate() {
+            let cell = &row[col_idx];
+            let cell_ref = sheet.cell_reference(row_idx, col_idx);
+            let column_type = column_types.get(i).copied().flatten();
+            match converter.convert(cell, column_type, &cell_ref, &mut warnings) {
+                Ok(value) => row_values.push(value),
+                Err(e) if options.keep_going => {
+                    row_problem = Some(e);
+                    break;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        if let Some(e) = row_problem {
+            problems.push(format!("{e}; row skipped"));
+            failed_rows += 1;
+            continue;
+        }
+        for (i, value) in row_values.iter_mut().enumerate() {
+            if !fill_down[i] {
+                continue;
+            }
+            if *value == SqlValue::Null {
+                value.clone_from(&last_values[i]);
+            } else {
+                last_values[i].clone_from(value);
+            }
+        }
+        // Excel errors in rows the filter leaves out are not reported
+        if !row_filters.iter().all(|filter| filter.matches(&row_values)) {
+            filtered_rows += 1;
+            continue;
+        }
+        error_cells.extend(row_errors);
+        for (source_idx, derivation) in &derivations {
+            let derived = derivation.derive(&row_values[*source_idx]);
+            row_values.extend(derived);
+        }
+        for &(idx, method) in &masks {
+            row_values[idx] = method.apply(&row_values[idx]);
+        }
+        values.push(row_values);
+        source_rows.push(row_idx);
     }
+
+    let mut declared_types: Vec<Option<String>> = column_types
+        .iter()
+        .map(|column_type| column_type.map(ColumnType::to_string))
+        .collect();
+    for (_, derivation) in &derivations {
+        columns.extend(derivation.columns());
+    }
+    declared_types.resize(columns.len(), None);
+
+    if !error_cells.is_empty() {
+        let cells = summarize_cells(&error_cells);
+        match options.on_cell_error {
+            CellErrorPolicy::Null => warnings.push(format!(
+                "{}: {} error cell(s) emitted as NULL: {cells}",
+                sheet.name,
+                error_cells.len()
+            )),
+            CellErrorPolicy::SkipRow => warnings.push(format!(
+                "{}: skipped rows with {} error cell(s): {cells}",
+                sheet.name,
+                error_cells.len()
+            )),
+            CellErrorPolicy::Fail if options.keep_going => problems.push(format!(
+                "{}: skipped rows with {} error cell(s): {cells}",
+                sheet.name,
+                error_cells.len()
+            )),
+            CellErrorPolicy::Fail => {
+                return Err(GeneratorError::CellErrors {
+                    count: error_cells.len(),
+                    cells,
+                })
+            }
+        }
+    }
+
+    if let Some(tenant) = &options.tenant {
+        check_tenant(tenant, sheet, &columns, &kept, &values, &source_rows)?;
+    }
+
+    if values.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(SqlStatement {
+        table_name,
+        columns,
+        values,
+        warnings,
+        stats: StatementStats {
+            sheet: sheet.name.clone(),
+            convert_time: started.elapsed(),
+            filtered_rows,
+            error_rows,
+            failed_rows,
+            declared_types,
+            ..Default::default()
+        },
+    }))
 }
 
-impl MySqlGenerator {
-    fn format_sql_value(&self, value: &SqlValue) -> String {
-        match value {
-            SqlValue::Null => "NULL".to_string(),
-            SqlValue::Text(s) => SqlFormatter::format_string_literal(s),
-            SqlValue::Number(f) => f.to_string(),
-            SqlValue::Integer(i) => i.to_string(),
-            SqlValue::Boolean(b) => if *b { "1" } else { "0" }.to_string(),
-            SqlValue::DateTime(dt) => format!("'{dt}'"),
+/// Fail unless every row of `values` has the expected tenant; `kept` maps
+/// columns to the sheet's and `source_rows` rows to its data rows, for the
+/// cell references of the mismatches
+fn check_tenant(
+    tenant: &TenantCheck,
+    sheet: &SheetData,
+    columns: &[String],
+    kept: &[usize],
+    values: &[Vec<SqlValue>],
+    source_rows: &[usize],
+) -> Result<(), GeneratorError> {
+    let Some(col_idx) = columns.iter().position(|c| *c == tenant.column) else {
+        return Err(GeneratorError::MissingTenantColumn {
+            sheet: sheet.name.clone(),
+            column: tenant.column.clone(),
+        });
+    };
+
+    // Derived columns have no cell of their own
+    let sheet_col = kept.get(col_idx).copied().unwrap_or(col_idx);
+    let mismatches: Vec<String> = values
+        .iter()
+        .zip(source_rows)
+        .filter(|(row, _)| {
+            let found = match row.get(col_idx) {
+                Some(SqlValue::Text(text)) => text.trim().to_string(),
+                Some(SqlValue::Null) | None => String::new(),
+                Some(other) => other.to_string(),
+            };
+            found != tenant.expected
+        })
+        .map(|(_, &row_idx)| sheet.cell_reference(row_idx, sheet_col))
+        .collect();
+
+    if mismatches.is_empty() {
+        return Ok(());
+    }
+    Err(GeneratorError::TenantMismatch {
+        expected: tenant.expected.clone(),
+        count: mismatches.len(),
+        cells: summarize_cells(&mismatches),
+    })
+}
+
+/// The first few cell references, for messages about many cells
+fn summarize_cells(cells: &[String]) -> String {
+    let mut summary = cells.iter().take(5).cloned().collect::<Vec<_>>().join(", ");
+    if cells.len() > 5 {
+        summary.push_str(", ...");
+    }
+    summary
+}
+
+fn format_insert(
+    statement: &SqlStatement,
+    dialect: Dialect,
+    options: &GeneratorOptions,
+    format_value: impl Fn(&SqlValue) -> String,
+) -> String {
+    let columns: Vec<String> = statement
+        .columns
+        .iter()
+        .map(|col| dialect.quote_identifier(col))
+        .collect();
+    let rows: Vec<Vec<String>> = statement
+        .values
+        .iter()
+        .map(|row| row.iter().map(&format_value).collect())
+        .collect();
+    let table = dialect.quote_table(options.schema.as_deref(), &statement.table_name);
+    // The key as the sheet spells it, so it matches its quoted column
+    let key = options.upsert_key.as_deref().unwrap_or("id");
+    let key = dialect.quote_identifier(
+        statement
+            .columns
+            .iter()
+            .find(|column| column.eq_ignore_ascii_case(key))
+            .map_or(key, String::as_str),
+    );
+    let conflict = match (options.insert_mode, dialect) {
+        (InsertMode::Insert, _) => Conflict::Fail,
+        (InsertMode::Ignore, Dialect::Mysql) => Conflict::MysqlIgnore,
+        (InsertMode::Ignore, Dialect::Postgres) => Conflict::PostgresIgnore,
+        (InsertMode::Upsert, Dialect::Mysql) => Conflict::MysqlUpdate { key: &key },
+        (InsertMode::Upsert, Dialect::Postgres) => Conflict::PostgresUpdate { key: &key },
+    };
+    SqlFormatter::format_insert(&table, &columns, &rows, &options.layout, conflict)
+}
+
+fn format_value(value: &SqlValue, dialect: Dialect, options: &GeneratorOptions) -> String {
+    match value {
+        SqlValue::Null => "NULL".to_string(),
+        SqlValue::Text(s) => SqlFormatter::format_string_literal(s),
+        SqlValue::Number(f) => f.to_string(),
+        SqlValue::Integer(i) => i.to_string(),
+        SqlValue::Decimal(d) => d.clone(),
+        SqlValue::Boolean(b) => {
+            let bool_format = options
+                .bool_format
+                .unwrap_or_else(|| dialect.default_bool_format());
+            match (bool_format, b) {
+                (BoolFormat::Numeric, true) => "1",
+                (BoolFormat::Numeric, false) => "0",
+                (BoolFormat::Keyword, true) => "TRUE",
+                (BoolFormat::Keyword, false) => "FALSE",
+                (BoolFormat::Yn, true) => "'Y'",
+                (BoolFormat::Yn, false) => "'N'",
+            }
+            .to_string()
         }
+        SqlValue::DateTime(dt) => format!("'{dt}'"),
+        SqlValue::Json(json) => {
+            let literal = SqlFormatter::format_string_literal(json);
+            match dialect {
+                Dialect::Mysql => format!("CAST({literal} AS JSON)"),
+                Dialect::Postgres => format!("{literal}::jsonb"),
+            }
+        }
+        SqlValue::Duration(duration) => {
+            let duration = format_duration(duration);
+            match dialect {
+                Dialect::Mysql => format!("TIME '{duration}'"),
+                Dialect::Postgres => format!("INTERVAL '{duration}'"),
+            }
+        }
+        SqlValue::Uuid(uuid) => match dialect {
+            Dialect::Mysql => SqlFormatter::format_string_literal(uuid),
+            Dialect::Postgres => format!("{}::uuid", SqlFormatter::format_string_literal(uuid)),
+        },
+        SqlValue::Binary(bytes) => {
+            let hex: String = bytes.iter().map(|b| format!("{b:02x}")).collect();
+            match dialect {
+                Dialect::Mysql => format!("X'{}'", hex.to_uppercase()),
+                Dialect::Postgres => format!("decode('{hex}','hex')"),
+            }
+        }
+        SqlValue::Array(items) => match (dialect, options.array_style) {
+            (Dialect::Postgres, ArrayStyle::Constructor) => {
+                SqlFormatter::format_array_constructor(items)
+            }
+            (Dialect::Postgres, ArrayStyle::Literal) => SqlFormatter::format_array_literal(items),
+            // MySQL has no array type; store the list as a JSON array
+            (Dialect::Mysql, _) => {
+                let json = serde_json::to_string(items).unwrap_or_default();
+                format!(
+                    "CAST({} AS JSON)",
+                    SqlFormatter::format_string_literal(&json)
+                )
+            }
+        },
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::parser::data_model::SqlValue;
+    use crate::generator::column_filter::{ColumnFilter, ColumnSpec};
+    use crate::generator::mask::parse_mask;
+    use crate::generator::options::{OversizedCellPolicy, TableMapping};
+    use crate::generator::row_filter::parse_row_filter;
+    use crate::parser::data_model::{SqlValue, TableDefinition};
+    use calamine::{CellErrorType, Range};
 
     #[test]
+    #[allow(clippy::approx_constant)]
     fn test_format_sql_value_escaping() {
-        let generator = MySqlGenerator;
+        let generator = MySqlGenerator::default();
 
         assert_eq!(generator.format_sql_value(&SqlValue::Null), "NULL");
         assert_eq!(generator.format_sql_value(&SqlValue::Integer(42)), "42");
@@ -102,9 +961,104 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_bool_formats() {
+        let mut generator = MySqlGenerator::default();
+        generator.options.bool_format = Some(BoolFormat::Keyword);
+        assert_eq!(generator.format_sql_value(&SqlValue::Boolean(true)), "TRUE");
+        generator.options.bool_format = Some(BoolFormat::Yn);
+        assert_eq!(generator.format_sql_value(&SqlValue::Boolean(false)), "'N'");
+    }
+
+    #[test]
+    fn test_json_literals() {
+        let json = SqlValue::Json("{\"a\":\"it's\"}".to_string());
+        let mysql = MySqlGenerator::default();
+        assert_eq!(
+            mysql.format_sql_value(&json),
+            "CAST('{\"a\":\"it''s\"}' AS JSON)"
+        );
+        assert_eq!(
+            PostgresGenerator::default().format_sql_value(&json),
+            "'{\"a\":\"it''s\"}'::jsonb"
+        );
+    }
+
+    #[test]
+    fn test_array_values() {
+        let array = SqlValue::Array(vec!["red".to_string(), "green".to_string()]);
+        let mut postgres = PostgresGenerator::default();
+        assert_eq!(postgres.format_sql_value(&array), "ARRAY['red','green']");
+        assert_eq!(
+            postgres.format_sql_value(&SqlValue::Array(Vec::new())),
+            "'{}'"
+        );
+        postgres.options.array_style = ArrayStyle::Literal;
+        assert_eq!(postgres.format_sql_value(&array), "'{red,green}'");
+        assert_eq!(
+            MySqlGenerator::default().format_sql_value(&array),
+            "CAST('[\"red\",\"green\"]' AS JSON)"
+        );
+    }
+
+    #[test]
+    fn test_binary_values() {
+        let binary = SqlValue::Binary(vec![0xde, 0xad, 0xbe, 0xef]);
+        assert_eq!(
+            MySqlGenerator::default().format_sql_value(&binary),
+            "X'DEADBEEF'"
+        );
+        assert_eq!(
+            PostgresGenerator::default().format_sql_value(&binary),
+            "decode('deadbeef','hex')"
+        );
+    }
+
+    #[test]
+    fn test_duration_values() {
+        let duration = SqlValue::Duration(chrono::Duration::seconds(37 * 3600 + 5));
+        assert_eq!(
+            MySqlGenerator::default().format_sql_value(&duration),
+            "TIME '37:00:05'"
+        );
+        assert_eq!(
+            PostgresGenerator::default().format_sql_value(&duration),
+            "INTERVAL '37:00:05'"
+        );
+    }
+
+    #[test]
+    fn test_uuid_values() {
+        let uuid = SqlValue::Uuid("123e4567-e89b-12d3-a456-426614174000".to_string());
+        assert_eq!(
+            MySqlGenerator::default().format_sql_value(&uuid),
+            "'123e4567-e89b-12d3-a456-426614174000'"
+        );
+        assert_eq!(
+            PostgresGenerator::default().format_sql_value(&uuid),
+            "'123e4567-e89b-12d3-a456-426614174000'::uuid"
+        );
+    }
+
+    #[test]
+    fn test_postgres_statement() {
+        let statement = SqlStatement {
+            table_name: "users".to_string(),
+            columns: vec!["id".to_string(), "active".to_string()],
+            values: vec![vec![SqlValue::Integer(1), SqlValue::Boolean(true)]],
+            warnings: vec![],
+            stats: Default::default(),
+        };
+        let sql = PostgresGenerator::default().format_statement(&statement);
+        assert_eq!(
+            sql,
+            "INSERT INTO \"users\" (\"id\", \"active\") VALUES\n(1,TRUE);"
+        );
+    }
+
     #[test]
     fn test_sql_injection_prevention() {
-        let generator = MySqlGenerator;
+        let generator = MySqlGenerator::default();
         let malicious_text = "'; DROP TABLE users; --";
         let sql_value = SqlValue::Text(malicious_text.to_string());
 
@@ -114,7 +1068,7 @@ mod tests {
 
     #[test]
     fn test_unicode_in_sql_generation() {
-        let generator = MySqlGenerator;
+        let generator = MySqlGenerator::default();
         let japanese_text = "業務用";
         let sql_value = SqlValue::Text(japanese_text.to_string());
 
@@ -131,9 +1085,11 @@ mod tests {
                 vec![SqlValue::Integer(1), SqlValue::Text("John".to_string())],
                 vec![SqlValue::Integer(2), SqlValue::Text("Jane".to_string())],
             ],
+            warnings: vec![],
+            stats: Default::default(),
         };
 
-        let generator = MySqlGenerator;
+        let generator = MySqlGenerator::default();
         let sql = generator.format_statement(&statement);
 
         assert!(sql.contains("INSERT INTO `test_table`"));
@@ -143,17 +1099,839 @@ mod tests {
         assert!(sql.contains("(2,'Jane')"));
     }
 
+    #[test]
+    fn test_format_statement_schema_and_upsert() {
+        let statement = SqlStatement {
+            table_name: "users".to_string(),
+            columns: vec!["ID".to_string(), "name".to_string()],
+            values: vec![vec![
+                SqlValue::Integer(1),
+                SqlValue::Text("John".to_string()),
+            ]],
+            warnings: vec![],
+            stats: Default::default(),
+        };
+        let options = GeneratorOptions {
+            schema: Some("staging".to_string()),
+            insert_mode: InsertMode::Upsert,
+            ..GeneratorOptions::default()
+        };
+        assert_eq!(
+            PostgresGenerator::new(options.clone()).format_statement(&statement),
+            "INSERT INTO \"staging\".\"users\" (\"ID\", \"name\") VALUES\n(1,'John')\nON CONFLICT (\"ID\") DO UPDATE SET \"name\" = EXCLUDED.\"name\";"
+        );
+        assert!(MySqlGenerator::new(options)
+            .format_statement(&statement)
+            .starts_with("INSERT INTO `staging`.`users`"));
+    }
+
+    #[test]
+    fn test_generate_appends_fiscal_columns() {
+        let mut range = Range::new((0, 0), (1, 1));
+        range.set_value((0, 0), Data::String("id".to_string()));
+        range.set_value((0, 1), Data::String("order_date".to_string()));
+        range.set_value((1, 0), Data::Int(1));
+        range.set_value((1, 1), Data::DateTimeIso("2024-02-10T00:00:00".to_string()));
+        let data = WorkbookData::new(vec![SheetData::new("orders".to_string(), range)]);
+
+        let mut generator = MySqlGenerator::default();
+        generator.options.fiscal_derivations =
+            vec!["order_date->fy,fq start=April".parse().unwrap()];
+        let statements = generator.generate(&data).unwrap();
+
+        assert_eq!(statements[0].columns, ["id", "order_date", "fy", "fq"]);
+        assert_eq!(
+            statements[0].values[0][2..],
+            [SqlValue::Integer(2023), SqlValue::Integer(4)]
+        );
+    }
+
+    #[test]
+    fn test_table_mapping_renames_table_and_columns() {
+        let mut range = Range::new((0, 0), (1, 1));
+        range.set_value((0, 0), Data::String("Order ID".to_string()));
+        range.set_value((0, 1), Data::String("Total".to_string()));
+        range.set_value((1, 0), Data::Int(1));
+        range.set_value((1, 1), Data::String("$10.50".to_string()));
+        let data = WorkbookData::new(vec![SheetData::new("Orders".to_string(), range)]);
+
+        let mut generator = MySqlGenerator::default();
+        generator.options.table_mappings = vec![TableMapping {
+            sheets: vec!["orders".to_string()],
+            table: Some("sales_orders".to_string()),
+            columns: [
+                ("order id".to_string(), "id".to_string()),
+                ("Total".to_string(), "total".to_string()),
+            ]
+            .into(),
+            types: [("total".to_string(), ColumnType::Currency)].into(),
+            ..TableMapping::default()
+        }];
+        let statement = &generator.generate(&data).unwrap()[0];
+
+        assert_eq!(statement.table_name, "sales_orders");
+        assert_eq!(statement.columns, ["id", "total"]);
+        assert_eq!(statement.values[0][1], SqlValue::Number(10.5));
+    }
+
+    #[test]
+    fn test_rename_columns() {
+        let mut range = Range::new((0, 0), (1, 2));
+        range.set_value((0, 0), Data::String("Customer Name".to_string()));
+        range.set_value((0, 1), Data::String("E-mail".to_string()));
+        range.set_value((0, 2), Data::String("Order ID".to_string()));
+        let data = WorkbookData::new(vec![SheetData::new("Orders".to_string(), range)]);
+
+        let mut generator = MySqlGenerator::default();
+        generator.options.column_renames = vec![
+            ("customer name".to_string(), "customer_name".to_string()),
+            ("Order ID".to_string(), "order_id".to_string()),
+        ];
+        generator.options.table_mappings = vec![TableMapping {
+            sheets: vec!["Orders".to_string()],
+            columns: [
+                ("E-mail".to_string(), "email".to_string()),
+                ("Order ID".to_string(), "id".to_string()),
+            ]
+            .into(),
+            ..TableMapping::default()
+        }];
+        let statement = &generator.generate(&data).unwrap()[0];
+        assert_eq!(statement.columns, ["customer_name", "email", "order_id"]);
+    }
+
+    #[test]
+    fn test_snake_case_identifiers() {
+        let mut range = Range::new((0, 0), (1, 1));
+        range.set_value((0, 0), Data::String("CustomerID".to_string()));
+        range.set_value((0, 1), Data::String("Order Date".to_string()));
+        range.set_value((1, 0), Data::Int(7));
+        let data = WorkbookData::new(vec![SheetData::new("Order Lines".to_string(), range)]);
+
+        let mut generator = MySqlGenerator::default();
+        generator.options.identifiers.snake_case = true;
+        generator.options.column_types =
+            [("customer_id".to_string(), ColumnType::Text { width: None })].into();
+        let statement = &generator.generate(&data).unwrap()[0];
+        assert_eq!(statement.table_name, "order_lines");
+        assert_eq!(statement.columns, ["customer_id", "order_date"]);
+        assert_eq!(statement.values[0][0], SqlValue::Text("7".to_string()));
+    }
+
+    #[test]
+    fn test_sanitize_identifiers() {
+        let mut range = Range::new((0, 0), (1, 3));
+        for (col, header) in ["id", "E-mail", "E mail", "顧客"].iter().enumerate() {
+            range.set_value((0, col as u32), Data::String(header.to_string()));
+        }
+        range.set_value((1, 0), Data::Int(1));
+        let data = WorkbookData::new(vec![SheetData::new("Order List".to_string(), range)]);
+
+        let mut generator = MySqlGenerator::default();
+        generator.options.identifiers.sanitize = true;
+        let statement = &generator.generate(&data).unwrap()[0];
+        assert_eq!(statement.table_name, "Order_List");
+        assert_eq!(statement.columns, ["id", "E_mail", "E_mail_2", "column_4"]);
+        assert_eq!(
+            statement.warnings,
+            [
+                "Order List: sanitized identifiers: table 'Order List' -> Order_List, \
+              'E-mail' -> E_mail, 'E mail' -> E_mail_2, '顧客' -> column_4"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_reserved_words() {
+        let mut range = Range::new((0, 0), (1, 2));
+        for (col, header) in ["order", "Rank", "total"].iter().enumerate() {
+            range.set_value((0, col as u32), Data::String(header.to_string()));
+        }
+        range.set_value((1, 0), Data::Int(1));
+        let data = WorkbookData::new(vec![SheetData::new("group".to_string(), range)]);
+
+        let mut generator = MySqlGenerator::default();
+        let statement = &generator.generate(&data).unwrap()[0];
+        assert_eq!(statement.columns, ["order", "Rank", "total"]);
+        assert!(statement.warnings.is_empty());
+
+        generator.options.identifiers.reserved = ReservedWords::Rename;
+        let statement = &generator.generate(&data).unwrap()[0];
+        assert_eq!(statement.table_name, "group_");
+        assert_eq!(statement.columns, ["order_", "Rank_", "total"]);
+        assert_eq!(
+            statement.warnings,
+            ["group: renamed MySQL reserved word(s): group -> group_, order -> order_, Rank -> Rank_"]
+        );
+        // rank is not reserved in PostgreSQL
+        let postgres = PostgresGenerator::new(generator.options.clone());
+        assert_eq!(
+            postgres.generate(&data).unwrap()[0].columns,
+            ["order_", "Rank", "total"]
+        );
+
+        generator.options.identifiers.reserved = ReservedWords::Error;
+        assert_eq!(
+            generator.generate(&data).unwrap_err().to_string(),
+            "Sheet group uses MySQL reserved word(s) as names: group, order, Rank; \
+             rename them with --rename or pass --reserved-words quote or rename"
+        );
+    }
+
+    #[test]
+    fn test_duplicate_headers() {
+        let mut range = Range::new((0, 1), (1, 4));
+        for (col, header) in ["id", "amount", "note", "Amount"].iter().enumerate() {
+            range.set_value((0, col as u32 + 1), Data::String(header.to_string()));
+            range.set_value((1, col as u32 + 1), Data::Int(col as i64));
+        }
+        let data = WorkbookData::new(vec![SheetData::new("Orders".to_string(), range)]);
+
+        let mut generator = MySqlGenerator::default();
+        let statement = &generator.generate(&data).unwrap()[0];
+        assert_eq!(statement.columns, ["id", "amount", "note", "Amount_2"]);
+        assert_eq!(
+            statement.warnings,
+            ["Orders: duplicate column name(s) amount (C, E) inserted as amount, Amount_2"]
+        );
+
+        generator.options.identifiers.duplicates = DuplicateHeaders::Error;
+        assert_eq!(
+            generator.generate(&data).unwrap_err().to_string(),
+            "Sheet Orders has duplicate column names: amount (C, E); \
+             rename one with --rename 'E:E=NAME' or pass --duplicate-headers suffix"
+        );
+
+        generator.options.column_renames = vec![("E:E".to_string(), "amount_tax".to_string())];
+        let statement = &generator.generate(&data).unwrap()[0];
+        assert_eq!(statement.columns, ["id", "amount", "note", "amount_tax"]);
+    }
+
+    #[test]
+    fn test_per_sheet_header_row_and_filter() {
+        // Title and blank row above the headers, and a cancelled order
+        let mut range = Range::new((0, 0), (4, 1));
+        range.set_value((0, 0), Data::String("Q1 orders".to_string()));
+        range.set_value((2, 0), Data::String("id".to_string()));
+        range.set_value((2, 1), Data::String("status".to_string()));
+        range.set_value((3, 0), Data::Int(1));
+        range.set_value((3, 1), Data::String("open".to_string()));
+        range.set_value((4, 0), Data::Int(2));
+        range.set_value((4, 1), Data::String("cancelled".to_string()));
+        let mut customers = Range::new((0, 0), (1, 0));
+        customers.set_value((0, 0), Data::String("name".to_string()));
+        customers.set_value((1, 0), Data::String("Ann".to_string()));
+        let data = WorkbookData::new(vec![
+            SheetData::new("Orders".to_string(), range),
+            SheetData::new("Customers".to_string(), customers),
+        ]);
+
+        let mut generator = MySqlGenerator::default();
+        generator.options.table_mappings = vec![TableMapping {
+            sheets: vec!["orders".to_string()],
+            header_row: Some(3),
+            filter: Some(parse_row_filter(r#"status != "cancelled""#).unwrap()),
+            ..TableMapping::default()
+        }];
+        let statements = generator.generate(&data).unwrap();
+
+        assert_eq!(statements[0].columns, ["id", "status"]);
+        assert_eq!(
+            statements[0].values,
+            [[SqlValue::Integer(1), SqlValue::Text("open".to_string())]]
+        );
+        assert_eq!(statements[1].values, [[SqlValue::Text("Ann".to_string())]]);
+    }
+
+    #[test]
+    fn test_sheet_selection() {
+        let sheet = |name: &str| {
+            let mut range = Range::new((0, 0), (1, 0));
+            range.set_value((0, 0), Data::String("id".to_string()));
+            range.set_value((1, 0), Data::Int(1));
+            SheetData::new(name.to_string(), range)
+        };
+        let data = WorkbookData::new(vec![sheet("Orders"), sheet("Notes")]);
+
+        let mut generator = MySqlGenerator::default();
+        generator.options.sheet_filter.names = vec!["Orders".to_string()];
+        let statements = generator.generate(&data).unwrap();
+        assert_eq!(statements.len(), 1);
+        assert_eq!(statements[0].table_name, "Orders");
+
+        generator.options.sheet_filter.names = vec!["Invoices".to_string()];
+        assert!(matches!(
+            generator.generate(&data),
+            Err(GeneratorError::UnknownSheets(names)) if names == "Invoices"
+        ));
+    }
+
+    #[test]
+    fn test_named_ranges() {
+        let mut range = Range::new((0, 0), (3, 2));
+        range.set_value((0, 0), Data::String("Sales report".to_string()));
+        range.set_value((2, 1), Data::String("region".to_string()));
+        range.set_value((3, 1), Data::String("EMEA".to_string()));
+        range.set_value((3, 2), Data::String("scratch".to_string()));
+        let mut data = WorkbookData::new(vec![SheetData::new("Sheet1".to_string(), range)]);
+        data.defined_names = vec![
+            ("SalesData".to_string(), "Sheet1!$B$3:$B$4".to_string()),
+            ("Rate".to_string(), "0.08".to_string()),
+        ];
+
+        let mut generator = MySqlGenerator::default();
+        generator.options.named_ranges = vec!["SalesData".to_string()];
+        let statements = generator.generate(&data).unwrap();
+        assert_eq!(statements.len(), 1);
+        assert_eq!(statements[0].table_name, "SalesData");
+        assert_eq!(statements[0].columns, ["region"]);
+        assert_eq!(statements[0].values, [[SqlValue::Text("EMEA".to_string())]]);
+
+        generator.options.named_ranges = vec!["Rate".to_string()];
+        assert!(matches!(
+            generator.generate(&data),
+            Err(GeneratorError::InvalidNamedRange { .. })
+        ));
+        generator.options.named_ranges = vec!["Costs".to_string()];
+        assert!(matches!(
+            generator.generate(&data),
+            Err(GeneratorError::UnknownNamedRanges(names)) if names == "Costs"
+        ));
+    }
+
+    #[test]
+    fn test_tables_mode() {
+        let mut range = Range::new((0, 0), (4, 3));
+        range.set_value((0, 0), Data::String("notes".to_string()));
+        range.set_value((1, 1), Data::String("id".to_string()));
+        range.set_value((2, 1), Data::Int(1));
+        range.set_value((2, 3), Data::String("stray".to_string()));
+        range.set_value((4, 2), Data::String("sum".to_string()));
+        let mut other = Range::new((0, 0), (1, 0));
+        other.set_value((0, 0), Data::String("code".to_string()));
+        other.set_value((1, 0), Data::String("A".to_string()));
+        let mut data = WorkbookData::new(vec![
+            SheetData::new("Sheet1".to_string(), range),
+            SheetData::new("Codes".to_string(), other),
+        ]);
+        data.tables.push(TableDefinition {
+            name: "Orders".to_string(),
+            sheet: "Sheet1".to_string(),
+            area: ((1, 1), (2, 2)),
+        });
+
+        let mut generator = MySqlGenerator::default();
+        generator.options.tables = true;
+        let statements = generator.generate(&data).unwrap();
+        let tables: Vec<&str> = statements.iter().map(|s| s.table_name.as_str()).collect();
+        assert_eq!(tables, ["Orders", "Codes"]);
+        assert_eq!(statements[0].columns, ["id", ""]);
+        assert_eq!(
+            statements[0].values,
+            [[SqlValue::Integer(1), SqlValue::Null]]
+        );
+    }
+
+    #[test]
+    fn test_fill_down() {
+        let mut range = Range::new((0, 0), (3, 1));
+        range.set_value((0, 0), Data::String("Region".to_string()));
+        range.set_value((0, 1), Data::String("store".to_string()));
+        range.set_value((1, 0), Data::String("EMEA".to_string()));
+        range.set_value((1, 1), Data::Int(1));
+        range.set_value((2, 1), Data::Int(2));
+        range.set_value((3, 0), Data::String("APAC".to_string()));
+        let data = WorkbookData::new(vec![SheetData::new("stores".to_string(), range)]);
+
+        let mut generator = MySqlGenerator::default();
+        generator.options.fill_down = vec!["region".to_string()];
+        let statements = generator.generate(&data).unwrap();
+        let region = |row: usize| statements[0].values[row][0].clone();
+        assert_eq!(region(1), SqlValue::Text("EMEA".to_string()));
+        assert_eq!(region(2), SqlValue::Text("APAC".to_string()));
+        assert_eq!(statements[0].values[2][1], SqlValue::Null);
+    }
+
+    #[test]
+    fn test_masks() {
+        let mut range = Range::new((0, 0), (2, 1));
+        range.set_value((0, 0), Data::String("E-mail".to_string()));
+        range.set_value((0, 1), Data::String("plan".to_string()));
+        range.set_value((1, 0), Data::String("a@example.com".to_string()));
+        range.set_value((1, 1), Data::String("pro".to_string()));
+        range.set_value((2, 1), Data::String("free".to_string()));
+        let data = WorkbookData::new(vec![SheetData::new("users".to_string(), range)]);
+
+        let mut generator = MySqlGenerator::default();
+        generator.options.masks = vec![parse_mask("e-mail").unwrap()];
+        // Filters see the values before they are masked
+        generator.options.row_filter =
+            Some(parse_row_filter("`E-mail` = 'a@example.com'").unwrap());
+        let statements = generator.generate(&data).unwrap();
+        assert_eq!(
+            statements[0].values,
+            [[
+                SqlValue::Text("***".to_string()),
+                SqlValue::Text("pro".to_string())
+            ]]
+        );
+    }
+
+    #[test]
+    fn test_stop_at_blank_row() {
+        let mut range = Range::new((0, 0), (4, 1));
+        range.set_value((0, 0), Data::String("id".to_string()));
+        range.set_value((1, 0), Data::Int(1));
+        range.set_value((2, 1), Data::String(" ".to_string()));
+        range.set_value(
+            (4, 0),
+            Data::String("Notes: figures are provisional".to_string()),
+        );
+        let data = WorkbookData::new(vec![SheetData::new("ids".to_string(), range)]);
+
+        let mut generator = MySqlGenerator::default();
+        assert_eq!(generator.generate(&data).unwrap()[0].values.len(), 4);
+        generator.options.stop_at_blank_row = true;
+        let statements = generator.generate(&data).unwrap();
+        assert_eq!(
+            statements[0].values,
+            [[SqlValue::Integer(1), SqlValue::Null]]
+        );
+    }
+
+    #[test]
+    fn test_sample() {
+        let mut range = Range::new((0, 0), (5, 0));
+        range.set_value((0, 0), Data::String("id".to_string()));
+        for row in 1..=5 {
+            range.set_value((row, 0), Data::Int(row as i64));
+        }
+        let data = WorkbookData::new(vec![SheetData::new("ids".to_string(), range)]);
+
+        let mut generator = MySqlGenerator::default();
+        generator.options.sample = Some(2);
+        generator.options.row_filter = Some(parse_row_filter("id > 1").unwrap());
+        let statements = generator.generate(&data).unwrap();
+        assert_eq!(
+            statements[0].values,
+            [[SqlValue::Integer(2)], [SqlValue::Integer(3)]]
+        );
+    }
+
+    #[test]
+    fn test_limit() {
+        let sheet = |name: &str, rows: i64| {
+            let mut range = Range::new((0, 0), (rows as u32, 0));
+            range.set_value((0, 0), Data::String("id".to_string()));
+            for row in 1..=rows {
+                range.set_value((row as u32, 0), Data::Int(row));
+            }
+            SheetData::new(name.to_string(), range)
+        };
+        let data = WorkbookData::new(vec![sheet("Jan", 3), sheet("Feb", 3), sheet("Mar", 2)]);
+
+        let mut generator = MySqlGenerator::default();
+        generator.options.limit = Some(2);
+        let rows: Vec<(usize, usize)> = generator
+            .generate(&data)
+            .unwrap()
+            .iter()
+            .map(|statement| (statement.values.len(), statement.stats.limited_rows))
+            .collect();
+        assert_eq!(rows, [(2, 1), (2, 1), (2, 0)]);
+
+        // Sheets sharing a table share the limit
+        generator.options.table_name = Some("sales".to_string());
+        generator.options.limit = Some(4);
+        let rows: Vec<usize> = generator
+            .generate(&data)
+            .unwrap()
+            .iter()
+            .map(|statement| statement.values.len())
+            .collect();
+        assert_eq!(rows, [3, 1]);
+    }
+
+    #[test]
+    fn test_statements_iterator() {
+        let mut range = Range::new((0, 0), (1, 0));
+        range.set_value((0, 0), Data::String("id".to_string()));
+        range.set_value((1, 0), Data::Int(1));
+        let data = WorkbookData::new(vec![
+            SheetData::new("Jan".to_string(), range.clone()),
+            SheetData::new("Empty".to_string(), Range::empty()),
+            SheetData::new("Mar".to_string(), range),
+        ]);
+
+        // Sheets are converted as the iterator advances; it ends at the first error
+        let mut generator = MySqlGenerator::default();
+        let mut statements = generator.statements(&data);
+        assert_eq!(statements.next().unwrap().unwrap().table_name, "Jan");
+        assert!(statements.next().unwrap().is_err());
+        assert!(statements.next().is_none());
+
+        generator.options.keep_going = true;
+        let mut statements = generator.statements(&data);
+        let tables: Vec<String> = statements
+            .by_ref()
+            .map(|statement| statement.unwrap().table_name)
+            .collect();
+        assert_eq!(tables, ["Jan", "Mar"]);
+        assert_eq!(statements.problems().len(), 1);
+
+        let empty = WorkbookData::new(Vec::new());
+        let mut statements = generator.statements(&empty);
+        assert!(matches!(
+            statements.next(),
+            Some(Err(GeneratorError::NoData))
+        ));
+        assert!(statements.next().is_none());
+    }
+
+    #[test]
+    fn test_union_sheets() {
+        let month = |name: &str, id: i64, header: &str| {
+            let mut range = Range::new((0, 0), (1, 1));
+            range.set_value((0, 0), Data::String("id".to_string()));
+            range.set_value((0, 1), Data::String(header.to_string()));
+            range.set_value((1, 0), Data::Int(id));
+            SheetData::new(name.to_string(), range)
+        };
+        let mut data = WorkbookData::new(vec![month("Jan", 1, "total"), month("Feb", 2, "total")]);
+
+        let mut generator = MySqlGenerator::default();
+        generator.options.union_sheets = true;
+        generator.options.table_name = Some("sales".to_string());
+        generator.options.table_prefix = "stg_".to_string();
+        generator.options.table_suffix = "_import".to_string();
+        let statements = generator.generate(&data).unwrap();
+        assert_eq!(statements.len(), 1);
+        assert_eq!(statements[0].table_name, "stg_sales_import");
+        assert_eq!(
+            statements[0].values,
+            [
+                [SqlValue::Integer(1), SqlValue::Null],
+                [SqlValue::Integer(2), SqlValue::Null]
+            ]
+        );
+
+        data.sheets.push(month("Mar", 3, "amount"));
+        match generator.generate(&data) {
+            Err(GeneratorError::UnionColumns { sheet, first, .. }) => {
+                assert_eq!((sheet.as_str(), first.as_str()), ("Mar", "Jan"));
+            }
+            other => panic!("unexpected result: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_tenant_check() {
+        let mut range = Range::new((0, 0), (2, 0));
+        range.set_value((0, 0), Data::String("tenant_id".to_string()));
+        range.set_value((1, 0), Data::Int(42));
+        range.set_value((2, 0), Data::String("42".to_string()));
+        let mut data = WorkbookData::new(vec![SheetData::new("orders".to_string(), range)]);
+
+        let mut generator = MySqlGenerator::default();
+        generator.options.tenant = Some(TenantCheck {
+            column: "tenant_id".to_string(),
+            expected: "42".to_string(),
+        });
+        assert!(generator.generate(&data).is_ok());
+
+        data.sheets[0].range.set_value((2, 0), Data::Int(7));
+        match generator.generate(&data) {
+            Err(GeneratorError::TenantMismatch { count, cells, .. }) => {
+                assert_eq!(count, 1);
+                assert_eq!(cells, "orders!A3");
+            }
+            other => panic!("unexpected result: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_tenant_mismatch_cells() {
+        // A title row, then notes | tenant_id | kind
+        let mut range = Range::new((0, 0), (5, 2));
+        range.set_value((0, 0), Data::String("Export".to_string()));
+        for (col, header) in ["notes", "tenant_id", "kind"].into_iter().enumerate() {
+            range.set_value((1, col as u32), Data::String(header.to_string()));
+        }
+        for (row, (tenant, kind)) in [(42, "a"), (7, "b"), (42, "a"), (7, "a")]
+            .into_iter()
+            .enumerate()
+        {
+            range.set_value((row as u32 + 2, 1), Data::Int(tenant));
+            range.set_value((row as u32 + 2, 2), Data::String(kind.to_string()));
+        }
+        let data = WorkbookData::new(vec![SheetData::new("orders".to_string(), range)]);
+
+        let mut generator = MySqlGenerator::default();
+        generator.options.skip_rows = 1;
+        generator.options.column_filter.skip = vec![ColumnSpec::Name("notes".to_string())];
+        generator.options.row_filter = Some(parse_row_filter("kind = 'a'").unwrap());
+        generator.options.tenant = Some(TenantCheck {
+            column: "tenant_id".to_string(),
+            expected: "42".to_string(),
+        });
+        match generator.generate(&data) {
+            // The tenant 7 row of kind b is filtered out
+            Err(GeneratorError::TenantMismatch { count, cells, .. }) => {
+                assert_eq!(count, 1);
+                assert_eq!(cells, "orders!B6");
+            }
+            other => panic!("unexpected result: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_error_cells() {
+        let mut range = Range::new((0, 0), (2, 1));
+        range.set_value((0, 0), Data::String("id".to_string()));
+        range.set_value((0, 1), Data::String("ratio".to_string()));
+        range.set_value((1, 0), Data::Int(1));
+        range.set_value((1, 1), Data::Error(CellErrorType::Div0));
+        range.set_value((2, 0), Data::Int(2));
+        range.set_value((2, 1), Data::Float(0.5));
+        let data = WorkbookData::new(vec![SheetData::new("stats".to_string(), range)]);
+
+        let mut generator = MySqlGenerator::default();
+        let statements = generator.generate(&data).unwrap();
+        assert_eq!(statements[0].values[0][1], SqlValue::Null);
+        assert_eq!(
+            statements[0].warnings,
+            ["stats: 1 error cell(s) emitted as NULL: stats!B2 #DIV/0!"]
+        );
+
+        generator.options.on_cell_error = CellErrorPolicy::SkipRow;
+        let statements = generator.generate(&data).unwrap();
+        assert_eq!(
+            statements[0].values,
+            [[SqlValue::Integer(2), SqlValue::Number(0.5)]]
+        );
+
+        generator.options.on_cell_error = CellErrorPolicy::Fail;
+        assert!(matches!(
+            generator.generate(&data),
+            Err(GeneratorError::CellErrors { count: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_keep_going() {
+        let mut range = Range::new((0, 0), (3, 1));
+        range.set_value((0, 0), Data::String("id".to_string()));
+        range.set_value((0, 1), Data::String("note".to_string()));
+        range.set_value((1, 0), Data::Int(1));
+        range.set_value((1, 1), Data::Error(CellErrorType::NA));
+        range.set_value((2, 0), Data::Int(2));
+        range.set_value((2, 1), Data::String("far too long".to_string()));
+        range.set_value((3, 0), Data::Int(3));
+        range.set_value((3, 1), Data::String("ok".to_string()));
+        let mut reserved = Range::new((0, 0), (1, 0));
+        reserved.set_value((0, 0), Data::String("order".to_string()));
+        reserved.set_value((1, 0), Data::Int(1));
+        let data = WorkbookData::new(vec![
+            SheetData::new("notes".to_string(), range),
+            SheetData::new("orders".to_string(), reserved),
+        ]);
+
+        let mut generator = MySqlGenerator::default();
+        generator.options.on_cell_error = CellErrorPolicy::Fail;
+        generator.options.max_cell_size = Some(4);
+        generator.options.oversized_cell_policy = OversizedCellPolicy::Fail;
+        generator.options.identifiers.reserved = ReservedWords::Error;
+        assert!(matches!(
+            generator.generate(&data),
+            Err(GeneratorError::OversizedCell { .. })
+        ));
+
+        generator.options.keep_going = true;
+        let (statements, problems) = generator.generate_all(&data).unwrap();
+        assert_eq!(statements.len(), 1);
+        assert_eq!(
+            statements[0].values,
+            [[SqlValue::Integer(3), SqlValue::Text("ok".to_string())]]
+        );
+        assert_eq!(
+            (
+                statements[0].stats.failed_rows,
+                statements[0].stats.error_rows
+            ),
+            (1, 1)
+        );
+        assert_eq!(
+            problems,
+            [
+                "Cell notes!B3 is 12 bytes, exceeding the limit of 4; row skipped",
+                "notes: skipped rows with 1 error cell(s): notes!B2 #N/A",
+                "orders: Sheet orders uses MySQL reserved word(s) as names: order; \
+                 rename them with --rename or pass --reserved-words quote or rename; sheet skipped",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_column_filter() {
+        let mut range = Range::new((0, 0), (1, 3));
+        for (col, header) in ["id", "name", "total", "scratch"].iter().enumerate() {
+            range.set_value((0, col as u32), Data::String(header.to_string()));
+            range.set_value((1, col as u32), Data::Int(col as i64));
+        }
+        range.set_value((1, 3), Data::Error(CellErrorType::NA));
+        let data = WorkbookData::new(vec![SheetData::new("orders".to_string(), range)]);
+
+        let mut generator = MySqlGenerator::default();
+        generator.options.on_cell_error = CellErrorPolicy::Fail;
+        generator.options.column_filter = ColumnFilter {
+            specs: vec![
+                ColumnSpec::Range(0, 1),
+                ColumnSpec::Name("TOTAL".to_string()),
+            ],
+            ..ColumnFilter::default()
+        };
+        let statements = generator.generate(&data).unwrap();
+        assert_eq!(statements[0].columns, ["id", "name", "total"]);
+        assert_eq!(
+            statements[0].values,
+            [[
+                SqlValue::Integer(0),
+                SqlValue::Integer(1),
+                SqlValue::Integer(2)
+            ]]
+        );
+
+        generator.options.column_filter = ColumnFilter {
+            specs: vec![ColumnSpec::Range(10, 12)],
+            ..ColumnFilter::default()
+        };
+        assert!(matches!(
+            generator.generate(&data),
+            Err(GeneratorError::NoData)
+        ));
+
+        generator.options.on_cell_error = CellErrorPolicy::Null;
+        generator.options.column_filter = ColumnFilter {
+            skip: vec![
+                ColumnSpec::Name("scratch".to_string()),
+                ColumnSpec::Name("Name".to_string()),
+            ],
+            ..ColumnFilter::default()
+        };
+        let statements = generator.generate(&data).unwrap();
+        assert_eq!(statements[0].columns, ["id", "total"]);
+
+        generator.options.column_filter = ColumnFilter {
+            skip: vec![ColumnSpec::Range(0, 3)],
+            ..ColumnFilter::default()
+        };
+        assert!(matches!(
+            generator.generate(&data),
+            Err(GeneratorError::NoData)
+        ));
+    }
+
+    #[test]
+    fn test_row_filter() {
+        let mut range = Range::new((0, 0), (3, 1));
+        range.set_value((0, 0), Data::String("Status".to_string()));
+        range.set_value((0, 1), Data::String("amount".to_string()));
+        for (row, (status, amount)) in [("open", 5), ("deleted", 3), ("open", 0)]
+            .iter()
+            .enumerate()
+        {
+            range.set_value((row as u32 + 1, 0), Data::String(status.to_string()));
+            range.set_value((row as u32 + 1, 1), Data::Int(*amount));
+        }
+        range.set_value((3, 1), Data::Error(CellErrorType::NA));
+        let data = WorkbookData::new(vec![SheetData::new("orders".to_string(), range)]);
+
+        let mut generator = MySqlGenerator::default();
+        generator.options.on_cell_error = CellErrorPolicy::Fail;
+        generator.options.row_filter =
+            Some(parse_row_filter(r#"status != "deleted" && amount > 0"#).unwrap());
+        let statements = generator.generate(&data).unwrap();
+        assert_eq!(
+            statements[0].values,
+            [[SqlValue::Text("open".to_string()), SqlValue::Integer(5)]]
+        );
+
+        generator.options.row_filter = Some(parse_row_filter("total > 0").unwrap());
+        assert!(matches!(
+            generator.generate(&data),
+            Err(GeneratorError::FilterColumn { column, .. }) if column == "total"
+        ));
+    }
+
+    #[test]
+    fn test_row_filter_before_side_effects() {
+        let mut range = Range::new((0, 0), (2, 1));
+        range.set_value((0, 0), Data::String("status".to_string()));
+        range.set_value((0, 1), Data::String("note".to_string()));
+        range.set_value((1, 0), Data::String("open".to_string()));
+        range.set_value((1, 1), Data::String("ok".to_string()));
+        range.set_value((2, 0), Data::String("done".to_string()));
+        range.set_value((2, 1), Data::String("far too long".to_string()));
+        let data = WorkbookData::new(vec![SheetData::new("notes".to_string(), range)]);
+        let dir = tempfile::tempdir().unwrap();
+
+        let mut generator = MySqlGenerator::default();
+        generator.options.row_filter = Some(parse_row_filter("status = 'open'").unwrap());
+        generator.options.max_cell_size = Some(4);
+        // The left-out row neither fails the run nor writes a file
+        generator.options.oversized_cell_policy = OversizedCellPolicy::Fail;
+        let statements = generator.generate(&data).unwrap();
+        assert_eq!(statements[0].values.len(), 1);
+        assert_eq!(statements[0].stats.filtered_rows, 1);
+        generator.options.oversized_cell_policy = OversizedCellPolicy::Externalize;
+        generator.options.externalize_dir = Some(dir.path().to_path_buf());
+        generator.generate(&data).unwrap();
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
+
     #[test]
     fn test_table_name_sanitization() {
         let statement = SqlStatement {
             table_name: "table with spaces".to_string(),
             columns: vec!["id".to_string()],
             values: vec![vec![SqlValue::Integer(1)]],
+            warnings: vec![],
+            stats: Default::default(),
         };
 
-        let generator = MySqlGenerator;
+        let generator = MySqlGenerator::default();
         let sql = generator.format_statement(&statement);
 
         assert!(sql.contains("`table with spaces`"));
     }
+
+    #[test]
+    fn test_table_from_file_name() {
+        let sheet = |name: &str| {
+            let mut range = Range::new((0, 0), (1, 0));
+            range.set_value((0, 0), Data::String("id".to_string()));
+            range.set_value((1, 0), Data::Int(1));
+            SheetData::new(name.to_string(), range)
+        };
+        let mut generator = MySqlGenerator::default();
+        generator.options.file_table_name = Some("customer_list".to_string());
+
+        let data = WorkbookData::new(vec![sheet("Sheet1"), sheet("Orders")]);
+        let tables: Vec<String> = generator
+            .generate(&data)
+            .unwrap()
+            .into_iter()
+            .map(|statement| statement.table_name)
+            .collect();
+        assert_eq!(tables, ["customer_list", "Orders"]);
+
+        let data = WorkbookData::new(vec![sheet("Sheet1"), sheet("Sheet2")]);
+        let tables: Vec<String> = generator
+            .generate(&data)
+            .unwrap()
+            .into_iter()
+            .map(|statement| statement.table_name)
+            .collect();
+        assert_eq!(tables, ["customer_list_1", "customer_list_2"]);
+    }
 }
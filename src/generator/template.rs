@@ -0,0 +1,127 @@
+// Target schema templates (--target-template)
+use crate::generator::options::TableMapping;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+const BUILTIN_TEMPLATES: &[(&str, &str)] = &[
+    (
+        "wordpress-users",
+        include_str!("../../templates/wordpress-users.toml"),
+    ),
+    (
+        "customer-order-line-item",
+        include_str!("../../templates/customer-order-line-item.toml"),
+    ),
+];
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SchemaTemplate {
+    #[serde(default)]
+    pub description: String,
+    /// Table settings keyed by sheet name
+    #[serde(default)]
+    pub sheets: BTreeMap<String, TemplateSheet>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct TemplateSheet {
+    /// Other sheet names this entry applies to
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    #[serde(flatten)]
+    pub mapping: TableMapping,
+}
+
+impl SchemaTemplate {
+    /// Load a built-in template by name, or a TOML template file by path
+    pub fn load(name_or_path: &str) -> Result<Self, String> {
+        let content = match BUILTIN_TEMPLATES
+            .iter()
+            .find(|(name, _)| *name == name_or_path)
+        {
+            Some((_, content)) => content.to_string(),
+            None => std::fs::read_to_string(Path::new(name_or_path)).map_err(|e| {
+                format!(
+                    "'{name_or_path}' is neither a built-in template ({}) nor a readable file: {e}",
+                    builtin_names().join(", ")
+                )
+            })?,
+        };
+        toml::from_str(&content).map_err(|e| format!("invalid template {name_or_path}: {e}"))
+    }
+
+    /// Table mappings with every sheet name and alias they apply to
+    pub fn into_mappings(self) -> Vec<TableMapping> {
+        self.sheets
+            .into_iter()
+            .map(|(sheet, entry)| {
+                let mut mapping = entry.mapping;
+                mapping.sheets.push(sheet);
+                mapping.sheets.extend(entry.aliases);
+                mapping
+            })
+            .collect()
+    }
+}
+
+pub fn builtin_names() -> Vec<&'static str> {
+    BUILTIN_TEMPLATES.iter().map(|(name, _)| *name).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::column_type::ColumnType;
+
+    #[test]
+    fn test_builtin_templates_parse() {
+        for name in builtin_names() {
+            let template = SchemaTemplate::load(name).unwrap();
+            assert!(!template.sheets.is_empty(), "{name} has no sheets");
+        }
+    }
+
+    #[test]
+    fn test_template_mappings() {
+        let mappings = SchemaTemplate::load("customer-order-line-item")
+            .unwrap()
+            .into_mappings();
+        let orders = mappings.iter().find(|m| m.applies_to("Orders")).unwrap();
+        assert_eq!(orders.table.as_deref(), Some("orders"));
+        assert_eq!(orders.column_name("Order Date"), "ordered_at");
+        assert_eq!(orders.column_name("notes"), "notes");
+        assert_eq!(orders.types.get("total"), Some(&ColumnType::Currency));
+    }
+
+    #[test]
+    fn test_per_sheet_settings() {
+        let template: SchemaTemplate = toml::from_str(
+            r#"
+            [sheets.Orders]
+            table = "orders"
+            header_row = 3
+            filter = 'status != "cancelled"'
+            exclude = ["Memo"]
+
+            [sheets.Orders.types]
+            total = "currency"
+            "#,
+        )
+        .unwrap();
+        let orders = &template.into_mappings()[0];
+        assert_eq!(orders.header_row, Some(3));
+        assert!(orders.filter.is_some());
+        assert!(orders.excludes(" memo"));
+        assert_eq!(orders.types.get("total"), Some(&ColumnType::Currency));
+
+        let invalid = toml::from_str::<SchemaTemplate>("[sheets.Orders]\nfilter = 'status =='");
+        assert!(invalid.is_err());
+    }
+
+    #[test]
+    fn test_unknown_template() {
+        let err = SchemaTemplate::load("no-such-template").unwrap_err();
+        assert!(err.contains("wordpress-users"));
+    }
+}
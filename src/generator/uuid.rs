@@ -0,0 +1,88 @@
+// UUID recognition and normalization
+use crate::generator::options::UuidFormat;
+
+/// Parse a UUID written as `8-4-4-4-12` hex groups, 32 bare hex digits,
+/// `{...}` or `urn:uuid:...`, returning its canonical lowercase hyphenated form.
+pub fn parse_uuid(text: &str) -> Option<String> {
+    let text = text.trim();
+    let inner = text
+        .strip_prefix('{')
+        .and_then(|t| t.strip_suffix('}'))
+        .or_else(|| text.strip_prefix("urn:uuid:"))
+        .unwrap_or(text);
+
+    let digits: String = match inner.len() {
+        32 => inner.to_string(),
+        36 => {
+            let groups: Vec<&str> = inner.split('-').collect();
+            let lengths: Vec<usize> = groups.iter().map(|g| g.len()).collect();
+            if lengths != [8, 4, 4, 4, 12] {
+                return None;
+            }
+            groups.concat()
+        }
+        _ => return None,
+    };
+    if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    let digits = digits.to_ascii_lowercase();
+    Some(format!(
+        "{}-{}-{}-{}-{}",
+        &digits[0..8],
+        &digits[8..12],
+        &digits[12..16],
+        &digits[16..20],
+        &digits[20..32]
+    ))
+}
+
+/// Whether undeclared text looks enough like a UUID to be treated as one;
+/// bare 32-digit hex is too easily a hash or token to qualify.
+pub fn looks_like_uuid(text: &str) -> bool {
+    let text = text.trim();
+    text.len() == 36 && text.matches('-').count() == 4 && parse_uuid(text).is_some()
+}
+
+/// Render a valid UUID in the requested format
+pub fn format_uuid(text: &str, canonical: String, format: UuidFormat) -> String {
+    match format {
+        UuidFormat::Preserve => text.trim().to_string(),
+        UuidFormat::Lower => canonical,
+        UuidFormat::Upper => canonical.to_ascii_uppercase(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_uuid() {
+        let canonical = Some("123e4567-e89b-12d3-a456-426614174000".to_string());
+        assert_eq!(
+            parse_uuid("123E4567-E89B-12D3-A456-426614174000"),
+            canonical
+        );
+        assert_eq!(parse_uuid("123e4567e89b12d3a456426614174000"), canonical);
+        assert_eq!(
+            parse_uuid("{123e4567-e89b-12d3-a456-426614174000}"),
+            canonical
+        );
+        assert_eq!(
+            parse_uuid("urn:uuid:123e4567-e89b-12d3-a456-426614174000"),
+            canonical
+        );
+        assert_eq!(parse_uuid("123e4567-e89b-12d3-a456-42661417400g"), None);
+        assert_eq!(parse_uuid("123e4567e-89b-12d3-a456-426614174000"), None);
+        assert_eq!(parse_uuid("not a uuid"), None);
+    }
+
+    #[test]
+    fn test_looks_like_uuid() {
+        assert!(looks_like_uuid("123e4567-e89b-12d3-a456-426614174000"));
+        assert!(!looks_like_uuid("123e4567e89b12d3a456426614174000"));
+        assert!(!looks_like_uuid("2024-01-05"));
+    }
+}
@@ -0,0 +1,820 @@
+// Cell to SQL value conversion honoring GeneratorOptions
+use crate::errors::GeneratorError;
+use crate::generator::column_type::{
+    decode_binary, format_text, parse_boolean, parse_currency, scale_percent, BinaryEncoding,
+    ColumnType,
+};
+use crate::generator::duration::parse_iso_duration;
+use crate::generator::era_date::parse_era_date;
+use crate::generator::locale::parse_localized_number;
+use crate::generator::options::{EmptyStringPolicy, GeneratorOptions, OversizedCellPolicy};
+use crate::generator::uuid::{format_uuid, looks_like_uuid, parse_uuid};
+use crate::parser::data_model::{format_excel_datetime, SqlValue};
+use calamine::Data;
+use std::path::Path;
+
+const TRUNCATION_MARKER: &str = "...[truncated]";
+
+pub struct ValueConverter<'a> {
+    options: &'a GeneratorOptions,
+    /// Write externalized cells to their files
+    write_files: bool,
+}
+
+impl<'a> ValueConverter<'a> {
+    pub fn new(options: &'a GeneratorOptions) -> Self {
+        Self {
+            options,
+            write_files: !options.preview,
+        }
+    }
+
+    /// A converter giving the same values without writing any file, to test
+    /// a row before converting it for good
+    pub fn probe(&self) -> Self {
+        Self {
+            options: self.options,
+            write_files: false,
+        }
+    }
+
+    /// Convert a single cell of a column declared as `column_type`;
+    /// `cell_ref` (e.g. `Sheet1!B12`) is used in warnings.
+    pub fn convert(
+        &self,
+        cell: &Data,
+        column_type: Option<&ColumnType>,
+        cell_ref: &str,
+        warnings: &mut Vec<String>,
+    ) -> Result<SqlValue, GeneratorError> {
+        let value = self.normalize_whitespace(self.read_cell(cell));
+        let value = self.apply_null_values(value);
+        let value = match column_type {
+            Some(column_type) => self.apply_column_type(value, column_type, cell_ref, warnings),
+            None => self.detect_uuid(self.parse_text_number(value)),
+        };
+        self.limit_size(value, cell_ref, warnings)
+    }
+
+    fn apply_column_type(
+        &self,
+        value: SqlValue,
+        column_type: &ColumnType,
+        cell_ref: &str,
+        warnings: &mut Vec<String>,
+    ) -> SqlValue {
+        match (column_type, &value) {
+            (ColumnType::Currency, SqlValue::Text(text)) => {
+                match parse_currency(text, self.options.number_locale) {
+                    Some(number) => number,
+                    None => {
+                        warnings.push(format!("{cell_ref}: '{text}' is not a currency amount"));
+                        value
+                    }
+                }
+            }
+            (ColumnType::Boolean, SqlValue::Null) => value,
+            (ColumnType::Boolean, _) => match parse_boolean(&value) {
+                Some(b) => SqlValue::Boolean(b),
+                None => {
+                    warnings.push(format!("{cell_ref}: {value} is not a boolean"));
+                    value
+                }
+            },
+            (ColumnType::EraDate, SqlValue::Text(text)) => match parse_era_date(text) {
+                Some(date) => SqlValue::DateTime(date.format("%Y-%m-%d").to_string()),
+                None => {
+                    warnings.push(format!("{cell_ref}: '{text}' is not a Japanese era date"));
+                    value
+                }
+            },
+            (ColumnType::Json, SqlValue::Text(text)) => {
+                if serde_json::from_str::<serde_json::Value>(text).is_ok() {
+                    SqlValue::Json(text.clone())
+                } else {
+                    warnings.push(format!("{cell_ref}: cell is not valid JSON"));
+                    value
+                }
+            }
+            (ColumnType::Array { separator }, SqlValue::Text(text)) => SqlValue::Array(
+                text.split(separator.as_str())
+                    .map(|item| item.trim().to_string())
+                    .filter(|item| !item.is_empty())
+                    .collect(),
+            ),
+            (ColumnType::Binary(encoding), SqlValue::Text(text)) => {
+                match decode_binary(text, *encoding) {
+                    Some(bytes) => SqlValue::Binary(bytes),
+                    None => {
+                        let encoding = match encoding {
+                            BinaryEncoding::Hex => "hex",
+                            BinaryEncoding::Base64 => "base64",
+                        };
+                        warnings.push(format!("{cell_ref}: '{text}' is not valid {encoding}"));
+                        value
+                    }
+                }
+            }
+            (ColumnType::Text { width }, _) => format_text(&value, *width)
+                .map(SqlValue::Text)
+                .unwrap_or(value),
+            (ColumnType::Uuid, SqlValue::Text(text)) => match parse_uuid(text) {
+                Some(canonical) => {
+                    SqlValue::Uuid(format_uuid(text, canonical, self.options.uuid_format))
+                }
+                None => {
+                    warnings.push(format!("{cell_ref}: '{text}' is not a UUID"));
+                    value
+                }
+            },
+            (ColumnType::Uuid, SqlValue::Null) => value,
+            (ColumnType::Uuid, _) => {
+                warnings.push(format!("{cell_ref}: {value} is not a UUID"));
+                value
+            }
+            (ColumnType::Percent(_), SqlValue::Null) => value,
+            (ColumnType::Percent(scale), _) => match scale_percent(&value, *scale) {
+                Some(scaled) => scaled,
+                None => {
+                    warnings.push(format!("{cell_ref}: {value} is not a percentage"));
+                    value
+                }
+            },
+            _ => value,
+        }
+    }
+
+    fn read_cell(&self, cell: &Data) -> SqlValue {
+        let precision = self.options.datetime_precision;
+        match cell {
+            Data::DateTime(dt) if precision > 0 && !dt.is_duration() => {
+                format_excel_datetime(dt.as_f64(), precision)
+                    .map(SqlValue::DateTime)
+                    .unwrap_or_else(|| SqlValue::from(cell))
+            }
+            _ if self.options.durations_as_text => SqlValue::from(cell),
+            Data::DurationIso(text) => parse_iso_duration(text)
+                .map(SqlValue::Duration)
+                .unwrap_or_else(|| SqlValue::from(cell)),
+            Data::DateTime(dt) => dt
+                .as_duration()
+                .filter(|_| dt.is_duration())
+                .map(SqlValue::Duration)
+                .unwrap_or_else(|| SqlValue::from(cell)),
+            _ => SqlValue::from(cell),
+        }
+    }
+
+    fn normalize_whitespace(&self, value: SqlValue) -> SqlValue {
+        let SqlValue::Text(text) = value else {
+            return value;
+        };
+        let text = if self.options.collapse_whitespace {
+            let mut collapsed = String::with_capacity(text.len());
+            let mut in_whitespace = false;
+            for c in text.chars() {
+                if c.is_whitespace() {
+                    if !in_whitespace {
+                        collapsed.push(' ');
+                    }
+                    in_whitespace = true;
+                } else {
+                    collapsed.push(c);
+                    in_whitespace = false;
+                }
+            }
+            collapsed
+        } else {
+            text
+        };
+        if self.options.trim {
+            SqlValue::Text(text.trim().to_string())
+        } else {
+            SqlValue::Text(text)
+        }
+    }
+
+    fn apply_null_values(&self, value: SqlValue) -> SqlValue {
+        match &value {
+            SqlValue::Text(text)
+                if text.is_empty() && self.options.empty_string == EmptyStringPolicy::Null =>
+            {
+                SqlValue::Null
+            }
+            SqlValue::Text(text)
+                if self
+                    .options
+                    .null_values
+                    .iter()
+                    .any(|token| token == text.trim()) =>
+            {
+                SqlValue::Null
+            }
+            _ => value,
+        }
+    }
+
+    /// Text such as "1.234,56" as a number; codes with leading zeros such as
+    /// "00123" stay text, as they do in CSV files
+    fn parse_text_number(&self, value: SqlValue) -> SqlValue {
+        match (&value, self.options.number_locale) {
+            (SqlValue::Text(text), _) if has_leading_zero(text) => value,
+            (SqlValue::Text(text), Some(locale)) => {
+                parse_localized_number(text, locale).unwrap_or(value)
+            }
+            _ => value,
+        }
+    }
+
+    fn detect_uuid(&self, value: SqlValue) -> SqlValue {
+        match &value {
+            SqlValue::Text(text) if self.options.detect_uuids && looks_like_uuid(text) => {
+                let canonical = parse_uuid(text).unwrap_or_default();
+                SqlValue::Uuid(format_uuid(text, canonical, self.options.uuid_format))
+            }
+            _ => value,
+        }
+    }
+
+    fn limit_size(
+        &self,
+        value: SqlValue,
+        cell_ref: &str,
+        warnings: &mut Vec<String>,
+    ) -> Result<SqlValue, GeneratorError> {
+        let Some(limit) = self.options.max_cell_size else {
+            return Ok(value);
+        };
+        let text = match &value {
+            SqlValue::Text(text) if text.len() > limit => text,
+            _ => return Ok(value),
+        };
+
+        match self.options.oversized_cell_policy {
+            OversizedCellPolicy::Truncate => {
+                let mut cut = limit;
+                while !text.is_char_boundary(cut) {
+                    cut -= 1;
+                }
+                warnings.push(format!(
+                    "{cell_ref}: {} bytes exceeds limit of {limit}, truncated",
+                    text.len()
+                ));
+                Ok(SqlValue::Text(format!(
+                    "{}{TRUNCATION_MARKER}",
+                    &text[..cut]
+                )))
+            }
+            OversizedCellPolicy::Externalize => {
+                let dir = self
+                    .options
+                    .externalize_dir
+                    .as_deref()
+                    .unwrap_or_else(|| Path::new("."));
+                let file_name: String = cell_ref
+                    .chars()
+                    .map(|c| if c.is_alphanumeric() { c } else { '_' })
+                    .collect();
+                let path = dir.join(format!("{file_name}.txt"));
+                if self.write_files {
+                    std::fs::create_dir_all(dir)?;
+                    std::fs::write(&path, text)?;
+                }
+                warnings.push(format!(
+                    "{cell_ref}: {} bytes exceeds limit of {limit}, written to {}",
+                    text.len(),
+                    path.display()
+                ));
+                Ok(SqlValue::Text(path.display().to_string()))
+            }
+            OversizedCellPolicy::Fail => Err(GeneratorError::OversizedCell {
+                cell: cell_ref.to_string(),
+                size: text.len(),
+                limit,
+            }),
+        }
+    }
+}
+
+/// "00123" or "-07", but not "0" or "0,5"
+fn has_leading_zero(text: &str) -> bool {
+    let text = text.trim();
+    let digits = text.strip_prefix('-').unwrap_or(text).as_bytes();
+    digits.len() > 1 && digits[0] == b'0' && digits[1].is_ascii_digit()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::locale::NumberLocale;
+    use calamine::{ExcelDateTime, ExcelDateTimeType};
+
+    fn options(policy: OversizedCellPolicy) -> GeneratorOptions {
+        GeneratorOptions {
+            max_cell_size: Some(4),
+            oversized_cell_policy: policy,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_number_locale_applies_to_text_only() {
+        let options = GeneratorOptions {
+            number_locale: Some(NumberLocale::De),
+            ..Default::default()
+        };
+        let converter = ValueConverter::new(&options);
+        let mut warnings = Vec::new();
+        let parsed = converter
+            .convert(
+                &Data::String("1.234,56".to_string()),
+                None,
+                "S!A2",
+                &mut warnings,
+            )
+            .unwrap();
+        assert_eq!(parsed, SqlValue::Number(1234.56));
+        let kept = converter
+            .convert(
+                &Data::String("ABC-1".to_string()),
+                None,
+                "S!A2",
+                &mut warnings,
+            )
+            .unwrap();
+        assert_eq!(kept, SqlValue::Text("ABC-1".to_string()));
+        for code in ["00123", "-0042"] {
+            let kept = converter
+                .convert(&Data::String(code.to_string()), None, "S!A2", &mut warnings)
+                .unwrap();
+            assert_eq!(kept, SqlValue::Text(code.to_string()));
+        }
+        let parsed = converter
+            .convert(
+                &Data::String("0,5".to_string()),
+                None,
+                "S!A2",
+                &mut warnings,
+            )
+            .unwrap();
+        assert_eq!(parsed, SqlValue::Number(0.5));
+    }
+
+    #[test]
+    fn test_currency_column() {
+        let options = GeneratorOptions {
+            column_types: [("price".to_string(), ColumnType::Currency)].into(),
+            ..Default::default()
+        };
+        let converter = ValueConverter::new(&options);
+        let mut warnings = Vec::new();
+        let price = converter
+            .convert(
+                &Data::String("$1,299.00".to_string()),
+                options.column_types.get("price"),
+                "S!A2",
+                &mut warnings,
+            )
+            .unwrap();
+        assert_eq!(price, SqlValue::Number(1299.0));
+        let other = converter
+            .convert(
+                &Data::String("$1,299.00".to_string()),
+                options.column_types.get("note"),
+                "S!B2",
+                &mut warnings,
+            )
+            .unwrap();
+        assert_eq!(other, SqlValue::Text("$1,299.00".to_string()));
+        converter
+            .convert(
+                &Data::String("TBD".to_string()),
+                options.column_types.get("price"),
+                "S!A3",
+                &mut warnings,
+            )
+            .unwrap();
+        assert_eq!(warnings, ["S!A3: 'TBD' is not a currency amount"]);
+    }
+
+    #[test]
+    fn test_percent_column() {
+        let options = GeneratorOptions {
+            column_types: [(
+                "rate".to_string(),
+                "percent(2)".parse::<ColumnType>().unwrap(),
+            )]
+            .into(),
+            ..Default::default()
+        };
+        let converter = ValueConverter::new(&options);
+        let mut warnings = Vec::new();
+        let rate = converter
+            .convert(
+                &Data::Float(0.15),
+                options.column_types.get("rate"),
+                "S!A2",
+                &mut warnings,
+            )
+            .unwrap();
+        assert_eq!(rate, SqlValue::Decimal("15.00".to_string()));
+        let empty = converter
+            .convert(
+                &Data::Empty,
+                options.column_types.get("rate"),
+                "S!A3",
+                &mut warnings,
+            )
+            .unwrap();
+        assert_eq!(empty, SqlValue::Null);
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn test_null_values() {
+        let options = GeneratorOptions {
+            null_values: vec!["N/A".to_string(), "なし".to_string()],
+            ..Default::default()
+        };
+        let converter = ValueConverter::new(&options);
+        let mut warnings = Vec::new();
+        for token in ["N/A", " なし "] {
+            let value = converter
+                .convert(
+                    &Data::String(token.to_string()),
+                    None,
+                    "S!A2",
+                    &mut warnings,
+                )
+                .unwrap();
+            assert_eq!(value, SqlValue::Null);
+        }
+        let kept = converter
+            .convert(
+                &Data::String("n/a".to_string()),
+                None,
+                "S!A2",
+                &mut warnings,
+            )
+            .unwrap();
+        assert_eq!(kept, SqlValue::Text("n/a".to_string()));
+    }
+
+    #[test]
+    fn test_era_date_column() {
+        let options = GeneratorOptions {
+            column_types: [("birthday".to_string(), ColumnType::EraDate)].into(),
+            ..Default::default()
+        };
+        let converter = ValueConverter::new(&options);
+        let mut warnings = Vec::new();
+        let value = converter
+            .convert(
+                &Data::String("令和6年1月5日".to_string()),
+                options.column_types.get("birthday"),
+                "S!A2",
+                &mut warnings,
+            )
+            .unwrap();
+        assert_eq!(value, SqlValue::DateTime("2024-01-05".to_string()));
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn test_empty_string_policy() {
+        let mut options = GeneratorOptions::default();
+        let empty = Data::String(String::new());
+        let value = ValueConverter::new(&options)
+            .convert(&empty, None, "S!A2", &mut vec![])
+            .unwrap();
+        assert_eq!(value, SqlValue::Text(String::new()));
+
+        options.empty_string = EmptyStringPolicy::Null;
+        let value = ValueConverter::new(&options)
+            .convert(&empty, None, "S!A2", &mut vec![])
+            .unwrap();
+        assert_eq!(value, SqlValue::Null);
+    }
+
+    #[test]
+    fn test_whitespace_normalization() {
+        let cell = Data::String("\u{a0} New\u{a0}\u{a0}York \t".to_string());
+        let convert = |options: &GeneratorOptions| {
+            ValueConverter::new(options)
+                .convert(&cell, None, "S!A2", &mut vec![])
+                .unwrap()
+        };
+
+        let mut options = GeneratorOptions {
+            trim: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            convert(&options),
+            SqlValue::Text("New\u{a0}\u{a0}York".to_string())
+        );
+        options.collapse_whitespace = true;
+        assert_eq!(convert(&options), SqlValue::Text("New York".to_string()));
+        options.trim = false;
+        assert_eq!(convert(&options), SqlValue::Text(" New York ".to_string()));
+    }
+
+    #[test]
+    fn test_json_column() {
+        let options = GeneratorOptions {
+            column_types: [("attrs".to_string(), ColumnType::Json)].into(),
+            ..Default::default()
+        };
+        let converter = ValueConverter::new(&options);
+        let mut warnings = Vec::new();
+        let json = converter
+            .convert(
+                &Data::String("{\"a\": [1, 2]}".to_string()),
+                options.column_types.get("attrs"),
+                "S!A2",
+                &mut warnings,
+            )
+            .unwrap();
+        assert_eq!(json, SqlValue::Json("{\"a\": [1, 2]}".to_string()));
+        let text = converter
+            .convert(
+                &Data::String("{oops".to_string()),
+                options.column_types.get("attrs"),
+                "S!A3",
+                &mut warnings,
+            )
+            .unwrap();
+        assert_eq!(text, SqlValue::Text("{oops".to_string()));
+        assert_eq!(warnings, ["S!A3: cell is not valid JSON"]);
+    }
+
+    #[test]
+    fn test_array_column() {
+        let options = GeneratorOptions {
+            column_types: [("tags".to_string(), "array(,)".parse().unwrap())].into(),
+            ..Default::default()
+        };
+        let value = ValueConverter::new(&options)
+            .convert(
+                &Data::String("red, green,,blue".to_string()),
+                options.column_types.get("tags"),
+                "S!A2",
+                &mut vec![],
+            )
+            .unwrap();
+        assert_eq!(
+            value,
+            SqlValue::Array(vec![
+                "red".to_string(),
+                "green".to_string(),
+                "blue".to_string()
+            ])
+        );
+        // A cell holding only separators is an empty list
+        let value = ValueConverter::new(&options)
+            .convert(
+                &Data::String(" , ,".to_string()),
+                options.column_types.get("tags"),
+                "S!A3",
+                &mut vec![],
+            )
+            .unwrap();
+        assert_eq!(value, SqlValue::Array(Vec::new()));
+    }
+
+    #[test]
+    fn test_binary_column() {
+        let options = GeneratorOptions {
+            column_types: [("avatar".to_string(), "binary(hex)".parse().unwrap())].into(),
+            ..Default::default()
+        };
+        let converter = ValueConverter::new(&options);
+        let mut warnings = Vec::new();
+        let value = converter
+            .convert(
+                &Data::String("0xCAFE".to_string()),
+                options.column_types.get("avatar"),
+                "S!A2",
+                &mut warnings,
+            )
+            .unwrap();
+        assert_eq!(value, SqlValue::Binary(vec![0xca, 0xfe]));
+        converter
+            .convert(
+                &Data::String("xyz".to_string()),
+                options.column_types.get("avatar"),
+                "S!A3",
+                &mut warnings,
+            )
+            .unwrap();
+        assert_eq!(warnings, ["S!A3: 'xyz' is not valid hex"]);
+    }
+
+    #[test]
+    fn test_text_column_restores_leading_zeros() {
+        let options = GeneratorOptions {
+            column_types: [("zip".to_string(), ColumnType::Text { width: Some(5) })].into(),
+            ..Default::default()
+        };
+        let value = ValueConverter::new(&options)
+            .convert(
+                &Data::Float(123.0),
+                options.column_types.get("zip"),
+                "S!A2",
+                &mut vec![],
+            )
+            .unwrap();
+        assert_eq!(value, SqlValue::Text("00123".to_string()));
+    }
+
+    #[test]
+    fn test_uuid_column() {
+        let options = GeneratorOptions {
+            column_types: [("id".to_string(), ColumnType::Uuid)].into(),
+            uuid_format: crate::generator::options::UuidFormat::Lower,
+            ..Default::default()
+        };
+        let converter = ValueConverter::new(&options);
+        let mut warnings = Vec::new();
+        let value = converter
+            .convert(
+                &Data::String("{123E4567-E89B-12D3-A456-426614174000}".to_string()),
+                options.column_types.get("id"),
+                "S!A2",
+                &mut warnings,
+            )
+            .unwrap();
+        assert_eq!(
+            value,
+            SqlValue::Uuid("123e4567-e89b-12d3-a456-426614174000".to_string())
+        );
+        converter
+            .convert(
+                &Data::Int(7),
+                options.column_types.get("id"),
+                "S!A3",
+                &mut warnings,
+            )
+            .unwrap();
+        assert_eq!(warnings, ["S!A3: 7 is not a UUID"]);
+    }
+
+    #[test]
+    fn test_detect_uuids() {
+        let uuid = "123E4567-E89B-12D3-A456-426614174000";
+        let mut options = GeneratorOptions::default();
+        let convert = |options: &GeneratorOptions| {
+            ValueConverter::new(options)
+                .convert(&Data::String(uuid.to_string()), None, "S!A2", &mut vec![])
+                .unwrap()
+        };
+        assert_eq!(convert(&options), SqlValue::Text(uuid.to_string()));
+        options.detect_uuids = true;
+        assert_eq!(convert(&options), SqlValue::Uuid(uuid.to_string()));
+    }
+
+    #[test]
+    fn test_duration_cells() {
+        let mut options = GeneratorOptions::default();
+        let convert = |options: &GeneratorOptions, cell: &Data| {
+            ValueConverter::new(options)
+                .convert(cell, None, "S!A2", &mut vec![])
+                .unwrap()
+        };
+        let iso = Data::DurationIso("PT1H30M".to_string());
+        let excel = Data::DateTime(ExcelDateTime::new(1.5, ExcelDateTimeType::TimeDelta, false));
+        assert_eq!(
+            convert(&options, &iso),
+            SqlValue::Duration(chrono::Duration::minutes(90))
+        );
+        assert_eq!(
+            convert(&options, &excel),
+            SqlValue::Duration(chrono::Duration::hours(36))
+        );
+        options.durations_as_text = true;
+        assert_eq!(
+            convert(&options, &iso),
+            SqlValue::Text("PT1H30M".to_string())
+        );
+    }
+
+    #[test]
+    fn test_datetime_precision() {
+        let options = GeneratorOptions {
+            datetime_precision: 3,
+            ..Default::default()
+        };
+        let serial = 45296.5 + 0.25 / 86400.0;
+        let cell = Data::DateTime(ExcelDateTime::new(
+            serial,
+            ExcelDateTimeType::DateTime,
+            false,
+        ));
+        let value = ValueConverter::new(&options)
+            .convert(&cell, None, "S!A2", &mut vec![])
+            .unwrap();
+        assert_eq!(
+            value,
+            SqlValue::DateTime("2024-01-05 12:00:00.250".to_string())
+        );
+    }
+
+    #[test]
+    fn test_small_cells_untouched() {
+        let options = options(OversizedCellPolicy::Fail);
+        let converter = ValueConverter::new(&options);
+        let mut warnings = Vec::new();
+        let value = converter
+            .convert(
+                &Data::String("abcd".to_string()),
+                None,
+                "S!A2",
+                &mut warnings,
+            )
+            .unwrap();
+        assert_eq!(value, SqlValue::Text("abcd".to_string()));
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn test_truncate_respects_char_boundary() {
+        let options = options(OversizedCellPolicy::Truncate);
+        let converter = ValueConverter::new(&options);
+        let mut warnings = Vec::new();
+        let value = converter
+            .convert(
+                &Data::String("業務用".to_string()),
+                None,
+                "S!A2",
+                &mut warnings,
+            )
+            .unwrap();
+        assert_eq!(value, SqlValue::Text(format!("業{TRUNCATION_MARKER}")));
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].starts_with("S!A2"));
+    }
+
+    #[test]
+    fn test_fail_policy() {
+        let options = options(OversizedCellPolicy::Fail);
+        let converter = ValueConverter::new(&options);
+        let result = converter.convert(
+            &Data::String("abcdef".to_string()),
+            None,
+            "S!A2",
+            &mut vec![],
+        );
+        assert!(matches!(
+            result,
+            Err(GeneratorError::OversizedCell {
+                size: 6,
+                limit: 4,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_externalize_policy() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut options = options(OversizedCellPolicy::Externalize);
+        options.externalize_dir = Some(dir.path().to_path_buf());
+        let converter = ValueConverter::new(&options);
+        let value = converter
+            .convert(
+                &Data::String("abcdef".to_string()),
+                None,
+                "S!A2",
+                &mut vec![],
+            )
+            .unwrap();
+        let path = dir.path().join("S_A2.txt");
+        assert_eq!(value, SqlValue::Text(path.display().to_string()));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "abcdef");
+
+        // A preview names the file without writing it
+        std::fs::remove_file(&path).unwrap();
+        options.preview = true;
+        let value = ValueConverter::new(&options)
+            .convert(
+                &Data::String("abcdef".to_string()),
+                None,
+                "S!A2",
+                &mut vec![],
+            )
+            .unwrap();
+        assert_eq!(value, SqlValue::Text(path.display().to_string()));
+        assert!(!path.exists());
+        options.preview = false;
+        ValueConverter::new(&options)
+            .probe()
+            .convert(
+                &Data::String("abcdef".to_string()),
+                None,
+                "S!A2",
+                &mut vec![],
+            )
+            .unwrap();
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,122 @@
+// Zip archives of workbooks, converted member by member
+use crate::errors::InputError;
+use crate::input::file_handler::is_supported_file;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// A workbook or delimited file inside an archive
+#[derive(Debug)]
+pub struct ArchiveMember {
+    /// Path inside the archive, e.g. `2024/january.xlsx`
+    pub name: String,
+    pub contents: Vec<u8>,
+}
+
+/// Largest member read into memory; every member is held until the archive
+/// has been converted, so a compression bomb must not fill it
+const MAX_MEMBER_BYTES: u64 = 1 << 30;
+
+pub fn is_zip_archive(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.to_string_lossy().eq_ignore_ascii_case("zip"))
+}
+
+/// Supported members of the zip archive at `path`, in archive order; macOS
+/// resource forks (`__MACOSX/`) and Excel lock files (`~$book.xlsx`) are skipped
+pub fn read_archive(path: &Path) -> Result<Vec<ArchiveMember>, InputError> {
+    let file = std::fs::File::open(path)
+        .map_err(|_| InputError::FileNotFound(path.display().to_string()))?;
+    let invalid =
+        |e: zip::result::ZipError| InputError::Archive(format!("{}: {e}", path.display()));
+    let mut archive = zip::ZipArchive::new(file).map_err(invalid)?;
+
+    let mut members = Vec::new();
+    for index in 0..archive.len() {
+        let mut entry = archive.by_index(index).map_err(invalid)?;
+        let Some(name) = entry
+            .enclosed_name()
+            .map(|name| name.to_string_lossy().into_owned())
+        else {
+            continue;
+        };
+        let member_path = Path::new(&name);
+        let skipped = entry.is_dir()
+            || name.starts_with("__MACOSX/")
+            || member_path
+                .file_name()
+                .is_some_and(|file| file.to_string_lossy().starts_with("~$"))
+            || !is_supported_file(member_path);
+        if skipped {
+            continue;
+        }
+        // The header's size is not trusted for the allocation
+        let mut contents = Vec::new();
+        entry
+            .by_ref()
+            .take(MAX_MEMBER_BYTES + 1)
+            .read_to_end(&mut contents)?;
+        if contents.len() as u64 > MAX_MEMBER_BYTES {
+            return Err(InputError::Archive(format!(
+                "{}: {name} is larger than {} MiB uncompressed",
+                path.display(),
+                MAX_MEMBER_BYTES >> 20
+            )));
+        }
+        members.push(ArchiveMember { name, contents });
+    }
+    Ok(members)
+}
+
+/// Where the SQL for `member` of `archive` goes: under `output_dir`, or a
+/// directory named after the archive next to it
+pub fn member_output_path(archive: &Path, member: &str, output_dir: Option<&Path>) -> PathBuf {
+    let dir = match output_dir {
+        Some(dir) => dir.to_path_buf(),
+        None => archive.with_extension(""),
+    };
+    dir.join(member).with_extension("sql")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_read_archive() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("monthly.zip");
+        let mut writer = zip::ZipWriter::new(std::fs::File::create(&path).unwrap());
+        for (name, contents) in [
+            ("2024/january.csv", "id\n1\n"),
+            ("2024/~$february.xlsx", ""),
+            ("__MACOSX/2024/._january.csv", ""),
+            ("README.txt", "monthly data"),
+        ] {
+            writer
+                .start_file(name, zip::write::SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(contents.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap();
+
+        let members = read_archive(&path).unwrap();
+        assert_eq!(members.len(), 1);
+        assert_eq!(members[0].name, "2024/january.csv");
+        assert_eq!(members[0].contents, b"id\n1\n");
+        assert!(is_zip_archive(&path));
+    }
+
+    #[test]
+    fn test_member_output_path() {
+        let archive = Path::new("data/monthly.zip");
+        assert_eq!(
+            member_output_path(archive, "2024/january.xlsx", None),
+            PathBuf::from("data/monthly/2024/january.sql")
+        );
+        assert_eq!(
+            member_output_path(archive, "january.xlsx", Some(Path::new("sql"))),
+            PathBuf::from("sql/january.sql")
+        );
+    }
+}
@@ -0,0 +1,162 @@
+// Input discovery for converting many files in one run
+use crate::errors::InputError;
+use crate::input::file_handler::is_supported_file;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Supported files matching a glob pattern such as `exports/**/*.xlsx`,
+/// sorted; Excel lock files are skipped as in [`walk_dir`]
+pub fn expand_glob(pattern: &str) -> Result<Vec<PathBuf>, InputError> {
+    let paths = glob::glob(pattern)
+        .map_err(|e| InputError::Pattern(format!("{pattern}: {e}")))?
+        .filter_map(Result::ok)
+        .filter(|path| path.is_file() && is_supported_file(path) && !is_lock_file(path));
+    let mut files: Vec<PathBuf> = paths.collect();
+    files.sort();
+    Ok(files)
+}
+
+/// Supported files in `dir` (and its subdirectories when `recursive`), sorted;
+/// Excel lock files (`~$book.xlsx`) are skipped
+pub fn walk_dir(dir: &Path, recursive: bool) -> Result<Vec<PathBuf>, InputError> {
+    let mut files = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        for entry in std::fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                if recursive {
+                    pending.push(path);
+                }
+            } else if is_supported_file(&path) && !is_lock_file(&path) {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Excel's `~$book.xlsx` marking a workbook as open
+fn is_lock_file(path: &Path) -> bool {
+    path.file_name()
+        .is_some_and(|name| name.to_string_lossy().starts_with("~$"))
+}
+
+/// The directory part of `pattern` before its first wildcard, which the
+/// matched files' outputs are laid out relative to
+pub fn glob_root(pattern: &str) -> PathBuf {
+    Path::new(pattern)
+        .components()
+        .take_while(|part| {
+            !part
+                .as_os_str()
+                .to_string_lossy()
+                .contains(['*', '?', '[', '{'])
+        })
+        .collect()
+}
+
+/// The SQL file for `input` found under `root`, at the same relative
+/// location under `output_dir` (or next to the input without one)
+pub fn mirrored_output_path(input: &Path, root: &Path, output_dir: Option<&Path>) -> PathBuf {
+    let output = input.with_extension("sql");
+    match (output_dir, output.strip_prefix(root)) {
+        (Some(dir), Ok(relative)) => dir.join(relative),
+        _ => output,
+    }
+}
+
+/// Fail when two inputs, such as `x.xlsx` and `x.csv`, map to the same output
+pub fn check_distinct_outputs<'a>(
+    jobs: impl IntoIterator<Item = (&'a Path, &'a Path)>,
+) -> Result<(), InputError> {
+    let mut seen: HashMap<&Path, &Path> = HashMap::new();
+    for (input, output) in jobs {
+        if let Some(first) = seen.insert(output, input) {
+            return Err(InputError::OutputCollision {
+                output: output.display().to_string(),
+                first: first.display().to_string(),
+                second: input.display().to_string(),
+            });
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_glob() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("2024")).unwrap();
+        for file in ["a.xlsx", "~$a.xlsx", "2024/b.csv", "2024/notes.txt"] {
+            std::fs::write(dir.path().join(file), "").unwrap();
+        }
+        let pattern = format!("{}/**/*", dir.path().display());
+        let files = expand_glob(&pattern).unwrap();
+        assert_eq!(
+            files,
+            [dir.path().join("2024/b.csv"), dir.path().join("a.xlsx")]
+        );
+        assert!(expand_glob("[").is_err());
+    }
+
+    #[test]
+    fn test_walk_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("q1/jan")).unwrap();
+        for file in [
+            "top.xlsx",
+            "~$top.xlsx",
+            "q1/jan/orders.csv",
+            "q1/readme.md",
+        ] {
+            std::fs::write(dir.path().join(file), "").unwrap();
+        }
+        assert_eq!(
+            walk_dir(dir.path(), false).unwrap(),
+            [dir.path().join("top.xlsx")]
+        );
+        assert_eq!(
+            walk_dir(dir.path(), true).unwrap(),
+            [
+                dir.path().join("q1/jan/orders.csv"),
+                dir.path().join("top.xlsx")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_mirrored_output_path() {
+        let input = Path::new("imports/q1/jan/orders.xlsx");
+        assert_eq!(
+            mirrored_output_path(input, Path::new("imports"), Some(Path::new("sql"))),
+            Path::new("sql/q1/jan/orders.sql")
+        );
+        assert_eq!(
+            mirrored_output_path(input, Path::new("imports"), None),
+            Path::new("imports/q1/jan/orders.sql")
+        );
+    }
+
+    #[test]
+    fn test_glob_outputs() {
+        let root = glob_root("exports/*/orders*.xlsx");
+        assert_eq!(root, Path::new("exports"));
+        assert_eq!(glob_root("*.csv"), Path::new(""));
+        let output =
+            |input: &str| mirrored_output_path(Path::new(input), &root, Some(Path::new("out")));
+        assert_eq!(output("exports/a/x.xlsx"), Path::new("out/a/x.sql"));
+        assert_eq!(output("exports/b/x.xlsx"), Path::new("out/b/x.sql"));
+
+        let (xlsx, csv, sql) = (Path::new("x.xlsx"), Path::new("x.csv"), Path::new("x.sql"));
+        assert!(check_distinct_outputs([(xlsx, sql)]).is_ok());
+        assert!(matches!(
+            check_distinct_outputs([(xlsx, sql), (csv, sql)]),
+            Err(InputError::OutputCollision { .. })
+        ));
+    }
+}
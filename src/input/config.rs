@@ -0,0 +1,282 @@
+// Defaults read from xlsx2sql.toml or --config; command-line flags override them
+use crate::errors::InputError;
+use crate::generator::template::SchemaTemplate;
+use crate::input::rules::Rules;
+use std::collections::BTreeMap;
+use std::path::Path;
+use toml::{Table, Value};
+
+pub const DEFAULT_CONFIG_FILE: &str = "xlsx2sql.toml";
+
+/// Options that may be written as a table, `[rename]` with `"E-mail" = "email"`
+/// standing for `rename = ["E-mail=email"]`
+const PAIR_OPTIONS: [&str; 1] = ["rename"];
+
+/// A parsed configuration file
+#[derive(Debug, Clone, Default)]
+pub struct Config {
+    /// Option values keyed by long flag name
+    pub options: Vec<(String, Value)>,
+    /// `[profile.NAME]` tables: options overriding the top-level ones under --profile NAME
+    pub profiles: BTreeMap<String, Vec<(String, Value)>>,
+    /// `[sheets.NAME]` tables, laid out as in a --target-template file
+    pub sheets: Option<SchemaTemplate>,
+    /// `[rules.NAME]` tables checked by `xlsx2sql validate`
+    pub rules: Rules,
+}
+
+impl Config {
+    pub fn load(path: &Path) -> Result<Self, InputError> {
+        let content = std::fs::read_to_string(path)?;
+        Self::parse(&content).map_err(|e| InputError::Config(format!("{}: {}", path.display(), e)))
+    }
+
+    fn parse(content: &str) -> Result<Self, String> {
+        let mut table: Table = toml::from_str(content).map_err(|e| e.to_string())?;
+        pairs_to_lists(&mut table)?;
+        let sheets = match table.remove("sheets") {
+            Some(sheets) => {
+                let mut template = Table::new();
+                template.insert("sheets".to_string(), sheets);
+                Some(
+                    Value::Table(template)
+                        .try_into::<SchemaTemplate>()
+                        .map_err(|e| format!("[sheets]: {e}"))?,
+                )
+            }
+            None => None,
+        };
+        let rules = match table.remove("rules") {
+            Some(rules) => rules.try_into().map_err(|e| format!("[rules]: {e}"))?,
+            None => Rules::new(),
+        };
+        let mut profiles = BTreeMap::new();
+        if let Some(entries) = table.remove("profile") {
+            let Value::Table(entries) = entries else {
+                return Err("profile: expected [profile.NAME] tables".to_string());
+            };
+            for (name, options) in entries {
+                let Value::Table(mut options) = options else {
+                    return Err(format!("profile.{name}: expected a table of options"));
+                };
+                pairs_to_lists(&mut options).map_err(|e| format!("profile.{name}.{e}"))?;
+                profiles.insert(name, options.into_iter().collect());
+            }
+        }
+        Ok(Config {
+            options: table.into_iter().collect(),
+            profiles,
+            sheets,
+            rules,
+        })
+    }
+
+    /// The top-level options, with those of `profile` taking their place
+    pub fn options_for(&self, profile: Option<&str>) -> Result<Vec<(String, Value)>, String> {
+        let Some(profile) = profile else {
+            return Ok(self.options.clone());
+        };
+        let overrides = self.profiles.get(profile).ok_or_else(|| {
+            let names: Vec<&str> = self.profiles.keys().map(String::as_str).collect();
+            match names.is_empty() {
+                true => format!("no profile '{profile}'; the file defines none"),
+                false => format!("no profile '{profile}' (defined: {})", names.join(", ")),
+            }
+        })?;
+        let mut options: Vec<(String, Value)> = self
+            .options
+            .iter()
+            .filter(|(key, _)| !overrides.iter().any(|(overridden, _)| overridden == key))
+            .cloned()
+            .collect();
+        options.extend(overrides.iter().cloned());
+        Ok(options)
+    }
+
+    /// The command-line arguments `key` stands for: `true` is a bare flag,
+    /// `false` none at all, and an array repeats the flag for each item
+    pub fn option_args(key: &str, value: &Value) -> Result<Vec<String>, String> {
+        let flag = format!("--{key}");
+        let scalar = |value: &Value| match value {
+            Value::String(s) => Ok(s.clone()),
+            Value::Integer(n) => Ok(n.to_string()),
+            Value::Float(n) => Ok(n.to_string()),
+            Value::Datetime(d) => Ok(d.to_string()),
+            _ => Err(format!("{key}: expected a string, number or list of them")),
+        };
+        match value {
+            Value::Boolean(true) => Ok(vec![flag]),
+            Value::Boolean(false) => Ok(Vec::new()),
+            Value::Array(items) => items
+                .iter()
+                .map(|item| Ok([flag.clone(), scalar(item)?]))
+                .collect::<Result<Vec<_>, String>>()
+                .map(|pairs| pairs.concat()),
+            value => Ok(vec![flag, scalar(value)?]),
+        }
+    }
+}
+
+fn pairs_to_lists(options: &mut Table) -> Result<(), String> {
+    for key in PAIR_OPTIONS {
+        let Some(Value::Table(pairs)) = options.get(key) else {
+            continue;
+        };
+        let items = pairs
+            .iter()
+            .map(|(name, value)| match value {
+                Value::String(value) => Ok(Value::String(format!("{name}={value}"))),
+                _ => Err(format!("{key}: expected \"{name}\" = \"...\"")),
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        options.insert(key.to_string(), Value::Array(items));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let config = Config::parse(
+            r#"
+dialect = "postgres"
+null-values = ["N/A", "-"]
+max-rows-per-file = 5000
+trim = true
+timings = false
+
+[sheets."Order List"]
+table = "orders"
+exclude = ["Memo"]
+"#,
+        )
+        .unwrap();
+        let args: Vec<Vec<String>> = config
+            .options
+            .iter()
+            .map(|(key, value)| Config::option_args(key, value).unwrap())
+            .collect();
+        assert_eq!(
+            args,
+            [
+                vec!["--dialect", "postgres"],
+                vec!["--max-rows-per-file", "5000"],
+                vec!["--null-values", "N/A", "--null-values", "-"],
+                vec![],
+                vec!["--trim"],
+            ]
+        );
+        let mapping = &config.sheets.unwrap().into_mappings()[0];
+        assert!(mapping.applies_to("Order List"));
+        assert_eq!(mapping.table.as_deref(), Some("orders"));
+        assert!(config.rules.is_empty());
+    }
+
+    #[test]
+    fn test_rules() {
+        let config = Config::parse(
+            r#"
+dialect = "postgres"
+
+[rules.Orders]
+required = ["id"]
+
+[rules.Orders.columns.qty]
+min = 0
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.options.len(), 1);
+        assert_eq!(config.rules["Orders"].columns["qty"].min, Some(0.0));
+        assert!(Config::parse(
+            "[rules.Orders]
+required = 1
+"
+        )
+        .unwrap_err()
+        .starts_with("[rules]"));
+    }
+
+    #[test]
+    fn test_profiles() {
+        let config = Config::parse(
+            r#"
+dialect = "mysql"
+trim = true
+
+[profile.staging]
+dialect = "postgres"
+schema = "staging"
+
+[profile.prod]
+insert-mode = "upsert"
+"#,
+        )
+        .unwrap();
+        let keys = |options: Vec<(String, Value)>| -> Vec<String> {
+            options
+                .iter()
+                .flat_map(|(key, value)| Config::option_args(key, value).unwrap())
+                .collect()
+        };
+        assert_eq!(
+            keys(config.options_for(Some("staging")).unwrap()),
+            ["--trim", "--dialect", "postgres", "--schema", "staging"]
+        );
+        assert_eq!(
+            keys(config.options_for(None).unwrap()),
+            ["--dialect", "mysql", "--trim"]
+        );
+        assert_eq!(
+            config.options_for(Some("dev")).unwrap_err(),
+            "no profile 'dev' (defined: prod, staging)"
+        );
+        assert!(Config::parse("profile = \"prod\"\n").is_err());
+    }
+
+    #[test]
+    fn test_rename_table() {
+        let config = Config::parse(
+            r#"
+[rename]
+"Customer Name" = "customer_name"
+"E-mail" = "email"
+
+[profile.legacy.rename]
+"E-mail" = "mail"
+"#,
+        )
+        .unwrap();
+        let args = |options: Vec<(String, Value)>| -> Vec<String> {
+            options
+                .iter()
+                .flat_map(|(key, value)| Config::option_args(key, value).unwrap())
+                .collect()
+        };
+        assert_eq!(
+            args(config.options_for(None).unwrap()),
+            [
+                "--rename",
+                "Customer Name=customer_name",
+                "--rename",
+                "E-mail=email"
+            ]
+        );
+        assert_eq!(
+            args(config.options_for(Some("legacy")).unwrap()),
+            ["--rename", "E-mail=mail"]
+        );
+        assert!(Config::parse("[rename]\nid = 1\n").is_err());
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(Config::option_args("sheet", &Value::Table(Table::new())).is_err());
+        assert!(Config::parse("[sheets.Orders]\ntable = 1\n")
+            .unwrap_err()
+            .starts_with("[sheets]"));
+    }
+}
@@ -9,14 +9,58 @@ pub fn validate_file_exists(path: &Path) -> Result<(), InputError> {
     Ok(())
 }
 
+/// Extensions of the workbooks read through calamine
+pub const SPREADSHEET_EXTENSIONS: &[&str] = &["xlsx", "xlsb", "xls", "ods"];
+
+/// Extensions of the files that can be converted
+pub const SUPPORTED_EXTENSIONS: &[&str] = &["xlsx", "xlsb", "xls", "ods", "csv", "tsv", "tab"];
+
+pub fn is_supported_file(path: &Path) -> bool {
+    path.extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .is_some_and(|ext| SUPPORTED_EXTENSIONS.contains(&ext.as_str()))
+}
+
 pub fn validate_file_format(path: &Path) -> Result<(), InputError> {
-    if let Some(extension) = path.extension() {
-        let ext = extension.to_string_lossy().to_lowercase();
-        if ext != "xlsx" && ext != "xls" {
-            return Err(InputError::InvalidFormat);
-        }
-    } else {
+    if !is_supported_file(path) {
         return Err(InputError::InvalidFormat);
     }
     Ok(())
 }
+
+/// Hex-encoded SHA-256 of a file's contents
+pub fn sha256_file(path: &Path) -> Result<String, InputError> {
+    use sha2::{Digest, Sha256};
+
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher)?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Hex-encoded SHA-256 of input read into memory
+pub fn sha256_bytes(bytes: &[u8]) -> String {
+    use sha2::{Digest, Sha256};
+
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+/// Whether `path` is `-`, which stands for standard input
+pub fn is_stdin_path(path: &Path) -> bool {
+    path.as_os_str() == "-"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_supported_file() {
+        assert!(is_supported_file(Path::new("data.xlsx")));
+        assert!(is_supported_file(Path::new("Report.ODS")));
+        assert!(is_supported_file(Path::new("large.xlsb")));
+        assert!(is_supported_file(Path::new("export.csv")));
+        assert!(!is_supported_file(Path::new("notes.txt")));
+        assert!(!is_supported_file(Path::new("README")));
+    }
+}
@@ -0,0 +1,149 @@
+// Google Sheets share URLs as input, downloaded through the xlsx export endpoint
+
+const SHEETS_PREFIX: &str = "https://docs.google.com/spreadsheets/d/";
+
+/// The document id of a URL such as
+/// `https://docs.google.com/spreadsheets/d/<id>/edit?usp=sharing#gid=0`
+pub fn spreadsheet_id(url: &str) -> Option<&str> {
+    let rest = url.trim().strip_prefix(SHEETS_PREFIX).or_else(|| {
+        url.trim()
+            .strip_prefix("http://docs.google.com/spreadsheets/d/")
+    })?;
+    let id = rest.split(['/', '?', '#']).next()?;
+    (!id.is_empty()).then_some(id)
+}
+
+/// URL downloading the whole workbook as xlsx
+pub fn export_url(url: &str) -> Option<String> {
+    spreadsheet_id(url).map(|id| format!("{SHEETS_PREFIX}{id}/export?format=xlsx"))
+}
+
+/// Downloads, with the `google-sheets` feature
+#[cfg(feature = "google-sheets")]
+mod client {
+    use super::{export_url, service_account};
+    use crate::errors::InputError;
+    use std::io::Read;
+    use std::path::Path;
+
+    /// Download the workbook behind `url`; private sheets need the JSON key of a
+    /// service account the sheet is shared with
+    pub fn download(url: &str, credentials: Option<&Path>) -> Result<Vec<u8>, InputError> {
+        let export = export_url(url)
+            .ok_or_else(|| InputError::Download(format!("{url} is not a Google Sheets URL")))?;
+        let mut request = ureq::get(&export);
+        if let Some(credentials) = credentials {
+            let token = service_account::access_token(credentials)?;
+            request = request.set("Authorization", &format!("Bearer {token}"));
+        }
+        let response = request.call().map_err(|error| match error {
+            ureq::Error::Status(401 | 403 | 404, _) => InputError::Download(format!(
+                "{url} is not shared publicly; share it or pass --google-credentials"
+            )),
+            other => InputError::Download(other.to_string()),
+        })?;
+
+        let mut bytes = Vec::new();
+        response.into_reader().read_to_end(&mut bytes)?;
+        // Sign-in pages come back as HTML with a 200 status
+        if !bytes.starts_with(b"PK") {
+            return Err(InputError::Download(format!(
+                "{url} did not return a workbook; is the sheet shared with the account?"
+            )));
+        }
+        Ok(bytes)
+    }
+}
+
+#[cfg(feature = "google-sheets")]
+pub use client::download;
+
+/// OAuth access tokens for a service account key (the JWT bearer grant)
+#[cfg(feature = "google-sheets")]
+mod service_account {
+    use crate::errors::InputError;
+    use serde::{Deserialize, Serialize};
+    use std::path::Path;
+
+    const SCOPE: &str = "https://www.googleapis.com/auth/drive.readonly";
+
+    #[derive(Deserialize)]
+    struct ServiceAccountKey {
+        client_email: String,
+        private_key: String,
+        token_uri: String,
+    }
+
+    #[derive(Serialize)]
+    struct Claims<'a> {
+        iss: &'a str,
+        scope: &'a str,
+        aud: &'a str,
+        iat: i64,
+        exp: i64,
+    }
+
+    #[derive(Deserialize)]
+    struct TokenResponse {
+        access_token: String,
+    }
+
+    pub fn access_token(key_path: &Path) -> Result<String, InputError> {
+        let invalid = |reason: String| {
+            InputError::Download(format!(
+                "service account key {}: {reason}",
+                key_path.display()
+            ))
+        };
+        let key: ServiceAccountKey = serde_json::from_str(&std::fs::read_to_string(key_path)?)
+            .map_err(|e| invalid(e.to_string()))?;
+
+        let now = chrono::Utc::now().timestamp();
+        let claims = Claims {
+            iss: &key.client_email,
+            scope: SCOPE,
+            aud: &key.token_uri,
+            iat: now,
+            exp: now + 3600,
+        };
+        let signing_key = jsonwebtoken::EncodingKey::from_rsa_pem(key.private_key.as_bytes())
+            .map_err(|e| invalid(e.to_string()))?;
+        let assertion = jsonwebtoken::encode(
+            &jsonwebtoken::Header::new(jsonwebtoken::Algorithm::RS256),
+            &claims,
+            &signing_key,
+        )
+        .map_err(|e| invalid(e.to_string()))?;
+
+        let response = ureq::post(&key.token_uri)
+            .send_form(&[
+                ("grant_type", "urn:ietf:params:oauth:grant-type:jwt-bearer"),
+                ("assertion", &assertion),
+            ])
+            .map_err(|e| InputError::Download(format!("token request failed: {e}")))?
+            .into_string()?;
+        let token: TokenResponse =
+            serde_json::from_str(&response).map_err(|e| invalid(e.to_string()))?;
+        Ok(token.access_token)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spreadsheet_id() {
+        let url = "https://docs.google.com/spreadsheets/d/1AbC-xyz_9/edit?usp=sharing#gid=0";
+        assert_eq!(spreadsheet_id(url), Some("1AbC-xyz_9"));
+        assert_eq!(
+            export_url(url).as_deref(),
+            Some("https://docs.google.com/spreadsheets/d/1AbC-xyz_9/export?format=xlsx")
+        );
+        assert_eq!(spreadsheet_id("data/report.xlsx"), None);
+        assert_eq!(
+            spreadsheet_id("https://docs.google.com/spreadsheets/d/"),
+            None
+        );
+    }
+}
@@ -1 +1,8 @@
+pub mod archive;
+pub mod batch;
+pub mod config;
 pub mod file_handler;
+pub mod google_sheets;
+pub mod rules;
+pub mod session;
+pub mod wizard;
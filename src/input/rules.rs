@@ -0,0 +1,473 @@
+// Checks of sheet contents declared in a config file's [rules] tables (xlsx2sql validate)
+use crate::generator::row_filter::{parse_row_filter, RowFilter};
+use crate::generator::{data_window, GeneratorOptions};
+use crate::output::inferred_type::parse_timestamp;
+use crate::parser::data_model::{SheetData, SqlValue, WorkbookData};
+use calamine::Data;
+use regex::Regex;
+use serde::{Deserialize, Deserializer};
+use std::collections::{BTreeMap, HashMap};
+
+/// Rules keyed by sheet name; those under `"*"` apply to every sheet
+pub type Rules = BTreeMap<String, SheetRules>;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SheetRules {
+    /// The header row, in order
+    pub headers: Option<Vec<String>>,
+    /// Columns that must be present with a value on every row
+    #[serde(default)]
+    pub required: Vec<String>,
+    /// Expressions in --filter syntax every row must satisfy
+    #[serde(default)]
+    pub checks: Vec<Check>,
+    /// Rules for the values of single columns, keyed by header
+    #[serde(default)]
+    pub columns: BTreeMap<String, ColumnRules>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ColumnRules {
+    #[serde(rename = "type")]
+    pub value_type: Option<ValueType>,
+    /// Every row has a value
+    #[serde(default)]
+    pub required: bool,
+    /// No value occurs twice
+    #[serde(default)]
+    pub unique: bool,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    /// Most characters a value may have
+    pub max_length: Option<usize>,
+    /// Regular expression every value matches
+    pub pattern: Option<Pattern>,
+    /// The only values allowed
+    pub values: Option<Vec<String>>,
+}
+
+/// Kind of value a column holds, whether typed into the cell or written as text
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ValueType {
+    Integer,
+    Number,
+    Date,
+    Boolean,
+    Text,
+}
+
+/// A row rule with the expression it was written as, for findings to quote
+#[derive(Debug, Clone)]
+pub struct Check {
+    pub source: String,
+    pub filter: RowFilter,
+}
+
+#[derive(Debug, Clone)]
+pub struct Pattern(pub Regex);
+
+impl<'de> Deserialize<'de> for Check {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let source = String::deserialize(deserializer)?;
+        let filter = parse_row_filter(&source).map_err(serde::de::Error::custom)?;
+        Ok(Check { source, filter })
+    }
+}
+
+impl<'de> Deserialize<'de> for Pattern {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let source = String::deserialize(deserializer)?;
+        Regex::new(&source)
+            .map(Pattern)
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/// One rule a workbook breaks
+#[derive(Debug, Clone, PartialEq)]
+pub struct Finding {
+    /// Sheet, row (`Orders!5:5`) or cell (`Orders!C5`)
+    pub location: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for Finding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.location, self.message)
+    }
+}
+
+impl ValueType {
+    fn accepts(self, cell: &Data) -> bool {
+        match (self, cell) {
+            (ValueType::Integer, Data::Int(_)) => true,
+            (ValueType::Integer, Data::Float(n)) => n.fract() == 0.0,
+            (ValueType::Integer, Data::String(s)) => s.trim().parse::<i64>().is_ok(),
+            (ValueType::Number, Data::Int(_) | Data::Float(_)) => true,
+            (ValueType::Number, Data::String(s)) => s.trim().parse::<f64>().is_ok(),
+            (ValueType::Date, Data::DateTime(_) | Data::DateTimeIso(_)) => true,
+            (ValueType::Date, Data::String(s)) => parse_timestamp(s.trim()).is_some(),
+            (ValueType::Boolean, Data::Bool(_)) => true,
+            (ValueType::Boolean, Data::String(s)) => {
+                ["true", "false"].contains(&s.trim().to_lowercase().as_str())
+            }
+            (ValueType::Text, Data::String(_)) => true,
+            _ => false,
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            ValueType::Integer => "an integer",
+            ValueType::Number => "a number",
+            ValueType::Date => "a date",
+            ValueType::Boolean => "true or false",
+            ValueType::Text => "text",
+        }
+    }
+}
+
+/// Every rule of `rules` that the sheets of `workbook` break, sheet by sheet,
+/// reading their headers and rows where the conversion under `options` does
+pub fn validate(
+    workbook: &WorkbookData,
+    rules: &Rules,
+    options: &GeneratorOptions,
+) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    for name in rules.keys().filter(|name| *name != "*") {
+        if !workbook
+            .sheets
+            .iter()
+            .any(|sheet| sheet.name.eq_ignore_ascii_case(name))
+        {
+            findings.push(Finding {
+                location: name.clone(),
+                message: "no such sheet".to_string(),
+            });
+        }
+    }
+    for sheet in &workbook.sheets {
+        for (name, sheet_rules) in rules {
+            if name == "*" || name.eq_ignore_ascii_case(&sheet.name) {
+                check_sheet(&data_window(options, sheet), sheet_rules, &mut findings);
+            }
+        }
+    }
+    findings
+}
+
+fn check_sheet(sheet: &SheetData, rules: &SheetRules, findings: &mut Vec<Finding>) {
+    let mut sheet_finding = |message: String| {
+        findings.push(Finding {
+            location: sheet.name.clone(),
+            message,
+        })
+    };
+    let headers: Vec<String> = match sheet.get_columns() {
+        Ok(headers) => headers.iter().map(|h| h.trim().to_string()).collect(),
+        Err(e) => return sheet_finding(e.to_string()),
+    };
+    let position = |name: &str| {
+        headers
+            .iter()
+            .position(|header| header.eq_ignore_ascii_case(name.trim()))
+    };
+
+    if let Some(expected) = &rules.headers {
+        let matches = expected.len() == headers.len()
+            && expected
+                .iter()
+                .zip(&headers)
+                .all(|(expected, header)| expected.trim() == header);
+        if !matches {
+            sheet_finding(format!(
+                "headers are {}; expected {}",
+                headers.join(", "),
+                expected.join(", ")
+            ));
+        }
+    }
+    // Column rules by position, with `required` folded in
+    let mut columns: Vec<(usize, &str, ColumnRules)> = Vec::new();
+    for (name, column) in &rules.columns {
+        match position(name) {
+            Some(idx) => columns.push((idx, name, column.clone())),
+            None => sheet_finding(format!("no column {name}")),
+        }
+    }
+    for name in &rules.required {
+        let Some(idx) = position(name) else {
+            if !rules.columns.contains_key(name) {
+                sheet_finding(format!("no required column {name}"));
+            }
+            continue;
+        };
+        match columns.iter_mut().find(|(existing, ..)| *existing == idx) {
+            Some((.., column)) => column.required = true,
+            None => columns.push((
+                idx,
+                name,
+                ColumnRules {
+                    required: true,
+                    ..Default::default()
+                },
+            )),
+        }
+    }
+    columns.sort_by_key(|(idx, ..)| *idx);
+    let checks: Vec<_> = rules
+        .checks
+        .iter()
+        .filter_map(|check| match check.filter.bind(position) {
+            Ok(bound) => Some((check, bound)),
+            Err(name) => {
+                sheet_finding(format!("check '{}' names no column {name}", check.source));
+                None
+            }
+        })
+        .collect();
+
+    let mut seen: Vec<HashMap<String, String>> = vec![HashMap::new(); columns.len()];
+    for (row_idx, row) in sheet.get_data_rows().enumerate() {
+        if row.iter().all(|cell| cell_text(cell).is_empty()) {
+            continue;
+        }
+        for ((idx, name, column), seen) in columns.iter().zip(&mut seen) {
+            let cell = row.get(*idx).unwrap_or(&Data::Empty);
+            let location = sheet.cell_reference(row_idx, *idx);
+            let text = cell_text(cell);
+            let message = match cell {
+                Data::Error(e) => Some(format!("error cell {e}")),
+                _ if text.is_empty() => column.required.then(|| "no value".to_string()),
+                _ => check_value(cell, &text, column, &location, seen),
+            };
+            if let Some(message) = message {
+                findings.push(Finding {
+                    location,
+                    message: format!("{name}: {message}"),
+                });
+            }
+        }
+        let values: Vec<SqlValue> = row.iter().map(SqlValue::from).collect();
+        for (check, bound) in &checks {
+            if !bound.matches(&values) {
+                findings.push(Finding {
+                    location: row_reference(sheet, row_idx),
+                    message: format!("fails check '{}'", check.source),
+                });
+            }
+        }
+    }
+}
+
+/// What is wrong with the non-empty `cell` at `location`, if anything
+fn check_value(
+    cell: &Data,
+    text: &str,
+    rules: &ColumnRules,
+    location: &str,
+    seen: &mut HashMap<String, String>,
+) -> Option<String> {
+    if let Some(value_type) = rules.value_type {
+        if !value_type.accepts(cell) {
+            return Some(format!("'{text}' is not {}", value_type.description()));
+        }
+    }
+    if rules.unique {
+        if let Some(first) = seen.get(text) {
+            return Some(format!("'{text}' repeats {first}"));
+        }
+        seen.insert(text.to_string(), location.to_string());
+    }
+    let number = match cell {
+        Data::Int(i) => Some(*i as f64),
+        Data::Float(n) => Some(*n),
+        Data::String(s) => s.trim().parse::<f64>().ok(),
+        _ => None,
+    };
+    if let (Some(n), Some(min)) = (number, rules.min) {
+        if n < min {
+            return Some(format!("{text} is below the minimum {min}"));
+        }
+    }
+    if let (Some(n), Some(max)) = (number, rules.max) {
+        if n > max {
+            return Some(format!("{text} is above the maximum {max}"));
+        }
+    }
+    if let Some(max_length) = rules.max_length {
+        let length = text.chars().count();
+        if length > max_length {
+            return Some(format!("{length} characters, more than {max_length}"));
+        }
+    }
+    if let Some(Pattern(pattern)) = &rules.pattern {
+        if !pattern.is_match(text) {
+            return Some(format!("'{text}' does not match {pattern}"));
+        }
+    }
+    if let Some(values) = &rules.values {
+        if !values.iter().any(|value| value.trim() == text) {
+            return Some(format!("'{text}' is not one of {}", values.join(", ")));
+        }
+    }
+    None
+}
+
+/// A cell's value as the sheet shows it, trimmed
+fn cell_text(cell: &Data) -> String {
+    match SqlValue::from(cell) {
+        SqlValue::Text(s) | SqlValue::DateTime(s) => s.trim().to_string(),
+        SqlValue::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+/// Excel reference of a whole data row, `Orders!5:5`
+fn row_reference(sheet: &SheetData, data_row: usize) -> String {
+    let row = sheet.range.start().map_or(0, |(row, _)| row as usize) + data_row + 2;
+    format!("{}!{row}:{row}", sheet.worksheet)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use calamine::Range;
+
+    fn sheet(rows: &[&[Data]]) -> WorkbookData {
+        let mut range = Range::new((0, 0), (rows.len() as u32 - 1, rows[0].len() as u32 - 1));
+        for (r, row) in rows.iter().enumerate() {
+            for (c, cell) in row.iter().enumerate() {
+                range.set_value((r as u32, c as u32), cell.clone());
+            }
+        }
+        WorkbookData::new(vec![SheetData::new("Orders".to_string(), range)])
+    }
+
+    fn text(s: &str) -> Data {
+        Data::String(s.to_string())
+    }
+
+    fn messages(findings: Vec<Finding>) -> Vec<String> {
+        findings.iter().map(Finding::to_string).collect()
+    }
+
+    #[test]
+    fn test_validate() {
+        let workbook = sheet(&[
+            &[text("id"), text("status"), text("qty"), text("email")],
+            &[
+                Data::Int(1),
+                text("open"),
+                Data::Float(2.0),
+                text("a@example.com"),
+            ],
+            &[Data::Int(1), text("lost"), text("two"), Data::Empty],
+            &[
+                Data::Int(3),
+                text("open"),
+                Data::Int(-4),
+                text("not-an-email"),
+            ],
+        ]);
+        let rules: Rules = toml::from_str(
+            r#"
+            [Orders]
+            required = ["email"]
+            checks = ['qty < 3']
+
+            [Orders.columns.id]
+            unique = true
+
+            [Orders.columns.status]
+            values = ["open", "shipped"]
+
+            [Orders.columns.qty]
+            type = "integer"
+            min = 0
+
+            [Orders.columns.email]
+            pattern = '^\S+@\S+$'
+
+            [Missing]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            messages(validate(&workbook, &rules, &GeneratorOptions::default())),
+            [
+                "Missing: no such sheet",
+                "Orders!A3: id: '1' repeats Orders!A2",
+                "Orders!B3: status: 'lost' is not one of open, shipped",
+                "Orders!C3: qty: 'two' is not an integer",
+                "Orders!D3: email: no value",
+                "Orders!3:3: fails check 'qty < 3'",
+                "Orders!C4: qty: -4 is below the minimum 0",
+                "Orders!D4: email: 'not-an-email' does not match ^\\S+@\\S+$",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sheet_rules() {
+        let workbook = sheet(&[
+            &[text("id"), text("Name")],
+            &[Data::Int(1), text("a very long name")],
+        ]);
+        let rules: Rules = toml::from_str(
+            r#"
+            ["*"]
+            headers = ["id", "name"]
+            required = ["sku"]
+            checks = ['price > 0']
+
+            ["*".columns.name]
+            max_length = 10
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            messages(validate(&workbook, &rules, &GeneratorOptions::default())),
+            [
+                "Orders: headers are id, Name; expected id, name",
+                "Orders: no required column sku",
+                "Orders: check 'price > 0' names no column price",
+                "Orders!B2: name: 16 characters, more than 10",
+            ]
+        );
+        assert!(toml::from_str::<Rules>("[Orders.columns.id]\ntype = \"uuid\"\n").is_err());
+        assert!(toml::from_str::<Rules>("[Orders]\nunique = true\n").is_err());
+    }
+
+    #[test]
+    fn test_header_below_title() {
+        let workbook = sheet(&[
+            &[text("Orders export"), Data::Empty],
+            &[Data::Empty, Data::Empty],
+            &[text("id"), text("qty")],
+            &[Data::Int(1), Data::Int(-2)],
+        ]);
+        let rules: Rules = toml::from_str(
+            r#"
+            [Orders]
+            headers = ["id", "qty"]
+
+            [Orders.columns.qty]
+            min = 0
+            "#,
+        )
+        .unwrap();
+        let options = GeneratorOptions {
+            skip_rows: 2,
+            ..Default::default()
+        };
+        assert_eq!(
+            messages(validate(&workbook, &rules, &options)),
+            ["Orders!B4: qty: -2 is below the minimum 0"]
+        );
+    }
+}
@@ -0,0 +1,117 @@
+// Recording and replaying the answers given during interactive runs
+use crate::errors::InputError;
+use crate::output::writer::write_atomically;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+pub const DEFAULT_SESSION_FILE: &str = "xlsx2sql-session.toml";
+
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Session {
+    /// Input file picked at the prompt
+    pub input: Option<PathBuf>,
+    /// Command-line arguments of the recorded run, plus prompt answers in flag form
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Set once any prompt was answered; not persisted
+    #[serde(skip)]
+    pub prompted: bool,
+}
+
+impl Session {
+    pub fn load(path: &Path) -> Result<Self, InputError> {
+        let content = std::fs::read_to_string(path)?;
+        toml::from_str(&content)
+            .map_err(|e| InputError::Session(format!("{}: {}", path.display(), e)))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), InputError> {
+        let content = toml::to_string(self)
+            .map_err(|e| InputError::Session(format!("{}: {}", path.display(), e)))?;
+        let content = format!(
+            "# Replay with: xlsx2sql --replay {}\n{content}",
+            path.display()
+        );
+        write_atomically(path, content.as_bytes())?;
+        Ok(())
+    }
+
+    /// Build the argument list for a replayed run; `extra` comes last so it
+    /// overrides recorded values.
+    pub fn replay_args(&self, extra: Vec<String>) -> Vec<String> {
+        let mut args = vec![env!("CARGO_PKG_NAME").to_string()];
+        if let Some(input) = &self.input {
+            args.push("--file".to_string());
+            args.push(input.display().to_string());
+        }
+        args.extend(self.args.iter().cloned());
+        args.extend(extra);
+        args
+    }
+}
+
+/// Drop `--replay <FILE>` / `--replay=<FILE>` from raw command-line arguments
+pub fn strip_replay_arg(args: impl IntoIterator<Item = String>) -> Vec<String> {
+    let mut result = Vec::new();
+    let mut skip_next = false;
+    for arg in args {
+        if skip_next {
+            skip_next = false;
+        } else if arg == "--replay" {
+            skip_next = true;
+        } else if !arg.starts_with("--replay=") {
+            result.push(arg);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.toml");
+        let session = Session {
+            input: Some(PathBuf::from("sales.xlsx")),
+            args: vec!["--number-locale".to_string(), "de".to_string()],
+            prompted: true,
+        };
+        session.save(&path).unwrap();
+
+        let loaded = Session::load(&path).unwrap();
+        assert_eq!(loaded.input, session.input);
+        assert_eq!(loaded.args, session.args);
+        assert!(!loaded.prompted);
+    }
+
+    #[test]
+    fn test_replay_args_order() {
+        let session = Session {
+            input: Some(PathBuf::from("sales.xlsx")),
+            args: vec!["-o".to_string(), "old.sql".to_string()],
+            prompted: false,
+        };
+        let args = session.replay_args(vec!["-o".to_string(), "new.sql".to_string()]);
+        assert_eq!(
+            args,
+            [
+                "xlsx2sql",
+                "--file",
+                "sales.xlsx",
+                "-o",
+                "old.sql",
+                "-o",
+                "new.sql"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_strip_replay_arg() {
+        let args = ["--replay", "s.toml", "-o", "x.sql", "--replay=t.toml"].map(String::from);
+        assert_eq!(strip_replay_arg(args), ["-o", "x.sql"]);
+    }
+}
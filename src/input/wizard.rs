@@ -0,0 +1,176 @@
+// Interactive column mapping, saved as a schema template (--wizard)
+use crate::generator::column_type::ColumnType;
+use dialoguer::{Confirm, Input};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+pub const DEFAULT_TEMPLATE_FILE: &str = "xlsx2sql-template.toml";
+
+/// Answers for every sheet, in the layout of a --target-template file
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct WizardAnswers {
+    pub sheets: BTreeMap<String, SheetAnswers>,
+}
+
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct SheetAnswers {
+    pub table: String,
+    /// Headers excluded from the table
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub exclude: Vec<String>,
+    /// Target column names of the renamed headers
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub columns: BTreeMap<String, String>,
+    /// Declared types keyed by header, as `--column-type` spells them
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub types: BTreeMap<String, String>,
+}
+
+/// What the answer for one header means
+#[derive(Debug, PartialEq)]
+pub enum ColumnAnswer {
+    Exclude,
+    Keep {
+        name: String,
+        column_type: Option<String>,
+    },
+}
+
+/// `-` excludes the column, `name` renames it and `name:type` also declares
+/// its type; an empty name keeps the header
+pub fn parse_column_answer(header: &str, answer: &str) -> Result<ColumnAnswer, String> {
+    let answer = answer.trim();
+    if answer == "-" {
+        return Ok(ColumnAnswer::Exclude);
+    }
+    let (name, column_type) = match answer.split_once(':') {
+        Some((name, column_type)) => (name.trim(), Some(column_type.trim())),
+        None => (answer, None),
+    };
+    if let Some(column_type) = column_type {
+        column_type.parse::<ColumnType>()?;
+    }
+    Ok(ColumnAnswer::Keep {
+        name: match name.is_empty() {
+            true => header.to_string(),
+            false => name.to_string(),
+        },
+        column_type: column_type.map(str::to_string),
+    })
+}
+
+impl SheetAnswers {
+    pub fn record(&mut self, header: &str, answer: ColumnAnswer) {
+        match answer {
+            ColumnAnswer::Exclude => self.exclude.push(header.to_string()),
+            ColumnAnswer::Keep { name, column_type } => {
+                if name != header {
+                    self.columns.insert(header.to_string(), name);
+                }
+                if let Some(column_type) = column_type {
+                    self.types.insert(header.to_string(), column_type);
+                }
+            }
+        }
+    }
+}
+
+impl WizardAnswers {
+    pub fn to_toml(&self) -> Result<String, String> {
+        toml::to_string(self).map_err(|e| e.to_string())
+    }
+}
+
+/// Ask for the table name of each sheet and what becomes of each header
+pub fn ask(sheets: &[(String, Vec<String>)]) -> Result<WizardAnswers, dialoguer::Error> {
+    let mut answers = WizardAnswers::default();
+    for (sheet, headers) in sheets {
+        eprintln!("== {sheet} ({} column(s)) ==", headers.len());
+        let table: String = Input::new()
+            .with_prompt("Table name")
+            .default(sheet.clone())
+            .interact_text()?;
+        let mut sheet_answers = SheetAnswers {
+            table,
+            ..SheetAnswers::default()
+        };
+        eprintln!("For each column: enter keeps it, `name` renames it, `name:type` (e.g. price:currency) also sets its type, `-` excludes it");
+        for header in headers {
+            let answer: String = Input::new()
+                .with_prompt(format!("  {header}"))
+                .allow_empty(true)
+                .validate_with(|answer: &String| parse_column_answer(header, answer).map(|_| ()))
+                .interact_text()?;
+            let answer = parse_column_answer(header, &answer).expect("validated above");
+            sheet_answers.record(header, answer);
+        }
+        answers.sheets.insert(sheet.clone(), sheet_answers);
+    }
+    Ok(answers)
+}
+
+/// Whether to keep the answers, and where
+pub fn ask_save_path() -> Result<Option<String>, dialoguer::Error> {
+    let save = Confirm::new()
+        .with_prompt("Save these mappings as a template for --target-template?")
+        .default(true)
+        .interact()?;
+    if !save {
+        return Ok(None);
+    }
+    Input::new()
+        .with_prompt("Template file")
+        .default(DEFAULT_TEMPLATE_FILE.to_string())
+        .interact_text()
+        .map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::template::SchemaTemplate;
+
+    #[test]
+    fn test_parse_column_answer() {
+        assert_eq!(
+            parse_column_answer("Price", "").unwrap(),
+            ColumnAnswer::Keep {
+                name: "Price".to_string(),
+                column_type: None
+            }
+        );
+        assert_eq!(
+            parse_column_answer("Price", " price : currency ").unwrap(),
+            ColumnAnswer::Keep {
+                name: "price".to_string(),
+                column_type: Some("currency".to_string())
+            }
+        );
+        assert_eq!(
+            parse_column_answer("Notes", "-").unwrap(),
+            ColumnAnswer::Exclude
+        );
+        assert!(parse_column_answer("Price", "price:money").is_err());
+    }
+
+    #[test]
+    fn test_answers_load_as_template() {
+        let mut orders = SheetAnswers {
+            table: "orders".to_string(),
+            ..SheetAnswers::default()
+        };
+        orders.record("Order ID", parse_column_answer("Order ID", "id").unwrap());
+        orders.record("Total", parse_column_answer("Total", ":currency").unwrap());
+        orders.record("Memo", ColumnAnswer::Exclude);
+        let mut answers = WizardAnswers::default();
+        answers.sheets.insert("Order List".to_string(), orders);
+
+        let template: SchemaTemplate = toml::from_str(&answers.to_toml().unwrap()).unwrap();
+        let mapping = &template.into_mappings()[0];
+        assert!(mapping.applies_to("Order List"));
+        assert_eq!(mapping.table.as_deref(), Some("orders"));
+        assert_eq!(mapping.column_name("Order ID"), "id");
+        assert_eq!(mapping.types.get("Total"), Some(&ColumnType::Currency));
+        assert!(mapping.excludes("memo"));
+    }
+}
@@ -0,0 +1,92 @@
+//! Convert Excel workbooks and CSV files into SQL INSERT statements.
+//!
+//! The `xlsx2sql` command line tool is a thin layer over this crate: a
+//! workbook is parsed into [`WorkbookData`], a [`SqlGenerator`] for the target
+//! [`Dialect`] turns its sheets into [`SqlStatement`]s, and the generator
+//! formats those as SQL text.
+//!
+//! ```
+//! use xlsx2sql::{Dialect, GeneratorOptions, ParseOptions};
+//!
+//! let workbook = xlsx2sql::parse_bytes("orders.csv", b"id,item\n1,Pen\n", ParseOptions::default())?;
+//! let sql = xlsx2sql::to_sql(&workbook, Dialect::Postgres, GeneratorOptions::default())?;
+//! assert_eq!(sql, "INSERT INTO \"orders\" (\"id\", \"item\") VALUES\n(1,'Pen');\n");
+//! # Ok::<(), xlsx2sql::Xlsx2SqlError>(())
+//! ```
+//!
+//! [`Converter`] takes the settings of the command line options one by one:
+//!
+//! ```
+//! use xlsx2sql::{Converter, Dialect};
+//!
+//! let converter = Converter::builder()
+//!     .dialect(Dialect::Postgres)
+//!     .header_row(2)
+//!     .batch_size(500)
+//!     .snake_case(true)
+//!     .build();
+//! let sql = converter.convert_bytes("orders.csv", b"Export\nOrder ID\n7\n")?;
+//! assert_eq!(sql, "INSERT INTO \"orders\" (\"order_id\") VALUES\n(7);\n");
+//! # Ok::<(), xlsx2sql::Xlsx2SqlError>(())
+//! ```
+//!
+//! [`generator_for`] gives access to the statements before they are
+//! formatted, and the modules below hold the pieces the command line options
+//! map to: value conversion, templates, output formats and the rest.
+
+#[cfg(feature = "tokio")]
+pub mod asynchronous;
+pub mod converter;
+pub mod errors;
+pub mod generator;
+pub mod input;
+pub mod output;
+pub mod parser;
+
+pub use converter::{Converter, ConverterBuilder};
+pub use errors::Xlsx2SqlError;
+pub use generator::{generator_for, Dialect, GeneratorOptions, RowAction, SqlGenerator};
+pub use parser::data_model::{SheetData, SqlStatement, SqlValue, WorkbookData};
+pub use parser::{CsvOptions, ParseOptions};
+
+use input::file_handler::validate_file_exists;
+use parser::{parser_for, parser_for_bytes};
+use std::path::Path;
+
+/// Read the workbook or CSV file at `path`, picking the parser by its extension
+pub fn parse(path: &Path, options: ParseOptions) -> Result<WorkbookData, Xlsx2SqlError> {
+    validate_file_exists(path)?;
+    Ok(parser_for(path, options).parse(path)?)
+}
+
+/// Read a workbook or CSV file held in memory, picking the parser by its
+/// contents; `name` stands in for the file name where sheets are named after it
+pub fn parse_bytes(
+    name: &str,
+    bytes: &[u8],
+    options: ParseOptions,
+) -> Result<WorkbookData, Xlsx2SqlError> {
+    Ok(parser_for_bytes(bytes, options).parse_bytes(name, bytes)?)
+}
+
+/// The rows of every converted sheet, one statement per sheet (per table
+/// with `GeneratorOptions::union_sheets`), ready for `dialect`
+pub fn generate(
+    workbook: &WorkbookData,
+    dialect: Dialect,
+    options: GeneratorOptions,
+) -> Result<Vec<SqlStatement>, Xlsx2SqlError> {
+    Ok(generator_for(dialect, options).generate(workbook)?)
+}
+
+/// The SQL text the command line tool writes for `workbook`: every
+/// statement, separated by blank lines
+pub fn to_sql(
+    workbook: &WorkbookData,
+    dialect: Dialect,
+    options: GeneratorOptions,
+) -> Result<String, Xlsx2SqlError> {
+    let generator = generator_for(dialect, options);
+    let statements = generator.generate(workbook)?;
+    Ok(converter::format_sql(generator.as_ref(), &statements))
+}
@@ -0,0 +1,162 @@
+// User-supplied Tera templates rendering each statement (--template)
+use crate::errors::OutputError;
+use crate::output::format::json_value;
+use crate::output::inferred_type::infer_type;
+use crate::parser::data_model::{SqlStatement, SqlValue};
+use serde_json::{json, Value};
+use std::error::Error;
+use std::path::Path;
+use tera::{Context, Tera};
+
+const TEMPLATE_NAME: &str = "template";
+
+/// A template file, parsed once and rendered for every statement
+#[derive(Debug, Clone)]
+pub struct OutputTemplate {
+    tera: Tera,
+    extension: String,
+}
+
+impl OutputTemplate {
+    /// Read and parse the template at `path`; clap value parser
+    pub fn load(path: &str) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("cannot read template {path}: {e}"))?;
+        Self::parse(&content, Path::new(path))
+    }
+
+    fn parse(content: &str, path: &Path) -> Result<Self, String> {
+        let mut tera = Tera::default();
+        tera.add_raw_template(TEMPLATE_NAME, content)
+            .map_err(|e| format!("invalid template {}: {}", path.display(), error_chain(&e)))?;
+        // `inserts.sql.tera` writes `.sql` files
+        let extension = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("tera") => Path::new(path.file_stem().unwrap_or_default())
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .unwrap_or("txt"),
+            _ => "txt",
+        };
+        Ok(OutputTemplate {
+            tera,
+            extension: extension.to_string(),
+        })
+    }
+
+    /// Extension of the files written with this template
+    pub fn extension(&self) -> &str {
+        &self.extension
+    }
+
+    /// Render `statement`. The template sees `workbook`, `table`, `columns`
+    /// (`name` and inferred `type` of each), `rows` (JSON values) and
+    /// `literals` (the same cells as SQL literals of the target dialect).
+    pub fn render(
+        &self,
+        statement: &SqlStatement,
+        workbook: &str,
+        literal: &dyn Fn(&SqlValue) -> String,
+    ) -> Result<String, OutputError> {
+        let columns: Vec<Value> = statement
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(idx, name)| {
+                let values = statement.values.iter().filter_map(|row| row.get(idx));
+                json!({ "name": name, "type": infer_type(values).name() })
+            })
+            .collect();
+        let rows: Vec<Vec<Value>> = statement
+            .values
+            .iter()
+            .map(|row| row.iter().map(json_value).collect())
+            .collect();
+        let literals: Vec<Vec<String>> = statement
+            .values
+            .iter()
+            .map(|row| row.iter().map(literal).collect())
+            .collect();
+        let mut context = Context::new();
+        context.insert("workbook", workbook);
+        context.insert("table", &statement.table_name);
+        context.insert("columns", &columns);
+        context.insert("rows", &rows);
+        context.insert("literals", &literals);
+        self.tera.render(TEMPLATE_NAME, &context).map_err(|e| {
+            OutputError::Template(format!("{}: {}", statement.table_name, error_chain(&e)))
+        })
+    }
+}
+
+/// Tera keeps the useful part of a message, such as the failing line, in
+/// the error's sources
+fn error_chain(error: &tera::Error) -> String {
+    let mut message = error.to_string();
+    let mut source = error.source();
+    while let Some(cause) = source {
+        message.push_str(&format!(": {cause}"));
+        source = cause.source();
+    }
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn statement() -> SqlStatement {
+        SqlStatement {
+            table_name: "products".to_string(),
+            columns: vec!["id".to_string(), "name".to_string()],
+            values: vec![
+                vec![SqlValue::Number(1.0), SqlValue::Text("Pen".to_string())],
+                vec![SqlValue::Integer(2), SqlValue::Null],
+            ],
+            warnings: vec![],
+            stats: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_render() {
+        let template = OutputTemplate::parse(
+            concat!(
+                "-- {{ workbook }}: {% for c in columns %}{{ c.name }} {{ c.type }}{% if not loop.last %}, {% endif %}{% endfor %}\n",
+                "{% for row in literals %}CALL add_{{ table }}({{ row | join(sep=\", \") }});\n{% endfor %}",
+                "{{ rows[1][0] + 1 }}\n"
+            ),
+            Path::new("calls.sql.tera"),
+        )
+        .unwrap();
+        assert_eq!(template.extension(), "sql");
+        let literal = |value: &SqlValue| match value {
+            SqlValue::Null => "NULL".to_string(),
+            SqlValue::Text(s) => format!("'{s}'"),
+            other => json_value(other).to_string(),
+        };
+        assert_eq!(
+            template
+                .render(&statement(), "shop.xlsx", &literal)
+                .unwrap(),
+            concat!(
+                "-- shop.xlsx: id integer, name text\n",
+                "CALL add_products(1, 'Pen');\n",
+                "CALL add_products(2, NULL);\n",
+                "3\n"
+            )
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(OutputTemplate::parse("{% for %}", Path::new("x.tera"))
+            .unwrap_err()
+            .starts_with("invalid template x.tera"));
+        let template = OutputTemplate::parse("{{ missing }}", Path::new("x.tera")).unwrap();
+        assert_eq!(template.extension(), "txt");
+        let error = template
+            .render(&statement(), "shop.xlsx", &|_| String::new())
+            .unwrap_err();
+        assert!(error.to_string().contains("products"));
+    }
+}
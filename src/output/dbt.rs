@@ -0,0 +1,113 @@
+// dbt seeds: seeds/<table>.csv plus seeds/seeds.yml (--format dbt-seed)
+use crate::generator::dialect::Dialect;
+use crate::output::inferred_type::{infer_type, InferredType};
+use crate::parser::data_model::SqlStatement;
+
+/// Name of the properties file written next to the seed CSVs
+pub const PROPERTIES_FILE: &str = "seeds.yml";
+
+/// Warehouse type of an inferred column, as `column_types` expects it
+fn warehouse_type(column_type: InferredType, dialect: Dialect) -> String {
+    match (column_type, dialect) {
+        (InferredType::Boolean, _) => "boolean".to_string(),
+        (InferredType::Int64, _) => "bigint".to_string(),
+        (InferredType::Float64, Dialect::Mysql) => "double".to_string(),
+        (InferredType::Float64, Dialect::Postgres) => "double precision".to_string(),
+        (InferredType::Decimal { scale }, Dialect::Mysql) => format!("decimal(38,{scale})"),
+        (InferredType::Decimal { scale }, Dialect::Postgres) => format!("numeric(38,{scale})"),
+        (InferredType::Timestamp, Dialect::Mysql) => "datetime".to_string(),
+        (InferredType::Timestamp, Dialect::Postgres) => "timestamp".to_string(),
+        (InferredType::Binary | InferredType::List | InferredType::Utf8, _) => "text".to_string(),
+    }
+}
+
+/// Plain YAML scalar for simple names, else a double-quoted one
+fn yaml_key(name: &str) -> String {
+    let simple = name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    match simple {
+        true => name.to_string(),
+        false => serde_json::Value::from(name).to_string(),
+    }
+}
+
+/// `seeds.yml` pinning the type of every column, so dbt does not guess from
+/// the CSV; a table split over several statements is typed from all its rows
+pub fn properties(statements: &[SqlStatement], dialect: Dialect) -> String {
+    let mut tables: Vec<&str> = Vec::new();
+    for statement in statements {
+        if !tables.contains(&statement.table_name.as_str()) {
+            tables.push(&statement.table_name);
+        }
+    }
+    let mut yaml = String::from("version: 2\n\nseeds:\n");
+    for table in tables {
+        let parts: Vec<&SqlStatement> = statements
+            .iter()
+            .filter(|statement| statement.table_name == table)
+            .collect();
+        yaml.push_str(&format!("  - name: {}\n", yaml_key(table)));
+        yaml.push_str("    config:\n      column_types:\n");
+        for (idx, column) in parts[0].columns.iter().enumerate() {
+            let values = parts
+                .iter()
+                .flat_map(|statement| statement.values.iter().filter_map(|row| row.get(idx)));
+            yaml.push_str(&format!(
+                "        {}: {}\n",
+                yaml_key(column),
+                warehouse_type(infer_type(values), dialect)
+            ));
+        }
+    }
+    yaml
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::data_model::SqlValue;
+
+    #[test]
+    fn test_properties() {
+        let statement = |values| SqlStatement {
+            table_name: "products".to_string(),
+            columns: vec![
+                "id".to_string(),
+                "unit price".to_string(),
+                "added".to_string(),
+                "note".to_string(),
+            ],
+            values,
+            warnings: vec![],
+            stats: Default::default(),
+        };
+        let statements = [
+            statement(vec![vec![
+                SqlValue::Number(1.0),
+                SqlValue::Decimal("9.5".to_string()),
+                SqlValue::DateTime("2024-01-02 03:04:05".to_string()),
+                SqlValue::Null,
+            ]]),
+            statement(vec![vec![
+                SqlValue::Integer(2),
+                SqlValue::Decimal("0.25".to_string()),
+                SqlValue::Null,
+                SqlValue::Text("x".to_string()),
+            ]]),
+        ];
+        assert_eq!(
+            properties(&statements, Dialect::Postgres),
+            concat!(
+                "version: 2\n\nseeds:\n",
+                "  - name: products\n",
+                "    config:\n",
+                "      column_types:\n",
+                "        id: bigint\n",
+                "        \"unit price\": numeric(38,2)\n",
+                "        added: timestamp\n",
+                "        note: text\n"
+            )
+        );
+        assert!(properties(&statements, Dialect::Mysql).contains(": decimal(38,2)\n"));
+    }
+}
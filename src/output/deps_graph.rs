@@ -0,0 +1,44 @@
+// Graphviz rendering of table dependencies and emission order
+
+/// Render tables (in emission order) and `(table, depends_on)` edges as DOT
+pub fn render_dependency_graph(tables: &[String], dependencies: &[(String, String)]) -> String {
+    let mut dot = String::from("digraph dependencies {\n    rankdir=LR;\n    node [shape=box];\n");
+    for (index, table) in tables.iter().enumerate() {
+        dot.push_str(&format!(
+            "    {} [label={}];\n",
+            quote(table),
+            quote(&format!("{}. {}", index + 1, table))
+        ));
+    }
+    for (table, depends_on) in dependencies {
+        dot.push_str(&format!("    {} -> {};\n", quote(table), quote(depends_on)));
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+fn quote(id: &str) -> String {
+    format!("\"{}\"", id.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_dependency_graph() {
+        let tables = vec!["customers".to_string(), "orders".to_string()];
+        let dependencies = vec![("orders".to_string(), "customers".to_string())];
+        let dot = render_dependency_graph(&tables, &dependencies);
+        assert!(dot.starts_with("digraph dependencies {"));
+        assert!(dot.contains("\"customers\" [label=\"1. customers\"];"));
+        assert!(dot.contains("\"orders\" [label=\"2. orders\"];"));
+        assert!(dot.contains("\"orders\" -> \"customers\";"));
+    }
+
+    #[test]
+    fn test_quotes_identifiers() {
+        let dot = render_dependency_graph(&["say \"hi\"".to_string()], &[]);
+        assert!(dot.contains("\"say \\\"hi\\\"\""));
+    }
+}
@@ -0,0 +1,282 @@
+// Sync SQL between two versions of a workbook (xlsx2sql diff)
+use crate::errors::OutputError;
+use crate::generator::dialect::Dialect;
+use crate::generator::sql_generator::SqlGenerator;
+use crate::output::row_state::key_text;
+use crate::parser::data_model::{SqlStatement, SqlValue, StatementStats};
+use std::collections::HashMap;
+
+/// What changed in one table between the old and the new workbook
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableDiff {
+    pub table: String,
+    /// Columns of the new version
+    pub columns: Vec<String>,
+    /// Position of the key column in `columns`
+    pub key: usize,
+    /// Rows whose key the old version lacks
+    pub inserted: Vec<Vec<SqlValue>>,
+    /// Rows whose values differ, with the positions of the changed columns
+    pub updated: Vec<(Vec<SqlValue>, Vec<usize>)>,
+    /// Keys of the rows the new version lacks
+    pub deleted: Vec<SqlValue>,
+}
+
+/// A table's name, columns and rows
+type Table<'a> = (&'a str, &'a [String], Vec<&'a [SqlValue]>);
+
+/// The rows of each table, in order of first appearance, merging statements
+/// that insert into the same table
+fn tables(statements: &[SqlStatement]) -> Vec<Table<'_>> {
+    let mut tables: Vec<Table> = Vec::new();
+    for statement in statements {
+        let rows = statement.values.iter().map(Vec::as_slice);
+        match tables
+            .iter_mut()
+            .find(|(table, ..)| *table == statement.table_name)
+        {
+            Some((.., existing)) => existing.extend(rows),
+            None => tables.push((&statement.table_name, &statement.columns, rows.collect())),
+        }
+    }
+    tables
+}
+
+/// Rows of one table keyed by the text of their `key` column
+fn keyed<'a>(
+    table: &str,
+    columns: &[String],
+    rows: &[&'a [SqlValue]],
+    key: &str,
+) -> Result<(usize, HashMap<String, &'a [SqlValue]>), OutputError> {
+    let key_idx = columns
+        .iter()
+        .position(|column| column.eq_ignore_ascii_case(key.trim()))
+        .ok_or_else(|| OutputError::KeyColumn {
+            table: table.to_string(),
+            column: key.to_string(),
+        })?;
+    let mut keyed = HashMap::with_capacity(rows.len());
+    for row in rows {
+        let key = key_text(&row[key_idx]);
+        if keyed.insert(key.clone(), *row).is_some() {
+            return Err(OutputError::DuplicateKey {
+                table: table.to_string(),
+                key,
+            });
+        }
+    }
+    Ok((key_idx, keyed))
+}
+
+/// Compare the tables of `new` with those of `old`, matching rows by `key`.
+/// Tables pair up by name, or regardless of it when each side has just one
+/// (two CSV files); tables only in `old` are left alone, with a warning
+pub fn diff_tables(
+    old: &[SqlStatement],
+    new: &[SqlStatement],
+    key: &str,
+) -> Result<Vec<TableDiff>, OutputError> {
+    let old_tables = tables(old);
+    let new_tables = tables(new);
+    let single = old_tables.len() == 1 && new_tables.len() == 1;
+    for (table, ..) in &old_tables {
+        if !single && !new_tables.iter().any(|(new_table, ..)| new_table == table) {
+            log::warn!("{table}: only in the old workbook; its rows are not deleted");
+        }
+    }
+    let mut diffs = Vec::new();
+    for (table, columns, rows) in new_tables {
+        let (key_idx, new_keyed) = keyed(table, columns, &rows, key)?;
+        let (old_columns, old_rows): (&[String], Vec<&[SqlValue]>) = old_tables
+            .iter()
+            .find(|(old_table, ..)| single || *old_table == table)
+            .map_or((&[], Vec::new()), |(_, columns, rows)| {
+                (*columns, rows.clone())
+            });
+        let old_keyed = match old_columns.is_empty() {
+            true => HashMap::new(),
+            false => keyed(table, old_columns, &old_rows, key)?.1,
+        };
+        // Where each new column sits in the old rows
+        let old_idx: Vec<Option<usize>> = columns
+            .iter()
+            .map(|column| {
+                old_columns
+                    .iter()
+                    .position(|old| old.eq_ignore_ascii_case(column))
+            })
+            .collect();
+
+        let mut diff = TableDiff {
+            table: table.to_string(),
+            columns: columns.to_vec(),
+            key: key_idx,
+            inserted: Vec::new(),
+            updated: Vec::new(),
+            deleted: Vec::new(),
+        };
+        for row in &rows {
+            let Some(old_row) = old_keyed.get(&key_text(&row[key_idx])) else {
+                diff.inserted.push(row.to_vec());
+                continue;
+            };
+            let changed: Vec<usize> = (0..columns.len())
+                .filter(|&idx| old_idx[idx].map(|old| &old_row[old]) != Some(&row[idx]))
+                .collect();
+            if !changed.is_empty() {
+                diff.updated.push((row.to_vec(), changed));
+            }
+        }
+        let old_key_idx = old_idx[key_idx];
+        for old_row in &old_rows {
+            let old_key = old_key_idx.map(|idx| &old_row[idx]);
+            if let Some(old_key) =
+                old_key.filter(|old_key| !new_keyed.contains_key(&key_text(old_key)))
+            {
+                diff.deleted.push(old_key.clone());
+            }
+        }
+        diffs.push(diff);
+    }
+    Ok(diffs)
+}
+
+impl TableDiff {
+    pub fn is_empty(&self) -> bool {
+        self.inserted.is_empty() && self.updated.is_empty() && self.deleted.is_empty()
+    }
+
+    /// DELETE, UPDATE and INSERT statements bringing the old table up to date,
+    /// in that order so a reused key is free again before it is inserted
+    pub fn to_sql(
+        &self,
+        generator: &dyn SqlGenerator,
+        dialect: Dialect,
+        schema: Option<&str>,
+    ) -> String {
+        let table = dialect.quote_table(schema, &self.table);
+        let key = dialect.quote_identifier(&self.columns[self.key]);
+        let mut sql = format!(
+            "-- {}: {} new, {} changed, {} removed row(s)\n",
+            self.table,
+            self.inserted.len(),
+            self.updated.len(),
+            self.deleted.len()
+        );
+        if !self.deleted.is_empty() {
+            let keys: Vec<String> = self
+                .deleted
+                .iter()
+                .map(|key| generator.format_sql_value(key))
+                .collect();
+            sql.push_str(&format!(
+                "DELETE FROM {table} WHERE {key} IN ({});\n",
+                keys.join(", ")
+            ));
+        }
+        for (row, changed) in &self.updated {
+            let assignments: Vec<String> = changed
+                .iter()
+                .map(|&idx| {
+                    format!(
+                        "{} = {}",
+                        dialect.quote_identifier(&self.columns[idx]),
+                        generator.format_sql_value(&row[idx])
+                    )
+                })
+                .collect();
+            sql.push_str(&format!(
+                "UPDATE {table} SET {} WHERE {key} = {};\n",
+                assignments.join(", "),
+                generator.format_sql_value(&row[self.key])
+            ));
+        }
+        if !self.inserted.is_empty() {
+            let statement = SqlStatement {
+                table_name: self.table.clone(),
+                columns: self.columns.clone(),
+                values: self.inserted.clone(),
+                warnings: Vec::new(),
+                stats: StatementStats::default(),
+            };
+            sql.push_str(&generator.format_statement(&statement));
+            sql.push('\n');
+        }
+        sql
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::sql_generator::MySqlGenerator;
+
+    fn statement(rows: &[(i64, &str, i64)]) -> SqlStatement {
+        SqlStatement {
+            table_name: "orders".to_string(),
+            columns: vec!["id".to_string(), "status".to_string(), "qty".to_string()],
+            values: rows
+                .iter()
+                .map(|(id, status, qty)| {
+                    vec![
+                        SqlValue::Integer(*id),
+                        SqlValue::Text(status.to_string()),
+                        SqlValue::Integer(*qty),
+                    ]
+                })
+                .collect(),
+            warnings: Vec::new(),
+            stats: StatementStats::default(),
+        }
+    }
+
+    #[test]
+    fn test_diff_tables() {
+        let old = statement(&[(1, "open", 1), (2, "open", 5), (3, "open", 2)]);
+        let new = statement(&[(1, "open", 1), (2, "shipped", 5), (4, "open", 9)]);
+        let diffs = diff_tables(&[old], &[new], "ID").unwrap();
+        let diff = &diffs[0];
+        assert_eq!(diff.key, 0);
+        assert_eq!(diff.inserted.len(), 1);
+        assert_eq!(diff.updated.len(), 1);
+        assert_eq!(diff.updated[0].1, [1]);
+        assert_eq!(diff.deleted, [SqlValue::Integer(3)]);
+
+        let sql = diff.to_sql(&MySqlGenerator::default(), Dialect::Mysql, None);
+        assert_eq!(
+            sql,
+            "-- orders: 1 new, 1 changed, 1 removed row(s)\n\
+             DELETE FROM `orders` WHERE `id` IN (3);\n\
+             UPDATE `orders` SET `status` = 'shipped' WHERE `id` = 2;\n\
+             INSERT INTO `orders` (`id`, `status`, `qty`) VALUES\n(4,'open',9);\n"
+        );
+    }
+
+    #[test]
+    fn test_diff_errors() {
+        let old = || statement(&[(1, "open", 1)]);
+        let new = statement(&[(1, "open", 1), (1, "shipped", 1)]);
+        assert!(matches!(
+            diff_tables(&[old()], &[new], "id"),
+            Err(OutputError::DuplicateKey { key, .. }) if key == "1"
+        ));
+        assert!(matches!(
+            diff_tables(&[old()], &[old()], "sku"),
+            Err(OutputError::KeyColumn { .. })
+        ));
+    }
+
+    #[test]
+    fn test_table_pairing() {
+        let new = statement(&[(1, "open", 1)]);
+        let diffs = diff_tables(&[], &[new], "id").unwrap();
+        assert_eq!(diffs[0].inserted.len(), 1);
+        assert!(!diffs[0].is_empty());
+
+        let mut old = statement(&[(1, "open", 1)]);
+        old.table_name = "orders_2024".to_string();
+        let diffs = diff_tables(&[old], &[statement(&[(1, "open", 1)])], "id").unwrap();
+        assert!(diffs[0].is_empty());
+    }
+}
@@ -0,0 +1,142 @@
+// Django fixtures for manage.py loaddata (--format django-fixture)
+use crate::output::format::json_value;
+use crate::output::inflection::ModelNames;
+use crate::output::split::TableOutput;
+use crate::parser::data_model::{SqlStatement, SqlValue};
+use serde_json::{Map, Value};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DjangoOptions {
+    /// App label the models belong to
+    pub app: String,
+    /// Column holding each row's primary key
+    pub pk_column: String,
+    pub models: ModelNames,
+}
+
+/// `app.model` for `table`; a `<app>_` prefix, as in Django's default table
+/// names, is dropped
+pub fn model_label(table: &str, options: &DjangoOptions) -> String {
+    let table = table
+        .strip_prefix(&format!("{}_", options.app))
+        .filter(|rest| !rest.is_empty())
+        .unwrap_or(table);
+    format!(
+        "{}.{}",
+        options.app,
+        options.models.model_name(table).to_lowercase()
+    )
+}
+
+/// One fixture object per line; the pk column becomes `pk` and the other
+/// columns `fields`
+pub fn fixture_objects(statement: &SqlStatement, options: &DjangoOptions) -> String {
+    let label = model_label(&statement.table_name, options);
+    let pk_idx = statement
+        .columns
+        .iter()
+        .position(|c| c.eq_ignore_ascii_case(options.pk_column.trim()));
+    let mut lines = String::new();
+    for row in &statement.values {
+        lines.push_str(&fixture_object(&label, pk_idx, &statement.columns, row));
+        lines.push('\n');
+    }
+    lines
+}
+
+pub fn fixture_object(
+    label: &str,
+    pk_idx: Option<usize>,
+    columns: &[String],
+    row: &[SqlValue],
+) -> String {
+    let mut object = Map::new();
+    object.insert("model".to_string(), Value::from(label));
+    // Without a pk column Django assigns new keys on load
+    if let Some(pk) = pk_idx.and_then(|idx| row.get(idx)) {
+        object.insert("pk".to_string(), json_value(pk));
+    }
+    let fields: Map<String, Value> = columns
+        .iter()
+        .zip(row)
+        .enumerate()
+        .filter(|(i, _)| Some(*i) != pk_idx)
+        .map(|(_, (column, value))| (column.clone(), json_value(value)))
+        .collect();
+    object.insert("fields".to_string(), Value::Object(fields));
+    Value::Object(object).to_string()
+}
+
+/// All objects of all parts in one fixture array
+pub fn fixture(parts: &[TableOutput]) -> String {
+    let objects: Vec<&str> = parts.iter().flat_map(|part| part.content.lines()).collect();
+    match objects.is_empty() {
+        true => "[]\n".to_string(),
+        false => format!("[\n  {}\n]\n", objects.join(",\n  ")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options() -> DjangoOptions {
+        DjangoOptions {
+            app: "shop".to_string(),
+            pk_column: "id".to_string(),
+            models: ModelNames {
+                singularize: true,
+                ..ModelNames::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_model_label() {
+        assert_eq!(model_label("products", &options()), "shop.product");
+        assert_eq!(
+            model_label("shop_order_items", &options()),
+            "shop.orderitem"
+        );
+    }
+
+    #[test]
+    fn test_fixture() {
+        let statement = SqlStatement {
+            table_name: "products".to_string(),
+            columns: vec!["ID".to_string(), "name".to_string(), "price".to_string()],
+            values: vec![
+                vec![
+                    SqlValue::Number(1.0),
+                    SqlValue::Text("Pen".to_string()),
+                    SqlValue::Number(1.5),
+                ],
+                vec![SqlValue::Number(2.0), SqlValue::Null, SqlValue::Null],
+            ],
+            warnings: vec![],
+            stats: Default::default(),
+        };
+        let part = TableOutput {
+            table: "products".to_string(),
+            content: fixture_objects(&statement, &options()),
+            rows: 2,
+        };
+        assert_eq!(
+            fixture(&[part]),
+            concat!(
+                "[\n",
+                r#"  {"model":"shop.product","pk":1,"fields":{"name":"Pen","price":1.5}},"#,
+                "\n",
+                r#"  {"model":"shop.product","pk":2,"fields":{"name":null,"price":null}}"#,
+                "\n]\n"
+            )
+        );
+        let no_pk = DjangoOptions {
+            pk_column: "sku".to_string(),
+            ..options()
+        };
+        assert!(fixture_objects(&statement, &no_pk)
+            .starts_with(r#"{"model":"shop.product","fields":{"ID":1,"#));
+        assert_eq!(fixture(&[]), "[]\n");
+    }
+}
@@ -0,0 +1,87 @@
+// Flyway versioned migrations (--format flyway --version V5_2)
+use crate::output::split::TableOutput;
+
+/// `V5_2`, `5.2` or `v5_2` as Flyway's `V5_2`; clap value parser
+pub fn parse_version(version: &str) -> Result<String, String> {
+    let number = version
+        .trim()
+        .strip_prefix(['V', 'v'])
+        .unwrap_or(version.trim());
+    let valid = number
+        .split(['.', '_'])
+        .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()));
+    if number.is_empty() || !valid {
+        return Err(format!(
+            "'{version}' is not a migration version such as V5_2 or 5.2"
+        ));
+    }
+    Ok(format!("V{}", number.replace('.', "_")))
+}
+
+/// `V5_2__seed_<workbook>.sql`, with characters Flyway reads as separators
+/// in the description replaced
+pub fn file_name(version: &str, workbook: &str) -> String {
+    let description: String = workbook
+        .chars()
+        .map(|c| match c {
+            c if c.is_alphanumeric() => c,
+            _ => '_',
+        })
+        .collect();
+    format!("{version}__seed_{description}.sql")
+}
+
+/// Leading comment of the migration. It holds nothing that changes between
+/// runs on the same workbook, so regenerating keeps Flyway's checksum.
+pub fn header(version: &str, workbook: &str, parts: &[TableOutput]) -> String {
+    let mut tables: Vec<(&str, usize)> = Vec::new();
+    for part in parts {
+        match tables.iter_mut().find(|(table, _)| *table == part.table) {
+            Some((_, rows)) => *rows += part.rows,
+            None => tables.push((&part.table, part.rows)),
+        }
+    }
+    let mut header = format!("-- Flyway migration {version}: seed data from {workbook}\n");
+    for (table, rows) in tables {
+        header.push_str(&format!("-- {table}: {rows} row(s)\n"));
+    }
+    header.push_str("-- Generated by xlsx2sql; regenerate it instead of editing by hand\n\n");
+    header
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(parse_version("V5_2").unwrap(), "V5_2");
+        assert_eq!(parse_version("5.2.1").unwrap(), "V5_2_1");
+        assert!(parse_version("V").is_err());
+        assert!(parse_version("V5__2").is_err());
+        assert!(parse_version("next").is_err());
+    }
+
+    #[test]
+    fn test_file_name_and_header() {
+        assert_eq!(file_name("V5_2", "price list"), "V5_2__seed_price_list.sql");
+        let part = |table: &str, rows| TableOutput {
+            table: table.to_string(),
+            content: String::new(),
+            rows,
+        };
+        assert_eq!(
+            header(
+                "V5_2",
+                "prices.xlsx",
+                &[part("prices", 2), part("regions", 1), part("prices", 3)]
+            ),
+            concat!(
+                "-- Flyway migration V5_2: seed data from prices.xlsx\n",
+                "-- prices: 5 row(s)\n",
+                "-- regions: 1 row(s)\n",
+                "-- Generated by xlsx2sql; regenerate it instead of editing by hand\n\n"
+            )
+        );
+    }
+}
@@ -0,0 +1,388 @@
+// Output formats other than SQL scripts (--format)
+use crate::generator::duration::format_duration;
+use crate::output::split::TableOutput;
+use crate::output::writer::Quoting;
+use crate::parser::data_model::{SqlStatement, SqlValue};
+use clap::ValueEnum;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// INSERT statements
+    #[default]
+    Sql,
+    /// One comma-separated file per table, with a header row
+    Csv,
+    /// One tab-separated file per table, with a header row
+    Tsv,
+    /// One file per table holding an array of row objects keyed by column
+    Json,
+    /// One file per table with a row object on each line
+    Ndjson,
+    /// Flyway migration V<version>__seed_<workbook>.sql (needs --version)
+    Flyway,
+    /// Laravel seeder class <Workbook>Seeder.php with DB::table()->insert() calls
+    LaravelSeeder,
+    /// Ruby for db/seeds.rb calling Model.insert_all or Model.create!
+    RailsSeed,
+    /// Django fixture with {"model": "app.model", "pk": ..., "fields": {...}} objects (needs --app)
+    DjangoFixture,
+    /// Liquibase XML changelog with an insert changeSet per sheet
+    Liquibase,
+    /// Liquibase YAML changelog with an insert changeSet per sheet
+    LiquibaseYaml,
+    /// One columnar file per table with inferred column types (needs the parquet feature)
+    Parquet,
+    /// dbt seeds/<table>.csv files plus a seeds.yml with inferred column types
+    DbtSeed,
+}
+
+impl OutputFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            OutputFormat::Sql | OutputFormat::Flyway => "sql",
+            OutputFormat::Csv | OutputFormat::DbtSeed => "csv",
+            OutputFormat::Tsv => "tsv",
+            OutputFormat::Json => "json",
+            OutputFormat::Ndjson => "ndjson",
+            OutputFormat::LaravelSeeder => "php",
+            OutputFormat::RailsSeed => "rb",
+            OutputFormat::DjangoFixture => "json",
+            OutputFormat::Liquibase => "xml",
+            OutputFormat::LiquibaseYaml => "yaml",
+            OutputFormat::Parquet => "parquet",
+        }
+    }
+
+    /// Whether every table needs a file of its own
+    pub fn per_table(self) -> bool {
+        !matches!(
+            self,
+            OutputFormat::Sql
+                | OutputFormat::Flyway
+                | OutputFormat::LaravelSeeder
+                | OutputFormat::RailsSeed
+                | OutputFormat::DjangoFixture
+                | OutputFormat::Liquibase
+                | OutputFormat::LiquibaseYaml
+        )
+    }
+
+    /// Whether the file name follows the format's conventions, making
+    /// --output the directory it is written into
+    pub fn names_file(self) -> bool {
+        matches!(self, OutputFormat::Flyway | OutputFormat::LaravelSeeder)
+    }
+
+    /// How the format's values are quoted, for --line-ending
+    pub fn quoting(self) -> Quoting {
+        match self {
+            OutputFormat::Sql | OutputFormat::Flyway => Quoting::Sql,
+            OutputFormat::Csv | OutputFormat::Tsv | OutputFormat::DbtSeed => Quoting::Csv,
+            OutputFormat::LaravelSeeder => Quoting::Php,
+            OutputFormat::Json
+            | OutputFormat::Ndjson
+            | OutputFormat::RailsSeed
+            | OutputFormat::DjangoFixture
+            | OutputFormat::Liquibase
+            | OutputFormat::LiquibaseYaml
+            | OutputFormat::Parquet => Quoting::Escaped,
+        }
+    }
+
+    /// Field separator of the delimited formats
+    pub fn delimiter(self) -> Option<u8> {
+        match self {
+            OutputFormat::Csv | OutputFormat::DbtSeed => Some(b','),
+            OutputFormat::Tsv => Some(b'\t'),
+            _ => None,
+        }
+    }
+}
+
+/// `n` as an integer when it has no fraction and fits into one exactly.
+/// Excel stores every number as a float, so whole numbers arrive as floats
+pub fn whole_number(n: f64) -> Option<i64> {
+    (n.fract() == 0.0 && n.abs() < 9e15).then_some(n as i64)
+}
+
+/// Rows per insert call of the seeder formats, well below the 65535 bind
+/// parameters MySQL and PostgreSQL allow in one query for tables of up to
+/// 130 columns
+pub const SEEDER_CHUNK_ROWS: usize = 500;
+
+/// `value` as a literal in a seeder's language, where booleans and numbers
+/// are written as they are and other values become strings quoted by `string`.
+/// Decimals stay strings so the language's floats do not round them
+pub fn seeder_literal(value: &SqlValue, string: impl Fn(&str) -> String) -> String {
+    match value {
+        SqlValue::Boolean(b) => b.to_string(),
+        SqlValue::Integer(i) => i.to_string(),
+        SqlValue::Number(n) if n.is_finite() => {
+            whole_number(*n).map_or_else(|| format!("{n:?}"), |i| i.to_string())
+        }
+        other => string(&plain_value(other).unwrap_or_default()),
+    }
+}
+
+/// The converted value as plain text, `None` for NULL
+pub fn plain_value(value: &SqlValue) -> Option<String> {
+    Some(match value {
+        SqlValue::Null => return None,
+        SqlValue::Text(s)
+        | SqlValue::DateTime(s)
+        | SqlValue::Json(s)
+        | SqlValue::Uuid(s)
+        | SqlValue::Decimal(s) => s.clone(),
+        SqlValue::Number(n) => n.to_string(),
+        SqlValue::Integer(i) => i.to_string(),
+        SqlValue::Boolean(b) => b.to_string(),
+        SqlValue::Array(items) => serde_json::to_string(items).unwrap_or_default(),
+        SqlValue::Binary(bytes) => bytes.iter().map(|byte| format!("{byte:02x}")).collect(),
+        SqlValue::Duration(d) => format_duration(d),
+    })
+}
+
+/// A header row of the column names, then one record per row; NULL is an
+/// empty field
+pub fn format_delimited(statement: &SqlStatement, delimiter: u8) -> String {
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(delimiter)
+        .from_writer(Vec::new());
+    let write = |writer: &mut csv::Writer<Vec<u8>>, record: Vec<String>| {
+        writer
+            .write_record(record)
+            .expect("writing to memory does not fail");
+    };
+    write(&mut writer, statement.columns.clone());
+    for row in &statement.values {
+        let record = row
+            .iter()
+            .map(|value| plain_value(value).unwrap_or_default())
+            .collect();
+        write(&mut writer, record);
+    }
+    let bytes = writer
+        .into_inner()
+        .expect("writing to memory does not fail");
+    String::from_utf8(bytes).expect("records are built from strings")
+}
+
+/// `value` as JSON: numbers stay numbers, NULL is null and JSON cells are
+/// embedded as documents
+pub fn json_value(value: &SqlValue) -> serde_json::Value {
+    use serde_json::Value;
+    match value {
+        SqlValue::Null => Value::Null,
+        SqlValue::Integer(i) => Value::from(*i),
+        SqlValue::Number(n) => match whole_number(*n) {
+            Some(i) => Value::from(i),
+            // Infinite and NaN floats have no JSON form
+            None => serde_json::Number::from_f64(*n).map_or(Value::Null, Value::Number),
+        },
+        SqlValue::Boolean(b) => Value::Bool(*b),
+        SqlValue::Decimal(s) => s
+            .parse::<serde_json::Number>()
+            .map_or_else(|_| Value::String(s.clone()), Value::Number),
+        SqlValue::Json(s) => serde_json::from_str(s).unwrap_or_else(|_| Value::String(s.clone())),
+        SqlValue::Array(items) => items.iter().cloned().map(Value::String).collect(),
+        other => plain_value(other).map_or(Value::Null, Value::String),
+    }
+}
+
+/// One row object per line, keys in column order
+pub fn format_ndjson(statement: &SqlStatement) -> String {
+    let mut lines = String::new();
+    for row in &statement.values {
+        lines.push_str(&json_row(&statement.columns, row));
+        lines.push('\n');
+    }
+    lines
+}
+
+pub fn json_row(columns: &[String], row: &[SqlValue]) -> String {
+    let object: serde_json::Map<String, serde_json::Value> = columns
+        .iter()
+        .cloned()
+        .zip(row.iter().map(json_value))
+        .collect();
+    serde_json::Value::Object(object).to_string()
+}
+
+/// Wrap the NDJSON lines of each run of same-table parts into one JSON array
+pub fn join_json(parts: &[TableOutput]) -> String {
+    let mut joined = String::new();
+    for (i, part) in parts.iter().enumerate() {
+        let continues = i > 0 && parts[i - 1].table == part.table;
+        let ends = parts.get(i + 1).is_none_or(|next| next.table != part.table);
+        let rows: Vec<&str> = part.content.lines().collect();
+        if !continues {
+            joined.push('[');
+        } else if !rows.is_empty() && !joined.ends_with('[') {
+            joined.push(',');
+        }
+        for (j, row) in rows.iter().enumerate() {
+            joined.push_str(if j == 0 { "\n  " } else { ",\n  " });
+            joined.push_str(row);
+        }
+        if ends {
+            joined.push_str(if joined.ends_with('[') {
+                "]\n"
+            } else {
+                "\n]\n"
+            });
+        }
+    }
+    joined
+}
+
+/// Concatenate delimited parts, keeping one header row for consecutive parts
+/// of the same table
+pub fn join_delimited(parts: &[TableOutput], delimiter: u8) -> String {
+    let mut joined = String::new();
+    for (i, part) in parts.iter().enumerate() {
+        let content = match i.checked_sub(1).map(|prev| &parts[prev]) {
+            Some(prev) if prev.table == part.table => {
+                &part.content[header_len(&part.content, delimiter)..]
+            }
+            _ => part.content.as_str(),
+        };
+        joined.push_str(content);
+    }
+    joined
+}
+
+/// Byte length of the header record, which may hold quoted line breaks
+fn header_len(content: &str, delimiter: u8) -> usize {
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .has_headers(false)
+        .from_reader(content.as_bytes());
+    let mut header = csv::ByteRecord::new();
+    match reader.read_byte_record(&mut header) {
+        Ok(true) => reader.position().byte() as usize,
+        _ => content.len(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_delimited() {
+        let statement = SqlStatement {
+            table_name: "people".to_string(),
+            columns: vec!["id".to_string(), "name".to_string(), "active".to_string()],
+            values: vec![
+                vec![
+                    SqlValue::Integer(1),
+                    SqlValue::Text("Smith, John".to_string()),
+                    SqlValue::Boolean(true),
+                ],
+                vec![SqlValue::Number(2.5), SqlValue::Null, SqlValue::Null],
+            ],
+            warnings: vec![],
+            stats: Default::default(),
+        };
+        assert_eq!(
+            format_delimited(&statement, b','),
+            "id,name,active\n1,\"Smith, John\",true\n2.5,,\n"
+        );
+        assert_eq!(
+            format_delimited(&statement, b'\t'),
+            "id\tname\tactive\n1\tSmith, John\ttrue\n2.5\t\t\n"
+        );
+    }
+
+    #[test]
+    fn test_join_delimited() {
+        let part = |table: &str, content: &str| TableOutput {
+            table: table.to_string(),
+            content: content.to_string(),
+            rows: 1,
+        };
+        let parts = [
+            part("a", "id,\"line\nbreak\"\n1,x\n"),
+            part("a", "id,\"line\nbreak\"\n2,y\n"),
+            part("b", "sku\nx\n"),
+        ];
+        assert_eq!(
+            join_delimited(&parts, b','),
+            "id,\"line\nbreak\"\n1,x\n2,y\nsku\nx\n"
+        );
+    }
+
+    #[test]
+    fn test_format_json() {
+        let statement = SqlStatement {
+            table_name: "orders".to_string(),
+            columns: vec![
+                "id".to_string(),
+                "total".to_string(),
+                "note".to_string(),
+                "tags".to_string(),
+            ],
+            values: vec![
+                vec![
+                    SqlValue::Integer(1),
+                    SqlValue::Decimal("9.50".to_string()),
+                    SqlValue::Null,
+                    SqlValue::Array(vec!["a".to_string()]),
+                ],
+                vec![
+                    SqlValue::Number(2.0),
+                    SqlValue::Number(f64::NAN),
+                    SqlValue::Text("say \"hi\"".to_string()),
+                    SqlValue::Json(r#"{"z": 1, "a": true}"#.to_string()),
+                ],
+            ],
+            warnings: vec![],
+            stats: Default::default(),
+        };
+        let ndjson = format_ndjson(&statement);
+        assert_eq!(
+            ndjson,
+            concat!(
+                r#"{"id":1,"total":9.5,"note":null,"tags":["a"]}"#,
+                "\n",
+                r#"{"id":2,"total":null,"note":"say \"hi\"","tags":{"z":1,"a":true}}"#,
+                "\n"
+            )
+        );
+
+        let part = |table: &str, content: &str| TableOutput {
+            table: table.to_string(),
+            content: content.to_string(),
+            rows: 1,
+        };
+        let parts = [
+            part("orders", "{\"id\":1}\n"),
+            part("orders", "{\"id\":2}\n"),
+            part("empty", ""),
+        ];
+        assert_eq!(join_json(&parts), "[\n  {\"id\":1},\n  {\"id\":2}\n]\n[]\n");
+    }
+
+    #[test]
+    fn test_plain_value() {
+        assert_eq!(plain_value(&SqlValue::Null), None);
+        assert_eq!(
+            plain_value(&SqlValue::Array(vec!["a".to_string(), "b\"c".to_string()])).unwrap(),
+            r#"["a","b\"c"]"#
+        );
+        assert_eq!(
+            plain_value(&SqlValue::Binary(vec![0xca, 0xfe])).unwrap(),
+            "cafe"
+        );
+    }
+
+    #[test]
+    fn test_whole_number() {
+        assert_eq!(whole_number(42.0), Some(42));
+        assert_eq!(whole_number(-3.0), Some(-3));
+        assert_eq!(whole_number(1.5), None);
+        // Past 2^53 floats no longer hold every integer
+        assert_eq!(whole_number(1e16), None);
+        assert_eq!(whole_number(f64::NAN), None);
+    }
+}
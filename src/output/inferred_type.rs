@@ -0,0 +1,115 @@
+// Column types inferred from converted values, for typed output formats
+use crate::output::format::whole_number;
+use crate::parser::data_model::SqlValue;
+use chrono::{NaiveDate, NaiveDateTime};
+
+/// Column type inferred from the converted values
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InferredType {
+    Boolean,
+    Int64,
+    Float64,
+    Decimal { scale: i8 },
+    Timestamp,
+    Binary,
+    List,
+    Utf8,
+}
+
+impl InferredType {
+    /// Generic name of the type, for reports and templates
+    pub fn name(self) -> &'static str {
+        match self {
+            InferredType::Boolean => "boolean",
+            InferredType::Int64 => "integer",
+            InferredType::Float64 => "float",
+            InferredType::Decimal { .. } => "decimal",
+            InferredType::Timestamp => "timestamp",
+            InferredType::Binary => "binary",
+            InferredType::List => "list",
+            InferredType::Utf8 => "text",
+        }
+    }
+}
+
+/// The narrowest type that holds every non-NULL value of a column; mixed
+/// numbers widen to a decimal or float, anything else mixed falls back to text
+pub fn infer_type<'a>(values: impl Iterator<Item = &'a SqlValue>) -> InferredType {
+    use InferredType::*;
+    let mut inferred = None;
+    for value in values {
+        let value_type = match value {
+            SqlValue::Null => continue,
+            SqlValue::Boolean(_) => Boolean,
+            SqlValue::Integer(_) => Int64,
+            SqlValue::Number(n) if whole_number(*n).is_some() => Int64,
+            SqlValue::Number(_) => Float64,
+            SqlValue::Decimal(s) => match decimal_scale(s) {
+                Some(scale) => Decimal { scale },
+                None => return Utf8,
+            },
+            SqlValue::DateTime(s) if parse_timestamp(s).is_some() => Timestamp,
+            SqlValue::Binary(_) => Binary,
+            SqlValue::Array(_) => List,
+            _ => return Utf8,
+        };
+        inferred = Some(match (inferred, value_type) {
+            (None, t) => t,
+            (Some(a), b) if a == b => a,
+            (Some(Decimal { scale: a }), Decimal { scale: b }) => Decimal { scale: a.max(b) },
+            (Some(Decimal { scale }), Int64) | (Some(Int64), Decimal { scale }) => {
+                Decimal { scale }
+            }
+            (Some(Int64 | Float64 | Decimal { .. }), Int64 | Float64 | Decimal { .. }) => Float64,
+            (Some(Boolean), Int64) | (Some(Int64), Boolean) => Int64,
+            _ => return Utf8,
+        });
+    }
+    inferred.unwrap_or(Utf8)
+}
+
+/// Digits after the point of a rendered decimal, if it fits Decimal128
+pub fn decimal_scale(decimal: &str) -> Option<i8> {
+    let scale = decimal
+        .split_once('.')
+        .map_or(0, |(_, fraction)| fraction.len());
+    i8::try_from(scale).ok().filter(|scale| *scale <= 38)
+}
+
+pub fn parse_timestamp(text: &str) -> Option<NaiveDateTime> {
+    ["%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M:%S%.f"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(text, format).ok())
+        .or_else(|| {
+            NaiveDate::parse_from_str(text, "%Y-%m-%d")
+                .ok()
+                .map(|date| date.and_hms_opt(0, 0, 0).expect("midnight exists"))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_infer_type() {
+        let ints = [SqlValue::Number(1.0), SqlValue::Null, SqlValue::Integer(2)];
+        assert_eq!(infer_type(ints.iter()), InferredType::Int64);
+        let floats = [SqlValue::Integer(1), SqlValue::Number(1.5)];
+        assert_eq!(infer_type(floats.iter()), InferredType::Float64);
+        let decimals = [
+            SqlValue::Decimal("1.5".to_string()),
+            SqlValue::Integer(3),
+            SqlValue::Decimal("2.25".to_string()),
+        ];
+        assert_eq!(
+            infer_type(decimals.iter()),
+            InferredType::Decimal { scale: 2 }
+        );
+        let dates = [SqlValue::DateTime("2025-07-25 00:00:00".to_string())];
+        assert_eq!(infer_type(dates.iter()), InferredType::Timestamp);
+        let mixed = [SqlValue::Integer(1), SqlValue::Text("n/a".to_string())];
+        assert_eq!(infer_type(mixed.iter()), InferredType::Utf8);
+        assert_eq!(infer_type([SqlValue::Null].iter()), InferredType::Utf8);
+    }
+}
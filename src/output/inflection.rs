@@ -0,0 +1,104 @@
+// Model class names derived from table names (--model, --no-singularize)
+
+/// How table names become model names for framework seed formats
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ModelNames {
+    /// `TABLE=Model` overrides of the inflected names
+    pub overrides: Vec<(String, String)>,
+    /// Singularize table names (`order_items` -> `OrderItem`)
+    pub singularize: bool,
+}
+
+/// Parse a `TABLE=MODEL` argument
+pub fn parse_model_arg(arg: &str) -> Result<(String, String), String> {
+    match arg.split_once('=') {
+        Some((table, model)) if !table.trim().is_empty() && !model.trim().is_empty() => {
+            Ok((table.trim().to_string(), model.trim().to_string()))
+        }
+        _ => Err(format!("expected TABLE=MODEL, got '{arg}'")),
+    }
+}
+
+impl ModelNames {
+    /// The model class for `table`: an override, else the camel-cased table
+    /// name with its last word singularized
+    pub fn model_name(&self, table: &str) -> String {
+        if let Some((_, model)) = self.overrides.iter().find(|(t, _)| t == table) {
+            return model.clone();
+        }
+        let words: Vec<&str> = table
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .collect();
+        let last = words.len().saturating_sub(1);
+        words
+            .iter()
+            .enumerate()
+            .map(|(i, word)| {
+                let word = word.to_lowercase();
+                let word = match i == last && self.singularize {
+                    true => singularize(&word),
+                    false => word,
+                };
+                let mut chars = word.chars();
+                match chars.next() {
+                    Some(first) => first.to_uppercase().chain(chars).collect(),
+                    None => String::new(),
+                }
+            })
+            .collect()
+    }
+}
+
+/// English singular of a lowercase word, following the common cases of
+/// ActiveSupport's rules
+fn singularize(word: &str) -> String {
+    const IRREGULAR: [(&str, &str); 6] = [
+        ("people", "person"),
+        ("men", "man"),
+        ("women", "woman"),
+        ("children", "child"),
+        ("mice", "mouse"),
+        ("data", "datum"),
+    ];
+    if let Some((_, singular)) = IRREGULAR.iter().find(|(plural, _)| *plural == word) {
+        return singular.to_string();
+    }
+    if let Some(stem) = word.strip_suffix("ies") {
+        return format!("{stem}y");
+    }
+    for suffix in ["sses", "shes", "ches", "xes", "zes"] {
+        if word.ends_with(suffix) {
+            return word[..word.len() - 2].to_string();
+        }
+    }
+    if ["ss", "us", "is"]
+        .iter()
+        .any(|suffix| word.ends_with(suffix))
+    {
+        return word.to_string();
+    }
+    word.strip_suffix('s').unwrap_or(word).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_model_name() {
+        let names = ModelNames {
+            singularize: true,
+            overrides: vec![("tblstaff".to_string(), "Employee".to_string())],
+        };
+        assert_eq!(names.model_name("order_items"), "OrderItem");
+        assert_eq!(names.model_name("categories"), "Category");
+        assert_eq!(names.model_name("boxes"), "Box");
+        assert_eq!(names.model_name("people"), "Person");
+        assert_eq!(names.model_name("status"), "Status");
+        assert_eq!(names.model_name("tblstaff"), "Employee");
+        let plural = ModelNames::default();
+        assert_eq!(plural.model_name("order_items"), "OrderItems");
+        assert!(parse_model_arg("staff").is_err());
+    }
+}
@@ -0,0 +1,158 @@
+// Laravel seeder classes (--format laravel-seeder)
+use crate::output::format::{seeder_literal, SEEDER_CHUNK_ROWS};
+use crate::output::split::TableOutput;
+use crate::parser::data_model::{SqlStatement, SqlValue};
+
+/// `price list` becomes `PriceListSeeder`
+pub fn class_name(workbook: &str) -> String {
+    let mut name: String = workbook
+        .split(|c: char| !c.is_alphanumeric())
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect();
+    if !name.starts_with(|c: char| c.is_alphabetic()) {
+        name.insert_str(0, "Workbook");
+    }
+    if !name.ends_with("Seeder") {
+        name.push_str("Seeder");
+    }
+    name
+}
+
+/// `<Class>.php`, as PSR-4 autoloading expects
+pub fn file_name(workbook: &str) -> String {
+    format!("{}.php", class_name(workbook))
+}
+
+fn php_value(value: &SqlValue) -> String {
+    match value {
+        SqlValue::Null => "null".to_string(),
+        SqlValue::Binary(bytes) => {
+            let hex: String = bytes.iter().map(|byte| format!("{byte:02x}")).collect();
+            format!("hex2bin('{hex}')")
+        }
+        other => seeder_literal(other, php_string),
+    }
+}
+
+/// Single-quoted PHP string; only `\` and `'` are special inside
+fn php_string(text: &str) -> String {
+    format!("'{}'", text.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+/// `['column' => value, ...]` of one row
+pub fn php_row(columns: &[String], row: &[SqlValue]) -> String {
+    let fields: Vec<String> = columns
+        .iter()
+        .zip(row)
+        .map(|(column, value)| format!("{} => {}", php_string(column), php_value(value)))
+        .collect();
+    format!("[{}]", fields.join(", "))
+}
+
+/// `DB::table('x')->insert([...]);` calls for the rows of `statement`
+pub fn table_insert(statement: &SqlStatement) -> String {
+    let mut code = String::new();
+    for rows in statement.values.chunks(SEEDER_CHUNK_ROWS) {
+        code.push_str(&format!(
+            "        DB::table({})->insert([\n",
+            php_string(&statement.table_name)
+        ));
+        for row in rows {
+            code.push_str(&format!(
+                "            {},\n",
+                php_row(&statement.columns, row)
+            ));
+        }
+        code.push_str("        ]);\n");
+    }
+    code
+}
+
+/// The seeder class `class` running the inserts of `parts`
+pub fn seeder_class(class: &str, workbook: &str, parts: &[TableOutput]) -> String {
+    let inserts: String = parts.iter().map(|part| part.content.as_str()).collect();
+    format!(
+        "<?php
+
+namespace Database\\Seeders;
+
+use Illuminate\\Database\\Seeder;
+use Illuminate\\Support\\Facades\\DB;
+
+class {class} extends Seeder
+{{
+    /**
+     * Seed the database with the sheets of {workbook}.
+     */
+    public function run(): void
+    {{
+{inserts}    }}
+}}
+"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_class_name() {
+        assert_eq!(class_name("price list"), "PriceListSeeder");
+        assert_eq!(class_name("UserSeeder"), "UserSeeder");
+        assert_eq!(class_name("2024-q1"), "Workbook2024Q1Seeder");
+        assert_eq!(file_name("orders"), "OrdersSeeder.php");
+    }
+
+    #[test]
+    fn test_seeder_class() {
+        let statement = SqlStatement {
+            table_name: "products".to_string(),
+            columns: vec![
+                "id".to_string(),
+                "name".to_string(),
+                "price".to_string(),
+                "active".to_string(),
+            ],
+            values: vec![
+                vec![
+                    SqlValue::Number(1.0),
+                    SqlValue::Text("O'Brien \\ Co".to_string()),
+                    SqlValue::Decimal("9.50".to_string()),
+                    SqlValue::Boolean(true),
+                ],
+                vec![
+                    SqlValue::Integer(2),
+                    SqlValue::Null,
+                    SqlValue::Number(0.5),
+                    SqlValue::Boolean(false),
+                ],
+            ],
+            warnings: vec![],
+            stats: Default::default(),
+        };
+        let part = TableOutput {
+            table: "products".to_string(),
+            content: table_insert(&statement),
+            rows: 2,
+        };
+        let class = seeder_class("ShopSeeder", "shop.xlsx", &[part]);
+        assert!(class.starts_with("<?php\n\nnamespace Database\\Seeders;\n"));
+        assert!(class.contains(concat!(
+            "    public function run(): void\n",
+            "    {\n",
+            "        DB::table('products')->insert([\n",
+            "            ['id' => 1, 'name' => 'O\\'Brien \\\\ Co', 'price' => '9.50', 'active' => true],\n",
+            "            ['id' => 2, 'name' => null, 'price' => 0.5, 'active' => false],\n",
+            "        ]);\n",
+            "    }\n",
+            "}\n"
+        )));
+    }
+}
@@ -0,0 +1,202 @@
+// Liquibase changelogs with one insert changeSet per sheet (--format liquibase)
+use crate::output::format::plain_value;
+use crate::output::split::TableOutput;
+use crate::parser::data_model::{SqlStatement, SqlValue};
+
+const XML_HEADER: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<databaseChangeLog
+    xmlns="http://www.liquibase.org/xml/ns/dbchangelog"
+    xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"
+    xsi:schemaLocation="http://www.liquibase.org/xml/ns/dbchangelog http://www.liquibase.org/xml/ns/dbchangelog/dbchangelog-latest.xsd">
+"#;
+
+/// changeSet id for the `part`th (0-based) changeSet into `table` of the
+/// workbook named `stem`; ids stay stable as long as the sheets do
+pub fn change_set_id(stem: &str, table: &str, part: usize) -> String {
+    match part {
+        0 => format!("{stem}-{table}"),
+        n => format!("{stem}-{table}-{}", n + 1),
+    }
+}
+
+/// Liquibase column attribute and value for `value`
+fn column_value(value: &SqlValue) -> (&'static str, String) {
+    match value {
+        SqlValue::Null => ("valueComputed", "NULL".to_string()),
+        SqlValue::Integer(_) | SqlValue::Number(_) | SqlValue::Decimal(_) => {
+            ("valueNumeric", plain_value(value).unwrap_or_default())
+        }
+        SqlValue::Boolean(b) => ("valueBoolean", b.to_string()),
+        SqlValue::DateTime(s) => ("valueDate", s.clone()),
+        other => ("value", plain_value(other).unwrap_or_default()),
+    }
+}
+
+/// `<insert>` of one row
+pub fn xml_insert(table: &str, columns: &[String], row: &[SqlValue]) -> String {
+    let mut insert = format!("        <insert tableName=\"{}\">\n", xml_escape(table));
+    for (column, value) in columns.iter().zip(row) {
+        let (attribute, text) = column_value(value);
+        insert.push_str(&format!(
+            "            <column name=\"{}\" {attribute}=\"{}\"/>\n",
+            xml_escape(column),
+            xml_escape(&text)
+        ));
+    }
+    insert.push_str("        </insert>\n");
+    insert
+}
+
+pub fn xml_change_set(statement: &SqlStatement, id: &str, author: &str) -> String {
+    let mut change_set = format!(
+        "    <changeSet id=\"{}\" author=\"{}\">\n",
+        xml_escape(id),
+        xml_escape(author)
+    );
+    if statement.values.is_empty() {
+        change_set.push_str("        <empty/>\n");
+    }
+    for row in &statement.values {
+        change_set.push_str(&xml_insert(&statement.table_name, &statement.columns, row));
+    }
+    change_set.push_str("    </changeSet>\n");
+    change_set
+}
+
+pub fn xml_changelog(parts: &[TableOutput]) -> String {
+    let change_sets: String = parts.iter().map(|part| part.content.as_str()).collect();
+    format!("{XML_HEADER}{change_sets}</databaseChangeLog>\n")
+}
+
+/// `- insert:` of one row
+pub fn yaml_insert(table: &str, columns: &[String], row: &[SqlValue]) -> String {
+    let mut insert = format!(
+        "        - insert:\n            tableName: {}\n            columns:\n",
+        yaml_string(table)
+    );
+    for (column, value) in columns.iter().zip(row) {
+        let (attribute, text) = column_value(value);
+        let text = match attribute {
+            "valueNumeric" | "valueBoolean" => text,
+            _ => yaml_string(&text),
+        };
+        insert.push_str(&format!(
+            "              - column:\n                  name: {}\n                  {attribute}: {text}\n",
+            yaml_string(column)
+        ));
+    }
+    insert
+}
+
+pub fn yaml_change_set(statement: &SqlStatement, id: &str, author: &str) -> String {
+    let mut change_set = format!(
+        "  - changeSet:\n      id: {}\n      author: {}\n      changes:\n",
+        yaml_string(id),
+        yaml_string(author)
+    );
+    if statement.values.is_empty() {
+        change_set.push_str("        - empty: {}\n");
+    }
+    for row in &statement.values {
+        change_set.push_str(&yaml_insert(&statement.table_name, &statement.columns, row));
+    }
+    change_set
+}
+
+pub fn yaml_changelog(parts: &[TableOutput]) -> String {
+    let change_sets: String = parts.iter().map(|part| part.content.as_str()).collect();
+    match change_sets.is_empty() {
+        true => "databaseChangeLog: []\n".to_string(),
+        false => format!("databaseChangeLog:\n{change_sets}"),
+    }
+}
+
+/// Escaped for an XML attribute; line breaks and tabs become character
+/// references so parsers do not normalize them to spaces
+fn xml_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\n' => escaped.push_str("&#10;"),
+            '\r' => escaped.push_str("&#13;"),
+            '\t' => escaped.push_str("&#9;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// A double-quoted YAML scalar; JSON string syntax is valid YAML
+fn yaml_string(text: &str) -> String {
+    serde_json::Value::from(text).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn statement() -> SqlStatement {
+        SqlStatement {
+            table_name: "orders".to_string(),
+            columns: vec!["id".to_string(), "note".to_string(), "paid".to_string()],
+            values: vec![vec![
+                SqlValue::Number(1.0),
+                SqlValue::Text("Tom & \"Jerry\"\nline".to_string()),
+                SqlValue::Null,
+            ]],
+            warnings: vec![],
+            stats: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_xml_change_set() {
+        let part = TableOutput {
+            table: "orders".to_string(),
+            content: xml_change_set(&statement(), &change_set_id("seed", "orders", 0), "seed"),
+            rows: 1,
+        };
+        let changelog = xml_changelog(&[part]);
+        assert!(changelog.starts_with("<?xml"));
+        assert!(changelog.contains(concat!(
+            "    <changeSet id=\"seed-orders\" author=\"seed\">\n",
+            "        <insert tableName=\"orders\">\n",
+            "            <column name=\"id\" valueNumeric=\"1\"/>\n",
+            "            <column name=\"note\" value=\"Tom &amp; &quot;Jerry&quot;&#10;line\"/>\n",
+            "            <column name=\"paid\" valueComputed=\"NULL\"/>\n",
+            "        </insert>\n",
+            "    </changeSet>\n",
+            "</databaseChangeLog>\n"
+        )));
+    }
+
+    #[test]
+    fn test_yaml_change_set() {
+        assert_eq!(
+            yaml_change_set(&statement(), &change_set_id("seed", "orders", 1), "seed"),
+            concat!(
+                "  - changeSet:\n",
+                "      id: \"seed-orders-2\"\n",
+                "      author: \"seed\"\n",
+                "      changes:\n",
+                "        - insert:\n",
+                "            tableName: \"orders\"\n",
+                "            columns:\n",
+                "              - column:\n",
+                "                  name: \"id\"\n",
+                "                  valueNumeric: 1\n",
+                "              - column:\n",
+                "                  name: \"note\"\n",
+                "                  value: \"Tom & \\\"Jerry\\\"\\nline\"\n",
+                "              - column:\n",
+                "                  name: \"paid\"\n",
+                "                  valueComputed: \"NULL\"\n",
+            )
+        );
+        assert_eq!(yaml_changelog(&[]), "databaseChangeLog: []\n");
+    }
+}
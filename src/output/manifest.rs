@@ -0,0 +1,121 @@
+// Sidecar describing a run's output, for checking a dump before loading it (--manifest)
+use crate::errors::OutputError;
+use crate::input::file_handler::sha256_bytes;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Manifest {
+    pub generator: String,
+    pub source: Source,
+    /// Sheets the tables were converted from, in workbook order
+    pub sheets: Vec<String>,
+    pub tables: Vec<TableRows>,
+    pub files: Vec<FileDigest>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Source {
+    pub path: String,
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TableRows {
+    pub name: String,
+    pub rows: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FileDigest {
+    /// Relative to the manifest's directory when inside it
+    pub path: String,
+    pub bytes: usize,
+    pub sha256: String,
+}
+
+impl Manifest {
+    /// Manifest of `files`, the paths and contents exactly as written
+    pub fn new(
+        source: Source,
+        sheets: Vec<String>,
+        parts: &[(String, usize)],
+        files: &[(PathBuf, String)],
+        manifest_path: &Path,
+    ) -> Self {
+        let mut tables: Vec<TableRows> = Vec::new();
+        for (name, rows) in parts {
+            match tables.iter_mut().find(|table| table.name == *name) {
+                Some(table) => table.rows += rows,
+                None => tables.push(TableRows {
+                    name: name.clone(),
+                    rows: *rows,
+                }),
+            }
+        }
+        let base = manifest_path.parent().unwrap_or(Path::new(""));
+        let files = files
+            .iter()
+            .map(|(path, content)| FileDigest {
+                path: path
+                    .strip_prefix(base)
+                    .unwrap_or(path)
+                    .display()
+                    .to_string(),
+                bytes: content.len(),
+                sha256: sha256_bytes(content.as_bytes()),
+            })
+            .collect();
+        Self {
+            generator: format!("xlsx2sql {}", env!("CARGO_PKG_VERSION")),
+            source,
+            sheets,
+            tables,
+            files,
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String, OutputError> {
+        serde_json::to_string_pretty(self)
+            .map(|json| json + "\n")
+            .map_err(|e| OutputError::WriteError(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manifest() {
+        let part = |table: &str, rows| (table.to_string(), rows);
+        let file = |path: &str, content: &str| (PathBuf::from(path), content.to_string());
+        let manifest = Manifest::new(
+            Source {
+                path: "shop.xlsx".to_string(),
+                sha256: "abc".to_string(),
+            },
+            vec!["Orders".to_string()],
+            &[part("orders", 2), part("orders", 3)],
+            &[
+                file("out/orders.csv", "id\n1\n"),
+                file("/elsewhere/x.csv", ""),
+            ],
+            Path::new("out/manifest.json"),
+        );
+        assert_eq!(
+            manifest.tables,
+            vec![TableRows {
+                name: "orders".to_string(),
+                rows: 5
+            }]
+        );
+        assert_eq!(manifest.files[0].path, "orders.csv");
+        assert_eq!(manifest.files[0].bytes, 5);
+        assert_eq!(manifest.files[1].path, "/elsewhere/x.csv");
+        assert_eq!(
+            manifest.files[1].sha256,
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+    }
+}
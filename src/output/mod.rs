@@ -1,3 +1,29 @@
+pub mod custom_template;
+pub mod database;
+pub mod dbt;
+pub mod deps_graph;
+pub mod diff;
+pub mod django;
+pub mod flyway;
+pub mod format;
+pub mod inferred_type;
+pub mod inflection;
+pub mod laravel;
+pub mod liquibase;
+pub mod manifest;
+#[cfg(feature = "parquet")]
+pub mod parquet;
+pub mod profile;
+pub mod rails;
+pub mod report;
+pub mod row_state;
+pub mod run_record;
+pub mod s3;
+pub mod split;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+pub mod summary;
+pub mod timings;
 pub mod writer;
 
 pub use writer::*;
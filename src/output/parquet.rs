@@ -0,0 +1,297 @@
+// Columnar output for data-lake ingestion (--format parquet)
+use crate::errors::OutputError;
+use crate::output::format::plain_value;
+use crate::output::inferred_type::{infer_type, InferredType};
+use crate::output::split::{numbered_path, split_statement, table_path, FileLimits};
+use crate::parser::data_model::{SqlStatement, SqlValue};
+use std::path::{Path, PathBuf};
+
+/// `decimal` as an integer count of 10^-scale units
+fn decimal_units(decimal: &str, scale: i8) -> Option<i128> {
+    let (whole, fraction) = decimal.split_once('.').unwrap_or((decimal, ""));
+    let padding = usize::try_from(scale).ok()?.checked_sub(fraction.len())?;
+    format!("{whole}{fraction}{}", "0".repeat(padding))
+        .parse()
+        .ok()
+}
+
+/// Write each table to `<dir>/<table>.parquet`, rolling over into numbered
+/// files when `limits` are set; returns every file written and its rows
+pub fn write_tables(
+    dir: &Path,
+    statements: Vec<SqlStatement>,
+    limits: FileLimits,
+) -> Result<Vec<(PathBuf, usize)>, OutputError> {
+    std::fs::create_dir_all(dir)?;
+    let mut files = Vec::new();
+    for table in merge_tables(statements)? {
+        let path = table_path(dir, &table.table_name, "parquet");
+        let types: Vec<InferredType> = (0..table.columns.len())
+            .map(|i| infer_type(table.values.iter().filter_map(|row| row.get(i))))
+            .collect();
+        let row_bytes = |row: &[SqlValue]| {
+            row.iter()
+                .map(|value| plain_value(value).map_or(1, |text| text.len()))
+                .sum()
+        };
+        let chunks = split_statement(table, limits, 0, row_bytes);
+        for (index, chunk) in chunks.iter().enumerate() {
+            let path = match limits.is_unlimited() {
+                true => path.clone(),
+                false => numbered_path(&path, index),
+            };
+            client::write(&path, chunk, &types)
+                .map_err(|e| OutputError::WriteError(format!("{}: {e}", path.display())))?;
+            files.push((path, chunk.values.len()));
+        }
+    }
+    Ok(files)
+}
+
+/// One statement per table, in order of first appearance
+fn merge_tables(statements: Vec<SqlStatement>) -> Result<Vec<SqlStatement>, OutputError> {
+    let mut tables: Vec<SqlStatement> = Vec::new();
+    for statement in statements {
+        match tables
+            .iter_mut()
+            .find(|table| table.table_name == statement.table_name)
+        {
+            Some(table) if table.columns == statement.columns => {
+                table.values.extend(statement.values)
+            }
+            Some(table) => {
+                return Err(OutputError::WriteError(format!(
+                    "Sheets written to table {} have different columns",
+                    table.table_name
+                )))
+            }
+            None => tables.push(statement),
+        }
+    }
+    Ok(tables)
+}
+
+mod client {
+    use super::decimal_units;
+    use crate::output::format::plain_value;
+    use crate::output::inferred_type::{parse_timestamp, InferredType};
+    use crate::parser::data_model::{SqlStatement, SqlValue};
+    use arrow_array::builder::{
+        BinaryBuilder, BooleanBuilder, Decimal128Builder, Float64Builder, Int64Builder,
+        ListBuilder, StringBuilder, TimestampMicrosecondBuilder,
+    };
+    use arrow_array::{ArrayRef, RecordBatch};
+    use arrow_schema::{DataType, Field, Schema, TimeUnit};
+    use parquet::arrow::ArrowWriter;
+    use parquet::basic::Compression;
+    use parquet::file::properties::WriterProperties;
+    use std::path::Path;
+    use std::sync::Arc;
+
+    pub fn write(
+        path: &Path,
+        statement: &SqlStatement,
+        types: &[InferredType],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let fields: Vec<Field> = statement
+            .columns
+            .iter()
+            .zip(types)
+            .map(|(column, column_type)| Field::new(column, data_type(*column_type), true))
+            .collect();
+        let schema = Arc::new(Schema::new(fields));
+        let columns = types
+            .iter()
+            .enumerate()
+            .map(|(i, column_type)| {
+                let values = statement.values.iter().map(|row| row.get(i));
+                column(values, *column_type)
+            })
+            .collect::<Result<Vec<ArrayRef>, _>>()?;
+        let batch = RecordBatch::try_new(schema.clone(), columns)?;
+
+        let properties = WriterProperties::builder()
+            .set_compression(Compression::SNAPPY)
+            .build();
+        let file = std::fs::File::create(path)?;
+        let mut writer = ArrowWriter::try_new(file, schema, Some(properties))?;
+        writer.write(&batch)?;
+        writer.close()?;
+        Ok(())
+    }
+
+    fn data_type(column_type: InferredType) -> DataType {
+        match column_type {
+            InferredType::Boolean => DataType::Boolean,
+            InferredType::Int64 => DataType::Int64,
+            InferredType::Float64 => DataType::Float64,
+            InferredType::Decimal { scale } => DataType::Decimal128(38, scale),
+            InferredType::Timestamp => DataType::Timestamp(TimeUnit::Microsecond, None),
+            InferredType::Binary => DataType::Binary,
+            InferredType::List => {
+                DataType::List(Arc::new(Field::new_list_field(DataType::Utf8, true)))
+            }
+            InferredType::Utf8 => DataType::Utf8,
+        }
+    }
+
+    /// Values `column_type` does not hold, which inference rules out, are NULL
+    fn column<'a>(
+        values: impl Iterator<Item = Option<&'a SqlValue>>,
+        column_type: InferredType,
+    ) -> Result<ArrayRef, arrow_schema::ArrowError> {
+        let values = values.map(|value| value.unwrap_or(&SqlValue::Null));
+        Ok(match column_type {
+            InferredType::Boolean => {
+                let mut builder = BooleanBuilder::new();
+                for value in values {
+                    builder.append_option(match value {
+                        SqlValue::Boolean(b) => Some(*b),
+                        _ => None,
+                    });
+                }
+                Arc::new(builder.finish())
+            }
+            InferredType::Int64 => {
+                let mut builder = Int64Builder::new();
+                for value in values {
+                    builder.append_option(match value {
+                        SqlValue::Integer(i) => Some(*i),
+                        SqlValue::Number(n) => Some(*n as i64),
+                        SqlValue::Boolean(b) => Some(i64::from(*b)),
+                        _ => None,
+                    });
+                }
+                Arc::new(builder.finish())
+            }
+            InferredType::Float64 => {
+                let mut builder = Float64Builder::new();
+                for value in values {
+                    builder.append_option(match value {
+                        SqlValue::Integer(i) => Some(*i as f64),
+                        SqlValue::Number(n) => Some(*n),
+                        SqlValue::Decimal(s) => s.parse().ok(),
+                        _ => None,
+                    });
+                }
+                Arc::new(builder.finish())
+            }
+            InferredType::Decimal { scale } => {
+                let mut builder = Decimal128Builder::new().with_precision_and_scale(38, scale)?;
+                for value in values {
+                    builder.append_option(match value {
+                        SqlValue::Decimal(s) => decimal_units(s, scale),
+                        SqlValue::Integer(i) => decimal_units(&i.to_string(), scale),
+                        SqlValue::Number(n) => decimal_units(&(*n as i64).to_string(), scale),
+                        _ => None,
+                    });
+                }
+                Arc::new(builder.finish())
+            }
+            InferredType::Timestamp => {
+                let mut builder = TimestampMicrosecondBuilder::new();
+                for value in values {
+                    builder.append_option(match value {
+                        SqlValue::DateTime(s) => {
+                            parse_timestamp(s).map(|t| t.and_utc().timestamp_micros())
+                        }
+                        _ => None,
+                    });
+                }
+                Arc::new(builder.finish())
+            }
+            InferredType::Binary => {
+                let mut builder = BinaryBuilder::new();
+                for value in values {
+                    match value {
+                        SqlValue::Binary(bytes) => builder.append_value(bytes),
+                        _ => builder.append_null(),
+                    }
+                }
+                Arc::new(builder.finish())
+            }
+            InferredType::List => {
+                let mut builder = ListBuilder::new(StringBuilder::new());
+                for value in values {
+                    match value {
+                        SqlValue::Array(items) => {
+                            for item in items {
+                                builder.values().append_value(item);
+                            }
+                            builder.append(true);
+                        }
+                        _ => builder.append(false),
+                    }
+                }
+                Arc::new(builder.finish())
+            }
+            InferredType::Utf8 => {
+                let mut builder = StringBuilder::new();
+                for value in values {
+                    builder.append_option(plain_value(value));
+                }
+                Arc::new(builder.finish())
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decimal_units() {
+        assert_eq!(decimal_units("12.5", 2), Some(1250));
+        assert_eq!(decimal_units("-0.25", 2), Some(-25));
+        assert_eq!(decimal_units("7", 0), Some(7));
+        assert_eq!(decimal_units("1.234", 2), None);
+    }
+
+    #[test]
+    fn test_merge_tables() {
+        let statement = |table: &str, column: &str, id: i64| SqlStatement {
+            table_name: table.to_string(),
+            columns: vec![column.to_string()],
+            values: vec![vec![SqlValue::Integer(id)]],
+            warnings: vec![],
+            stats: Default::default(),
+        };
+        let tables = merge_tables(vec![
+            statement("a", "id", 1),
+            statement("b", "id", 2),
+            statement("a", "id", 3),
+        ])
+        .unwrap();
+        assert_eq!(tables.len(), 2);
+        assert_eq!(tables[0].values.len(), 2);
+        assert!(merge_tables(vec![statement("a", "id", 1), statement("a", "sku", 2)]).is_err());
+    }
+
+    #[test]
+    fn test_write_tables() {
+        use parquet::file::reader::{FileReader, SerializedFileReader};
+
+        let dir = tempfile::tempdir().unwrap();
+        let statement = SqlStatement {
+            table_name: "orders".to_string(),
+            columns: vec!["id".to_string(), "total".to_string()],
+            values: vec![
+                vec![SqlValue::Number(1.0), SqlValue::Decimal("9.50".to_string())],
+                vec![SqlValue::Number(2.0), SqlValue::Null],
+            ],
+            warnings: vec![],
+            stats: Default::default(),
+        };
+        let files = write_tables(dir.path(), vec![statement], FileLimits::default()).unwrap();
+        assert_eq!(files, [(dir.path().join("orders.parquet"), 2)]);
+
+        let reader = SerializedFileReader::new(std::fs::File::open(&files[0].0).unwrap()).unwrap();
+        assert_eq!(reader.metadata().file_metadata().num_rows(), 2);
+        let schema = reader.metadata().file_metadata().schema_descr();
+        assert_eq!(
+            schema.column(0).physical_type(),
+            parquet::basic::Type::INT64
+        );
+    }
+}
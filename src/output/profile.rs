@@ -0,0 +1,260 @@
+// Per-column statistics of a workbook, for designing its target schema (xlsx2sql profile)
+use crate::errors::OutputError;
+use crate::output::inferred_type::{infer_type, InferredType};
+use crate::parser::data_model::{SqlStatement, SqlValue};
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::collections::HashSet;
+
+/// Longest min, max or example value shown in the text table
+const CELL_WIDTH: usize = 24;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TableProfile {
+    pub sheet: String,
+    pub table: String,
+    pub rows: usize,
+    pub columns: Vec<ColumnProfile>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ColumnProfile {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub column_type: String,
+    pub nulls: usize,
+    pub distinct: usize,
+    pub min: Option<String>,
+    pub max: Option<String>,
+    /// Characters in the longest value
+    pub max_length: usize,
+    /// The first distinct values, in row order
+    pub examples: Vec<String>,
+}
+
+/// Profiles of `statements`' columns, with up to `examples` example values each
+pub fn profile(statements: &[SqlStatement], examples: usize) -> Vec<TableProfile> {
+    statements
+        .iter()
+        .map(|statement| TableProfile {
+            sheet: statement.stats.sheet.clone(),
+            table: statement.table_name.clone(),
+            rows: statement.values.len(),
+            columns: statement
+                .columns
+                .iter()
+                .enumerate()
+                .map(|(idx, name)| {
+                    let values: Vec<&SqlValue> = statement
+                        .values
+                        .iter()
+                        .filter_map(|row| row.get(idx))
+                        .collect();
+                    profile_column(name, &values, examples)
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+fn profile_column(name: &str, values: &[&SqlValue], examples: usize) -> ColumnProfile {
+    let column_type = infer_type(values.iter().copied());
+    let present: Vec<&SqlValue> = values
+        .iter()
+        .copied()
+        .filter(|value| **value != SqlValue::Null)
+        .collect();
+    let mut seen = HashSet::new();
+    let mut distinct = Vec::new();
+    for value in &present {
+        let text = plain(value);
+        if seen.insert(text.clone()) {
+            distinct.push(text);
+        }
+    }
+    // Numbers compare by value, everything else by its text, which puts
+    // ISO dates in order too
+    let numeric = matches!(
+        column_type,
+        InferredType::Int64 | InferredType::Float64 | InferredType::Decimal { .. }
+    );
+    let compare = |a: &&&SqlValue, b: &&&SqlValue| match (numeric, number(a), number(b)) {
+        (true, Some(a), Some(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+        _ => plain(a).cmp(&plain(b)),
+    };
+    ColumnProfile {
+        name: name.to_string(),
+        column_type: column_type.name().to_string(),
+        nulls: values.len() - present.len(),
+        distinct: distinct.len(),
+        min: present.iter().min_by(compare).map(|value| plain(value)),
+        max: present.iter().max_by(compare).map(|value| plain(value)),
+        max_length: distinct
+            .iter()
+            .map(|text| text.chars().count())
+            .max()
+            .unwrap_or(0),
+        examples: distinct.into_iter().take(examples).collect(),
+    }
+}
+
+/// A value as a spreadsheet shows it: text without quotes
+fn plain(value: &SqlValue) -> String {
+    match value {
+        SqlValue::Text(s) | SqlValue::DateTime(s) | SqlValue::Json(s) | SqlValue::Uuid(s) => {
+            s.clone()
+        }
+        other => other.to_string(),
+    }
+}
+
+fn number(value: &SqlValue) -> Option<f64> {
+    match value {
+        SqlValue::Number(n) => Some(*n),
+        SqlValue::Integer(i) => Some(*i as f64),
+        SqlValue::Decimal(d) => d.parse().ok(),
+        _ => None,
+    }
+}
+
+pub fn to_json(tables: &[TableProfile]) -> Result<String, OutputError> {
+    serde_json::to_string_pretty(tables)
+        .map(|json| json + "\n")
+        .map_err(|e| OutputError::WriteError(e.to_string()))
+}
+
+/// One aligned table per sheet, long values cut short
+pub fn render(tables: &[TableProfile]) -> String {
+    let mut out = String::new();
+    for (idx, table) in tables.iter().enumerate() {
+        if idx > 0 {
+            out.push('\n');
+        }
+        out.push_str(&format!(
+            "== {} (sheet {}, {} row(s)) ==\n",
+            table.table, table.sheet, table.rows
+        ));
+        let mut lines: Vec<[String; 8]> = vec![[
+            "Column", "Type", "Nulls", "Distinct", "Min", "Max", "Length", "Examples",
+        ]
+        .map(String::from)];
+        for column in &table.columns {
+            let cell = |value: &Option<String>| value.as_deref().map(shorten).unwrap_or_default();
+            let examples: Vec<String> = column.examples.iter().map(|e| shorten(e)).collect();
+            lines.push([
+                column.name.clone(),
+                column.column_type.clone(),
+                column.nulls.to_string(),
+                column.distinct.to_string(),
+                cell(&column.min),
+                cell(&column.max),
+                column.max_length.to_string(),
+                examples.join(", "),
+            ]);
+        }
+        let widths: Vec<usize> = (0..8)
+            .map(|col| {
+                lines
+                    .iter()
+                    .map(|line| line[col].chars().count())
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+        for line in &lines {
+            let cells: Vec<String> = line
+                .iter()
+                .zip(&widths)
+                .enumerate()
+                .map(|(col, (cell, width))| {
+                    let pad = " ".repeat(width - cell.chars().count());
+                    // Counts line up on the right
+                    match [2, 3, 6].contains(&col) {
+                        true => format!("{pad}{cell}"),
+                        false => format!("{cell}{pad}"),
+                    }
+                })
+                .collect();
+            out.push_str(cells.join("  ").trim_end());
+            out.push('\n');
+        }
+    }
+    out
+}
+
+fn shorten(text: &str) -> String {
+    match text.chars().count() > CELL_WIDTH {
+        true => text.chars().take(CELL_WIDTH - 3).collect::<String>() + "...",
+        false => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::data_model::StatementStats;
+
+    fn statement() -> SqlStatement {
+        let row = |id: i64, name: Option<&str>, total: f64| {
+            vec![
+                SqlValue::Integer(id),
+                name.map_or(SqlValue::Null, |name| SqlValue::Text(name.to_string())),
+                SqlValue::Number(total),
+            ]
+        };
+        SqlStatement {
+            table_name: "orders".to_string(),
+            columns: vec!["id".to_string(), "name".to_string(), "total".to_string()],
+            values: vec![
+                row(1, Some("Alice"), 9.5),
+                row(2, None, 10.25),
+                row(3, Some("Bob"), 2.0),
+                row(4, Some("Alice"), 100.0),
+            ],
+            warnings: Vec::new(),
+            stats: StatementStats {
+                sheet: "Orders".to_string(),
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_profile() {
+        let tables = profile(&[statement()], 3);
+        let [id, name, total] = &tables[0].columns[..] else {
+            panic!("expected three columns");
+        };
+        assert_eq!((id.column_type.as_str(), id.distinct), ("integer", 4));
+        assert_eq!(id.examples, ["1", "2", "3"]);
+        assert_eq!((name.nulls, name.distinct, name.max_length), (1, 2, 5));
+        assert_eq!(
+            (name.min.as_deref(), name.max.as_deref()),
+            (Some("Alice"), Some("Bob"))
+        );
+        // 100 sorts after 9.5 as a number, not as text
+        assert_eq!(total.column_type, "float");
+        assert_eq!(
+            (total.min.as_deref(), total.max.as_deref()),
+            (Some("2"), Some("100"))
+        );
+    }
+
+    #[test]
+    fn test_render() {
+        let tables = profile(&[statement()], 2);
+        assert_eq!(
+            render(&tables),
+            concat!(
+                "== orders (sheet Orders, 4 row(s)) ==\n",
+                "Column  Type     Nulls  Distinct  Min    Max  Length  Examples\n",
+                "id      integer      0         4  1      4         1  1, 2\n",
+                "name    text         1         2  Alice  Bob       5  Alice, Bob\n",
+                "total   float        0         4  2      100       5  9.5, 10.25\n",
+            )
+        );
+        assert_eq!(shorten(&"x".repeat(30)), format!("{}...", "x".repeat(21)));
+        let json: serde_json::Value = serde_json::from_str(&to_json(&tables).unwrap()).unwrap();
+        assert_eq!(json[0]["columns"][1]["nulls"], 1);
+    }
+}
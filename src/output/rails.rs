@@ -0,0 +1,149 @@
+// Ruby for Rails' db/seeds.rb (--format rails-seed)
+use crate::output::format::{seeder_literal, SEEDER_CHUNK_ROWS};
+use crate::output::inflection::ModelNames;
+use crate::output::split::TableOutput;
+use crate::parser::data_model::{SqlStatement, SqlValue};
+use clap::ValueEnum;
+
+/// How the seed file creates rows
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum RailsMethod {
+    /// Model.insert_all([...]): one query per chunk, skipping validations and callbacks
+    #[default]
+    InsertAll,
+    /// Model.create!([...]): one record at a time, running validations and callbacks
+    Create,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RailsOptions {
+    pub method: RailsMethod,
+    pub models: ModelNames,
+}
+
+fn ruby_value(value: &SqlValue) -> String {
+    match value {
+        SqlValue::Null => "nil".to_string(),
+        SqlValue::Array(items) => {
+            let items: Vec<String> = items.iter().map(|item| ruby_string(item)).collect();
+            format!("[{}]", items.join(", "))
+        }
+        SqlValue::Binary(bytes) => {
+            let hex: String = bytes.iter().map(|byte| format!("{byte:02x}")).collect();
+            format!("[\"{hex}\"].pack(\"H*\")")
+        }
+        other => seeder_literal(other, ruby_string),
+    }
+}
+
+/// Double-quoted Ruby string, with interpolation escaped
+fn ruby_string(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for c in text.chars() {
+        match c {
+            '\\' => quoted.push_str("\\\\"),
+            '"' => quoted.push_str("\\\""),
+            '#' => quoted.push_str("\\#"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// `key:` for names that are valid symbols, else `"key":`
+fn ruby_key(column: &str) -> String {
+    let simple = column.starts_with(|c: char| c.is_ascii_lowercase() || c == '_')
+        && column
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_');
+    match simple {
+        true => format!("{column}:"),
+        false => format!("{}:", ruby_string(column)),
+    }
+}
+
+/// `{ column: value, ... }` of one row
+pub fn ruby_row(columns: &[String], row: &[SqlValue]) -> String {
+    let fields: Vec<String> = columns
+        .iter()
+        .zip(row)
+        .map(|(column, value)| format!("{} {}", ruby_key(column), ruby_value(value)))
+        .collect();
+    format!("{{ {} }}", fields.join(", "))
+}
+
+/// The calls creating the rows of `statement`
+pub fn model_inserts(statement: &SqlStatement, options: &RailsOptions) -> String {
+    let model = options.models.model_name(&statement.table_name);
+    let method = match options.method {
+        RailsMethod::InsertAll => "insert_all",
+        RailsMethod::Create => "create!",
+    };
+    let mut code = String::new();
+    for rows in statement.values.chunks(SEEDER_CHUNK_ROWS) {
+        code.push_str(&format!("{model}.{method}([\n"));
+        for row in rows {
+            code.push_str(&format!("  {},\n", ruby_row(&statement.columns, row)));
+        }
+        code.push_str("])\n\n");
+    }
+    code
+}
+
+pub fn seeds_file(workbook: &str, parts: &[TableOutput]) -> String {
+    let calls: String = parts.iter().map(|part| part.content.as_str()).collect();
+    format!("# Seed data from {workbook}, generated by xlsx2sql\n\n{calls}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_model_inserts() {
+        let statement = SqlStatement {
+            table_name: "products".to_string(),
+            columns: vec![
+                "id".to_string(),
+                "Unit Price".to_string(),
+                "note".to_string(),
+            ],
+            values: vec![
+                vec![
+                    SqlValue::Number(1.0),
+                    SqlValue::Decimal("9.50".to_string()),
+                    SqlValue::Text("say \"#{hi}\"".to_string()),
+                ],
+                vec![SqlValue::Integer(2), SqlValue::Null, SqlValue::Null],
+            ],
+            warnings: vec![],
+            stats: Default::default(),
+        };
+        let options = RailsOptions {
+            models: ModelNames {
+                singularize: true,
+                ..ModelNames::default()
+            },
+            ..RailsOptions::default()
+        };
+        assert_eq!(
+            model_inserts(&statement, &options),
+            concat!(
+                "Product.insert_all([\n",
+                "  { id: 1, \"Unit Price\": \"9.50\", note: \"say \\\"\\#{hi}\\\"\" },\n",
+                "  { id: 2, \"Unit Price\": nil, note: nil },\n",
+                "])\n\n"
+            )
+        );
+        let options = RailsOptions {
+            method: RailsMethod::Create,
+            ..options
+        };
+        assert!(model_inserts(&statement, &options).starts_with("Product.create!([\n"));
+    }
+}
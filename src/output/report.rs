@@ -0,0 +1,184 @@
+// Conversion outcome in JSON, for CI jobs to assert on (--report)
+use crate::errors::OutputError;
+use crate::output::inferred_type::infer_type;
+use crate::parser::data_model::SqlStatement;
+use serde::Serialize;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Report {
+    pub generator: String,
+    pub source: String,
+    /// Rows across all tables
+    pub rows: usize,
+    /// Warnings across all tables, plus `problems`
+    pub warnings: usize,
+    pub sheets: Vec<SheetReport>,
+    /// Rows and sheets skipped under --keep-going
+    pub problems: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SheetReport {
+    pub sheet: String,
+    pub table: String,
+    pub rows: usize,
+    pub skipped_rows: SkippedRows,
+    pub columns: Vec<ColumnReport>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SkippedRows {
+    /// Left out by --filter or a template's filter
+    pub filtered: usize,
+    /// Skipped for holding Excel error cells (--on-cell-error skip-row)
+    pub cell_errors: usize,
+    /// Skipped under --keep-going for a value that failed to convert
+    pub failed: usize,
+    /// Past --limit
+    pub limit: usize,
+    /// Unchanged since the last --incremental run
+    pub unchanged: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ColumnReport {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub column_type: String,
+    /// Whether the type was declared (--column-type or a template) or
+    /// inferred from the converted values
+    pub declared: bool,
+}
+
+impl Report {
+    pub fn new(source: String, statements: &[SqlStatement], problems: &[String]) -> Self {
+        let sheets: Vec<SheetReport> = statements
+            .iter()
+            .map(|statement| SheetReport {
+                sheet: statement.stats.sheet.clone(),
+                table: statement.table_name.clone(),
+                rows: statement.values.len(),
+                skipped_rows: SkippedRows {
+                    filtered: statement.stats.filtered_rows,
+                    cell_errors: statement.stats.error_rows,
+                    failed: statement.stats.failed_rows,
+                    limit: statement.stats.limited_rows,
+                    unchanged: statement.stats.unchanged_rows,
+                },
+                columns: statement
+                    .columns
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, name)| {
+                        let declared = statement.stats.declared_types.get(idx).cloned().flatten();
+                        ColumnReport {
+                            name: name.clone(),
+                            declared: declared.is_some(),
+                            column_type: declared.unwrap_or_else(|| {
+                                let values = statement.values.iter().filter_map(|row| row.get(idx));
+                                infer_type(values).name().to_string()
+                            }),
+                        }
+                    })
+                    .collect(),
+                warnings: statement.warnings.clone(),
+            })
+            .collect();
+        Self {
+            generator: format!("xlsx2sql {}", env!("CARGO_PKG_VERSION")),
+            source,
+            rows: sheets.iter().map(|sheet| sheet.rows).sum(),
+            warnings: sheets
+                .iter()
+                .map(|sheet| sheet.warnings.len())
+                .sum::<usize>()
+                + problems.len(),
+            sheets,
+            problems: problems.to_vec(),
+        }
+    }
+
+    /// Take the row counts of `statements`, the ones the report was built
+    /// from after --incremental left out their unchanged rows; column types
+    /// stay inferred from every row
+    pub fn update_rows(&mut self, statements: &[SqlStatement]) {
+        for (sheet, statement) in self.sheets.iter_mut().zip(statements) {
+            sheet.rows = statement.values.len();
+            sheet.skipped_rows.unchanged = statement.stats.unchanged_rows;
+        }
+        self.rows = self.sheets.iter().map(|sheet| sheet.rows).sum();
+    }
+
+    pub fn to_json(&self) -> Result<String, OutputError> {
+        serde_json::to_string_pretty(self)
+            .map(|json| json + "\n")
+            .map_err(|e| OutputError::WriteError(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::data_model::{SqlValue, StatementStats};
+
+    #[test]
+    fn test_report() {
+        let statement = SqlStatement {
+            table_name: "orders".to_string(),
+            columns: vec!["id".to_string(), "total".to_string()],
+            values: vec![
+                vec![SqlValue::Integer(1), SqlValue::Decimal("9.50".to_string())],
+                vec![SqlValue::Integer(2), SqlValue::Null],
+            ],
+            warnings: vec!["Orders: 1 error cell(s) emitted as NULL: B3 #N/A".to_string()],
+            stats: StatementStats {
+                sheet: "Orders".to_string(),
+                filtered_rows: 3,
+                failed_rows: 1,
+                declared_types: vec![None, Some("currency".to_string())],
+                ..Default::default()
+            },
+        };
+        let problems = ["Cell Orders!B5: invalid date; row skipped".to_string()];
+        let mut statements = [statement];
+        let mut report = Report::new("shop.xlsx".to_string(), &statements, &problems);
+        assert_eq!((report.rows, report.warnings), (2, 2));
+        let sheet = &report.sheets[0];
+        assert_eq!(
+            sheet.skipped_rows,
+            SkippedRows {
+                filtered: 3,
+                cell_errors: 0,
+                failed: 1,
+                limit: 0,
+                unchanged: 0,
+            }
+        );
+        assert_eq!(
+            sheet.columns,
+            vec![
+                ColumnReport {
+                    name: "id".to_string(),
+                    column_type: "integer".to_string(),
+                    declared: false
+                },
+                ColumnReport {
+                    name: "total".to_string(),
+                    column_type: "currency".to_string(),
+                    declared: true
+                },
+            ]
+        );
+        let json: serde_json::Value = serde_json::from_str(&report.to_json().unwrap()).unwrap();
+        assert_eq!(json["sheets"][0]["columns"][1]["type"], "currency");
+
+        // --incremental left out both rows as unchanged
+        statements[0].values.clear();
+        statements[0].stats.unchanged_rows = 2;
+        report.update_rows(&statements);
+        assert_eq!(report.rows, 0);
+        assert_eq!(report.sheets[0].skipped_rows.unchanged, 2);
+        assert_eq!(report.sheets[0].columns[0].column_type, "integer");
+    }
+}
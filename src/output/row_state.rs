@@ -0,0 +1,184 @@
+// Row hashes kept between runs so --incremental only emits new and changed rows
+use crate::errors::OutputError;
+use crate::output::writer::write_atomically;
+use crate::parser::data_model::{SqlStatement, SqlValue};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RowState {
+    /// Hash of every row of each table, keyed by the key column's value or,
+    /// without a key column, by the hash itself
+    pub tables: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+/// What `RowState::retain_changed` found in one table
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RowChanges {
+    pub added: usize,
+    pub changed: usize,
+    pub unchanged: usize,
+}
+
+impl RowState {
+    /// The state saved at `path`, or an empty one before the first run
+    pub fn load(path: &Path) -> Result<Self, OutputError> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(path)?;
+        serde_json::from_str(&content).map_err(|e| {
+            OutputError::WriteError(format!("Invalid state file {}: {}", path.display(), e))
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), OutputError> {
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| OutputError::WriteError(e.to_string()))?;
+        write_atomically(path, (content + "\n").as_bytes())?;
+        Ok(())
+    }
+
+    /// Drop the rows of `statement` that are identical to the previous run and
+    /// record its current rows; rows identified by `key_column` whose values
+    /// differ count as changed
+    pub fn retain_changed(
+        &mut self,
+        statement: &mut SqlStatement,
+        key_column: Option<&str>,
+    ) -> Result<RowChanges, OutputError> {
+        let key_idx = key_column
+            .map(|key| {
+                statement
+                    .columns
+                    .iter()
+                    .position(|c| c.eq_ignore_ascii_case(key.trim()))
+                    .ok_or_else(|| OutputError::KeyColumn {
+                        table: statement.table_name.clone(),
+                        column: key.to_string(),
+                    })
+            })
+            .transpose()?;
+
+        let previous = self
+            .tables
+            .remove(&statement.table_name)
+            .unwrap_or_default();
+        let mut current = BTreeMap::new();
+        let mut changes = RowChanges::default();
+        statement.values.retain(|row| {
+            let hash = row_hash(row);
+            let key = match key_idx {
+                Some(idx) => key_text(&row[idx]),
+                None => hash.clone(),
+            };
+            let keep = match previous.get(&key) {
+                None => {
+                    changes.added += 1;
+                    true
+                }
+                Some(old) if *old != hash => {
+                    changes.changed += 1;
+                    true
+                }
+                Some(_) => {
+                    changes.unchanged += 1;
+                    false
+                }
+            };
+            current.insert(key, hash);
+            keep
+        });
+        self.tables.insert(statement.table_name.clone(), current);
+        statement.stats.unchanged_rows += changes.unchanged;
+        Ok(changes)
+    }
+}
+
+fn row_hash(row: &[SqlValue]) -> String {
+    // Debug output distinguishes types and quotes text, so distinct rows
+    // never render the same
+    format!("{:x}", Sha256::digest(format!("{row:?}").as_bytes()))
+}
+
+pub fn key_text(value: &SqlValue) -> String {
+    match value {
+        SqlValue::Text(text) => text.trim().to_string(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::data_model::StatementStats;
+
+    fn statement(rows: &[(i64, &str)]) -> SqlStatement {
+        SqlStatement {
+            table_name: "orders".to_string(),
+            columns: vec!["id".to_string(), "status".to_string()],
+            values: rows
+                .iter()
+                .map(|(id, status)| {
+                    vec![SqlValue::Integer(*id), SqlValue::Text(status.to_string())]
+                })
+                .collect(),
+            warnings: Vec::new(),
+            stats: StatementStats::default(),
+        }
+    }
+
+    #[test]
+    fn test_retain_changed_by_key() {
+        let mut state = RowState::default();
+        let mut first = statement(&[(1, "open"), (2, "open")]);
+        state.retain_changed(&mut first, Some("id")).unwrap();
+        assert_eq!(first.values.len(), 2);
+
+        let mut second = statement(&[(1, "open"), (2, "shipped"), (3, "open")]);
+        let changes = state.retain_changed(&mut second, Some("id")).unwrap();
+        assert_eq!(
+            changes,
+            RowChanges {
+                added: 1,
+                changed: 1,
+                unchanged: 1
+            }
+        );
+        assert_eq!(
+            second.values,
+            statement(&[(2, "shipped"), (3, "open")]).values
+        );
+    }
+
+    #[test]
+    fn test_retain_changed_by_hash() {
+        let mut state = RowState::default();
+        state
+            .retain_changed(&mut statement(&[(1, "open")]), None)
+            .unwrap();
+        let mut second = statement(&[(1, "open"), (1, "shipped")]);
+        let changes = state.retain_changed(&mut second, None).unwrap();
+        assert_eq!((changes.added, changes.unchanged), (1, 1));
+        assert_eq!(second.values, statement(&[(1, "shipped")]).values);
+        assert!(matches!(
+            state.retain_changed(&mut statement(&[]), Some("sku")),
+            Err(OutputError::KeyColumn { .. })
+        ));
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".xlsx2sql-state.json");
+        assert_eq!(RowState::load(&path).unwrap(), RowState::default());
+        let mut state = RowState::default();
+        state
+            .retain_changed(&mut statement(&[(1, "open")]), Some("id"))
+            .unwrap();
+        state.save(&path).unwrap();
+        assert_eq!(RowState::load(&path).unwrap(), state);
+    }
+}
@@ -0,0 +1,172 @@
+// Record of a run's effective options and inputs, compared across runs
+use crate::errors::OutputError;
+use crate::output::writer::write_atomically;
+use crate::parser::data_model::SqlStatement;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunRecord {
+    pub input: PathBuf,
+    pub input_sha256: String,
+    /// Effective option values keyed by option name, defaults included
+    pub options: BTreeMap<String, Vec<String>>,
+    /// Columns of each generated table
+    pub tables: BTreeMap<String, Vec<String>>,
+    /// Differences from the previous record, empty on the first run
+    #[serde(default)]
+    pub changes: Vec<String>,
+}
+
+impl RunRecord {
+    pub fn new(
+        input: &Path,
+        input_sha256: String,
+        options: BTreeMap<String, Vec<String>>,
+        statements: &[SqlStatement],
+    ) -> Self {
+        let tables = statements
+            .iter()
+            .map(|s| (s.table_name.clone(), s.columns.clone()))
+            .collect();
+        Self {
+            input: input.to_path_buf(),
+            input_sha256,
+            options,
+            tables,
+            changes: Vec::new(),
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Option<Self>, OutputError> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = std::fs::read_to_string(path)?;
+        serde_json::from_str(&content).map(Some).map_err(|e| {
+            OutputError::WriteError(format!("Invalid run record {}: {}", path.display(), e))
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), OutputError> {
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| OutputError::WriteError(e.to_string()))?;
+        write_atomically(path, (content + "\n").as_bytes())?;
+        Ok(())
+    }
+
+    /// Describe what differs in this run compared to `previous`
+    pub fn diff(&self, previous: &RunRecord) -> Vec<String> {
+        let mut changes = Vec::new();
+
+        if self.input != previous.input {
+            changes.push(format!(
+                "input file changed: {} -> {}",
+                previous.input.display(),
+                self.input.display()
+            ));
+        }
+        if self.input_sha256 != previous.input_sha256 {
+            changes.push("input file contents changed".to_string());
+        }
+
+        for (name, value) in &self.options {
+            match previous.options.get(name) {
+                None => changes.push(format!("option {name} added: {}", value.join(","))),
+                Some(old) if old != value => changes.push(format!(
+                    "option {name} changed: {} -> {}",
+                    old.join(","),
+                    value.join(",")
+                )),
+                _ => {}
+            }
+        }
+        for name in previous.options.keys() {
+            if !self.options.contains_key(name) {
+                changes.push(format!("option {name} removed"));
+            }
+        }
+
+        for (table, columns) in &self.tables {
+            let Some(old_columns) = previous.tables.get(table) else {
+                changes.push(format!("new table {table}"));
+                continue;
+            };
+            for column in columns.iter().filter(|c| !old_columns.contains(c)) {
+                changes.push(format!("new column {table}.{column}"));
+            }
+            for column in old_columns.iter().filter(|c| !columns.contains(c)) {
+                changes.push(format!("removed column {table}.{column}"));
+            }
+        }
+        for table in previous.tables.keys() {
+            if !self.tables.contains_key(table) {
+                changes.push(format!("removed table {table}"));
+            }
+        }
+
+        changes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record() -> RunRecord {
+        RunRecord {
+            input: PathBuf::from("sales.xlsx"),
+            input_sha256: "abc".to_string(),
+            options: [("number_locale".to_string(), vec!["de".to_string()])].into(),
+            tables: [(
+                "orders".to_string(),
+                vec!["id".to_string(), "total".to_string()],
+            )]
+            .into(),
+            changes: vec![],
+        }
+    }
+
+    #[test]
+    fn test_identical_runs_have_no_changes() {
+        assert!(record().diff(&record()).is_empty());
+    }
+
+    #[test]
+    fn test_diff_reports_changes() {
+        let previous = record();
+        let mut current = record();
+        current.input_sha256 = "def".to_string();
+        current
+            .options
+            .insert("number_locale".to_string(), vec!["fr".to_string()]);
+        current
+            .tables
+            .get_mut("orders")
+            .unwrap()
+            .push("discount".to_string());
+        current
+            .tables
+            .insert("customers".to_string(), vec!["id".to_string()]);
+
+        assert_eq!(
+            current.diff(&previous),
+            [
+                "input file contents changed",
+                "option number_locale changed: de -> fr",
+                "new table customers",
+                "new column orders.discount",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("last-run.json");
+        assert_eq!(RunRecord::load(&path).unwrap(), None);
+        record().save(&path).unwrap();
+        assert_eq!(RunRecord::load(&path).unwrap(), Some(record()));
+    }
+}
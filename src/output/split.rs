@@ -0,0 +1,256 @@
+// Spreading formatted statements over several output files
+use crate::parser::data_model::{SqlStatement, SqlValue};
+use std::path::{Path, PathBuf};
+
+/// The formatted SQL of one statement and the table it inserts into
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableOutput {
+    pub table: String,
+    pub content: String,
+    /// Rows the statement inserts
+    pub rows: usize,
+}
+
+/// Upper bounds for each output file (--max-file-size, --max-rows-per-file)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FileLimits {
+    pub max_bytes: Option<usize>,
+    pub max_rows: Option<usize>,
+}
+
+impl FileLimits {
+    pub fn is_unlimited(&self) -> bool {
+        self.max_bytes.is_none() && self.max_rows.is_none()
+    }
+
+    fn exceeded(&self, bytes: usize, rows: usize) -> bool {
+        self.max_bytes.is_some_and(|max| bytes > max) || self.max_rows.is_some_and(|max| rows > max)
+    }
+}
+
+/// Break `statement` into statements that each fit in one file; `header_bytes`
+/// is the formatted size of the statement without rows and `row_bytes` the
+/// size one row adds. A row larger than the limit gets a statement of its own.
+pub fn split_statement(
+    statement: SqlStatement,
+    limits: FileLimits,
+    header_bytes: usize,
+    row_bytes: impl Fn(&[SqlValue]) -> usize,
+) -> Vec<SqlStatement> {
+    if limits.is_unlimited() || statement.values.is_empty() {
+        return vec![statement];
+    }
+    let SqlStatement {
+        table_name,
+        columns,
+        values,
+        mut warnings,
+        stats,
+    } = statement;
+
+    let mut chunks: Vec<Vec<Vec<SqlValue>>> = vec![Vec::new()];
+    let mut bytes = header_bytes;
+    for row in values {
+        let size = row_bytes(&row);
+        let current = chunks.last_mut().expect("chunks starts non-empty");
+        if !current.is_empty() && limits.exceeded(bytes + size, current.len() + 1) {
+            chunks.push(Vec::new());
+            bytes = header_bytes;
+        }
+        bytes += size;
+        chunks.last_mut().expect("chunks is non-empty").push(row);
+    }
+    chunks
+        .into_iter()
+        .map(|values| SqlStatement {
+            table_name: table_name.clone(),
+            columns: columns.clone(),
+            values,
+            // Reported once, with the first part
+            warnings: std::mem::take(&mut warnings),
+            stats: stats.clone(),
+        })
+        .collect()
+}
+
+/// Group consecutive statements into files that stay within `limits`
+pub fn pack(parts: Vec<TableOutput>, limits: FileLimits) -> Vec<Vec<TableOutput>> {
+    let mut files: Vec<Vec<TableOutput>> = Vec::new();
+    let (mut bytes, mut rows) = (0, 0);
+    for part in parts {
+        match files.last_mut() {
+            Some(file) if !limits.exceeded(bytes + part.content.len(), rows + part.rows) => {
+                bytes += part.content.len();
+                rows += part.rows;
+                file.push(part);
+            }
+            _ => {
+                (bytes, rows) = (part.content.len(), part.rows);
+                files.push(vec![part]);
+            }
+        }
+    }
+    files
+}
+
+/// `out.sql` numbered as `out.001.sql` for the first of several files
+pub fn numbered_path(path: &Path, index: usize) -> PathBuf {
+    let number = format!("{:03}", index + 1);
+    match path.extension() {
+        Some(ext) => path.with_extension(format!("{number}.{}", ext.to_string_lossy())),
+        None => path.with_extension(number),
+    }
+}
+
+/// Everything in one script, in statement order
+pub fn join(parts: &[TableOutput]) -> String {
+    parts.iter().map(|part| part.content.as_str()).collect()
+}
+
+/// One `<table>.<extension>` file per table under `dir`, in order of first
+/// appearance; statements into the same table share a file
+pub fn split_by_table(
+    parts: Vec<TableOutput>,
+    dir: &Path,
+    extension: &str,
+) -> Vec<(PathBuf, Vec<TableOutput>)> {
+    let mut files: Vec<(PathBuf, Vec<TableOutput>)> = Vec::new();
+    for part in parts {
+        let path = table_path(dir, &part.table, extension);
+        match files.iter_mut().find(|(existing, _)| *existing == path) {
+            Some((_, file)) => file.push(part),
+            None => files.push((path, vec![part])),
+        }
+    }
+    files
+}
+
+/// The directory per-table files are written into for `output`: `out.sql`
+/// (or `out.<extension>`) becomes `out/`, other paths are used as they are
+pub fn split_dir(output: &Path, extension: &str) -> PathBuf {
+    match output.extension() {
+        Some(ext) if ext.eq_ignore_ascii_case("sql") || ext.eq_ignore_ascii_case(extension) => {
+            output.with_extension("")
+        }
+        _ => output.to_path_buf(),
+    }
+}
+
+/// `<dir>/<table>.<extension>`, safe as a file name
+pub fn table_path(dir: &Path, table: &str, extension: &str) -> PathBuf {
+    dir.join(format!("{}.{extension}", table_file_name(table)))
+}
+
+/// `table` with characters that are not allowed in file names replaced
+fn table_file_name(table: &str) -> String {
+    let name: String = table
+        .trim()
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    match name.trim_matches('.') {
+        "" => "table".to_string(),
+        _ => name,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn part(table: &str, content: &str) -> TableOutput {
+        TableOutput {
+            table: table.to_string(),
+            content: content.to_string(),
+            rows: 1,
+        }
+    }
+
+    #[test]
+    fn test_split_statement() {
+        let statement = SqlStatement {
+            table_name: "orders".to_string(),
+            columns: vec!["id".to_string()],
+            values: (1..=5).map(|id| vec![SqlValue::Integer(id)]).collect(),
+            warnings: vec!["orders: a warning".to_string()],
+            stats: Default::default(),
+        };
+        let limits = FileLimits {
+            max_bytes: Some(30),
+            max_rows: Some(3),
+        };
+        let parts = split_statement(statement, limits, 10, |_| 8);
+        let rows: Vec<usize> = parts.iter().map(|s| s.values.len()).collect();
+        // 10 + 8 + 8 fits 30 bytes, a third row would not
+        assert_eq!(rows, [2, 2, 1]);
+        assert_eq!(parts[2].values, [[SqlValue::Integer(5)]]);
+        assert_eq!(parts[0].warnings.len(), 1);
+        assert!(parts[1].warnings.is_empty());
+    }
+
+    #[test]
+    fn test_pack() {
+        let parts = vec![part("a", "1234"), part("b", "1234"), part("c", "12")];
+        let limits = FileLimits {
+            max_bytes: Some(6),
+            max_rows: None,
+        };
+        let files = pack(parts, limits);
+        let tables: Vec<Vec<&str>> = files
+            .iter()
+            .map(|file| file.iter().map(|part| part.table.as_str()).collect())
+            .collect();
+        assert_eq!(tables, [vec!["a"], vec!["b", "c"]]);
+        assert_eq!(
+            numbered_path(Path::new("out/data.sql"), 0),
+            PathBuf::from("out/data.001.sql")
+        );
+        assert_eq!(
+            numbered_path(Path::new("data"), 11),
+            PathBuf::from("data.012")
+        );
+    }
+
+    #[test]
+    fn test_split_by_table() {
+        let parts = vec![
+            part("orders", "A;\n"),
+            part("customers", "B;\n"),
+            part("orders", "C;\n"),
+        ];
+        assert_eq!(join(&parts), "A;\nB;\nC;\n");
+        let files: Vec<(PathBuf, String)> = split_by_table(parts, Path::new("out"), "sql")
+            .into_iter()
+            .map(|(path, parts)| (path, join(&parts)))
+            .collect();
+        assert_eq!(
+            files,
+            [
+                (PathBuf::from("out/orders.sql"), "A;\nC;\n".to_string()),
+                (PathBuf::from("out/customers.sql"), "B;\n".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_file_names() {
+        assert_eq!(table_file_name("sales 2024/Q1"), "sales 2024_Q1");
+        assert_eq!(table_file_name(".."), "table");
+        assert_eq!(
+            split_dir(Path::new("data/out.sql"), "csv"),
+            PathBuf::from("data/out")
+        );
+        assert_eq!(
+            split_dir(Path::new("data/out.csv"), "csv"),
+            PathBuf::from("data/out")
+        );
+        assert_eq!(
+            split_dir(Path::new("data/sql"), "sql"),
+            PathBuf::from("data/sql")
+        );
+    }
+}
@@ -0,0 +1,189 @@
+// Writing converted sheets straight into a SQLite database file (--output-sqlite)
+use crate::errors::DatabaseError;
+use crate::generator::dialect::Dialect;
+use crate::output::database::TableCount;
+use crate::output::format::whole_number;
+use crate::parser::data_model::{SqlStatement, SqlValue};
+use std::path::Path;
+
+/// SQLite column type for the values of one column: the narrowest of
+/// INTEGER, REAL, NUMERIC and BLOB that holds every non-NULL value, else TEXT
+fn column_type<'a>(values: impl Iterator<Item = &'a SqlValue>) -> &'static str {
+    let mut column_type = None;
+    for value in values {
+        let value_type = match value {
+            SqlValue::Null => continue,
+            SqlValue::Integer(_) | SqlValue::Boolean(_) => "INTEGER",
+            SqlValue::Number(n) if whole_number(*n).is_some() => "INTEGER",
+            SqlValue::Number(_) => "REAL",
+            SqlValue::Decimal(_) => "NUMERIC",
+            SqlValue::Binary(_) => "BLOB",
+            _ => return "TEXT",
+        };
+        column_type = match (column_type, value_type) {
+            (None, t) => Some(t),
+            (Some(a), b) if a == b => Some(a),
+            (Some("INTEGER" | "REAL" | "NUMERIC"), "INTEGER" | "REAL" | "NUMERIC") => {
+                Some("NUMERIC")
+            }
+            _ => return "TEXT",
+        };
+    }
+    column_type.unwrap_or("TEXT")
+}
+
+/// `CREATE TABLE IF NOT EXISTS` with column types inferred from the rows
+fn create_table(statement: &SqlStatement) -> String {
+    let columns: Vec<String> = statement
+        .columns
+        .iter()
+        .enumerate()
+        .map(|(i, column)| {
+            let values = statement.values.iter().filter_map(|row| row.get(i));
+            format!(
+                "{} {}",
+                Dialect::Postgres.quote_identifier(column),
+                column_type(values)
+            )
+        })
+        .collect();
+    format!(
+        "CREATE TABLE IF NOT EXISTS {} ({})",
+        Dialect::Postgres.quote_identifier(&statement.table_name),
+        columns.join(", ")
+    )
+}
+
+/// Create the tables of `statements` in the database at `path` (created if
+/// missing) and insert their rows, all in one transaction
+pub fn write_database(
+    path: &Path,
+    statements: &[SqlStatement],
+    progress: &mut dyn FnMut(&TableCount),
+) -> Result<Vec<TableCount>, DatabaseError> {
+    client::run(path, statements, progress)
+}
+
+mod client {
+    use super::create_table;
+    use crate::errors::DatabaseError;
+    use crate::generator::dialect::Dialect;
+    use crate::generator::duration::format_duration;
+    use crate::output::database::{add_count, TableCount};
+    use crate::parser::data_model::{SqlStatement, SqlValue};
+    use rusqlite::types::Value;
+    use std::path::Path;
+
+    pub fn run(
+        path: &Path,
+        statements: &[SqlStatement],
+        progress: &mut dyn FnMut(&TableCount),
+    ) -> Result<Vec<TableCount>, DatabaseError> {
+        let open = |e: rusqlite::Error| DatabaseError::Connect(format!("{}: {e}", path.display()));
+        let mut connection = rusqlite::Connection::open(path).map_err(open)?;
+        let tx = connection.transaction().map_err(open)?;
+        let mut counts: Vec<TableCount> = Vec::new();
+        for statement in statements {
+            let failed = |e: rusqlite::Error| DatabaseError::Statement {
+                table: statement.table_name.clone(),
+                reason: e.to_string(),
+            };
+            tx.execute(&create_table(statement), []).map_err(failed)?;
+            let columns: Vec<String> = statement
+                .columns
+                .iter()
+                .map(|c| Dialect::Postgres.quote_identifier(c))
+                .collect();
+            let placeholders = vec!["?"; columns.len()].join(", ");
+            let mut insert = tx
+                .prepare(&format!(
+                    "INSERT INTO {} ({}) VALUES ({placeholders})",
+                    Dialect::Postgres.quote_identifier(&statement.table_name),
+                    columns.join(", ")
+                ))
+                .map_err(failed)?;
+            for row in &statement.values {
+                insert
+                    .execute(rusqlite::params_from_iter(row.iter().map(sqlite_value)))
+                    .map_err(failed)?;
+            }
+
+            let rows = statement.values.len() as u64;
+            progress(add_count(&mut counts, &statement.table_name, rows));
+        }
+        tx.commit().map_err(open)?;
+        Ok(counts)
+    }
+
+    fn sqlite_value(value: &SqlValue) -> Value {
+        match value {
+            SqlValue::Null => Value::Null,
+            SqlValue::Integer(i) => Value::Integer(*i),
+            SqlValue::Boolean(b) => Value::Integer(i64::from(*b)),
+            SqlValue::Number(n) => Value::Real(*n),
+            SqlValue::Binary(bytes) => Value::Blob(bytes.clone()),
+            SqlValue::Text(s)
+            | SqlValue::DateTime(s)
+            | SqlValue::Json(s)
+            | SqlValue::Uuid(s)
+            | SqlValue::Decimal(s) => Value::Text(s.clone()),
+            SqlValue::Array(items) => Value::Text(serde_json::to_string(items).unwrap_or_default()),
+            SqlValue::Duration(d) => Value::Text(format_duration(d)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_column_type() {
+        let ints = [SqlValue::Integer(1), SqlValue::Null, SqlValue::Number(2.0)];
+        assert_eq!(column_type(ints.iter()), "INTEGER");
+        let mixed = [SqlValue::Integer(1), SqlValue::Number(1.5)];
+        assert_eq!(column_type(mixed.iter()), "NUMERIC");
+        let text = [SqlValue::Integer(1), SqlValue::Text("n/a".to_string())];
+        assert_eq!(column_type(text.iter()), "TEXT");
+        assert_eq!(column_type([SqlValue::Null].iter()), "TEXT");
+    }
+
+    #[test]
+    fn test_create_table() {
+        let statement = SqlStatement {
+            table_name: "orders".to_string(),
+            columns: vec!["id".to_string(), "total".to_string()],
+            values: vec![vec![SqlValue::Integer(1), SqlValue::Number(9.5)]],
+            warnings: vec![],
+            stats: Default::default(),
+        };
+        assert_eq!(
+            create_table(&statement),
+            r#"CREATE TABLE IF NOT EXISTS "orders" ("id" INTEGER, "total" REAL)"#
+        );
+    }
+
+    #[test]
+    fn test_write_database() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("out.db");
+        let statement = SqlStatement {
+            table_name: "people".to_string(),
+            columns: vec!["id".to_string(), "name".to_string()],
+            values: vec![
+                vec![SqlValue::Integer(1), SqlValue::Text("O'Brien".to_string())],
+                vec![SqlValue::Integer(2), SqlValue::Null],
+            ],
+            warnings: vec![],
+            stats: Default::default(),
+        };
+        let counts = write_database(&path, &[statement], &mut |_| {}).unwrap();
+        assert_eq!(counts[0].rows, 2);
+
+        let connection = rusqlite::Connection::open(&path).unwrap();
+        let name: String = connection
+            .query_row("SELECT name FROM people WHERE id = 1", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(name, "O'Brien");
+    }
+}
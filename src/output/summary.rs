@@ -0,0 +1,178 @@
+// Table of what a run wrote, printed when it ends
+use std::path::PathBuf;
+
+/// One table's line of the summary
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TableSummary {
+    pub table: String,
+    pub columns: usize,
+    pub rows: usize,
+    pub warnings: usize,
+    /// Files holding the table's rows, with their sizes in bytes
+    pub files: Vec<(PathBuf, usize)>,
+}
+
+/// Summaries of `statements`' tables, merging statements into the same table
+pub fn summarize<'a>(
+    statements: impl IntoIterator<Item = (&'a str, usize, usize, usize)>,
+) -> Vec<TableSummary> {
+    let mut tables: Vec<TableSummary> = Vec::new();
+    for (table, columns, rows, warnings) in statements {
+        match tables.iter_mut().find(|summary| summary.table == table) {
+            Some(summary) => {
+                summary.rows += rows;
+                summary.warnings += warnings;
+            }
+            None => tables.push(TableSummary {
+                table: table.to_string(),
+                columns,
+                rows,
+                warnings,
+                files: Vec::new(),
+            }),
+        }
+    }
+    tables
+}
+
+/// Aligned table with a totals line; a file shared with the line above is
+/// not repeated
+pub fn render(tables: &[TableSummary]) -> String {
+    let file_cell = |summary: &TableSummary| match summary.files.as_slice() {
+        [] => (String::new(), String::new()),
+        [(path, bytes)] => (display_path(path), format_size(*bytes)),
+        [(first, _), rest @ ..] => (
+            format!("{} (+{} more)", display_path(first), rest.len()),
+            format_size(summary.files.iter().map(|(_, bytes)| bytes).sum()),
+        ),
+    };
+    let mut lines: Vec<[String; 6]> =
+        vec![["Table", "Columns", "Rows", "Warnings", "File", "Size"].map(String::from)];
+    let mut previous = None;
+    for summary in tables {
+        let (file, size) = match Some(&summary.files) == previous {
+            true => (String::new(), String::new()),
+            false => file_cell(summary),
+        };
+        previous = Some(&summary.files);
+        lines.push([
+            summary.table.clone(),
+            summary.columns.to_string(),
+            summary.rows.to_string(),
+            summary.warnings.to_string(),
+            file,
+            size,
+        ]);
+    }
+    let mut files: Vec<&(PathBuf, usize)> = Vec::new();
+    for file in tables.iter().flat_map(|summary| &summary.files) {
+        if !files.contains(&file) {
+            files.push(file);
+        }
+    }
+    lines.push([
+        format!("{} table(s)", tables.len()),
+        String::new(),
+        tables.iter().map(|s| s.rows).sum::<usize>().to_string(),
+        tables.iter().map(|s| s.warnings).sum::<usize>().to_string(),
+        format!("{} file(s)", files.len()),
+        format_size(files.iter().map(|(_, bytes)| bytes).sum()),
+    ]);
+
+    let widths: Vec<usize> = (0..6)
+        .map(|col| {
+            lines
+                .iter()
+                .map(|line| line[col].chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    let rule = widths
+        .iter()
+        .map(|width| "-".repeat(*width))
+        .collect::<Vec<_>>()
+        .join("  ");
+    let last = lines.len() - 1;
+    let mut out = String::new();
+    for (idx, line) in lines.iter().enumerate() {
+        if idx == 1 || idx == last {
+            out.push_str(&rule);
+            out.push('\n');
+        }
+        let cells: Vec<String> = line
+            .iter()
+            .zip(&widths)
+            .enumerate()
+            .map(|(col, (cell, width))| {
+                let pad = " ".repeat(width - cell.chars().count());
+                // Counts line up on the right
+                match (1..=3).contains(&col) || col == 5 {
+                    true => format!("{pad}{cell}"),
+                    false => format!("{cell}{pad}"),
+                }
+            })
+            .collect();
+        out.push_str(cells.join("  ").trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+fn display_path(path: &std::path::Path) -> String {
+    match path.to_str() {
+        Some("-") => "(stdout)".to_string(),
+        _ => path.display().to_string(),
+    }
+}
+
+/// `512 B`, `1.5 KB`, `12.0 MB`
+fn format_size(bytes: usize) -> String {
+    let mut size = bytes as f64;
+    for unit in ["B", "KB", "MB"] {
+        if size < 1024.0 {
+            return match unit {
+                "B" => format!("{bytes} B"),
+                _ => format!("{size:.1} {unit}"),
+            };
+        }
+        size /= 1024.0;
+    }
+    format!("{size:.1} GB")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let mut tables = summarize([
+            ("orders", 4, 120, 1),
+            ("customers", 3, 8, 0),
+            ("orders", 4, 30, 0),
+        ]);
+        assert_eq!(tables[0].rows, 150);
+        let file = (PathBuf::from("shop.sql"), 2048);
+        tables[0].files = vec![file.clone()];
+        tables[1].files = vec![file];
+        assert_eq!(
+            render(&tables),
+            concat!(
+                "Table       Columns  Rows  Warnings  File         Size\n",
+                "----------  -------  ----  --------  ---------  ------\n",
+                "orders            4   150         1  shop.sql   2.0 KB\n",
+                "customers         3     8         0\n",
+                "----------  -------  ----  --------  ---------  ------\n",
+                "2 table(s)            158         1  1 file(s)  2.0 KB\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(1536), "1.5 KB");
+        assert_eq!(format_size(3 << 20), "3.0 MB");
+    }
+}
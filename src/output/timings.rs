@@ -0,0 +1,111 @@
+// Per-stage timing breakdown of a conversion run
+use crate::parser::data_model::{SqlStatement, WorkbookData};
+use std::time::Duration;
+
+#[derive(Debug, Clone, Default)]
+pub struct Timings {
+    pub read: Duration,
+    pub parse: Duration,
+    pub convert: Duration,
+    pub format: Duration,
+    pub write: Duration,
+    /// `(sheet, parse, convert)` for every sheet that produced a statement
+    pub sheets: Vec<(String, Duration, Duration)>,
+}
+
+impl Timings {
+    /// Collect read, parse and conversion times recorded by the parser and generator
+    pub fn from_run(workbook: &WorkbookData, statements: &[SqlStatement]) -> Self {
+        let sheets: Vec<(String, Duration, Duration)> = statements
+            .iter()
+            .map(|statement| {
+                let parse = workbook
+                    .sheets
+                    .iter()
+                    .find(|s| s.name == statement.stats.sheet)
+                    .map(|s| s.parse_time)
+                    .unwrap_or_default();
+                (
+                    statement.stats.sheet.clone(),
+                    parse,
+                    statement.stats.convert_time,
+                )
+            })
+            .collect();
+        Self {
+            read: workbook.read_time,
+            parse: workbook.sheets.iter().map(|s| s.parse_time).sum(),
+            convert: sheets.iter().map(|(_, _, convert)| *convert).sum(),
+            sheets,
+            ..Default::default()
+        }
+    }
+
+    /// Add the times of another input, for an output combining several
+    pub fn add(&mut self, other: Timings) {
+        self.read += other.read;
+        self.parse += other.parse;
+        self.convert += other.convert;
+        self.format += other.format;
+        self.write += other.write;
+        self.sheets.extend(other.sheets);
+    }
+
+    pub fn total(&self) -> Duration {
+        self.read + self.parse + self.convert + self.format + self.write
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = String::from("Timings:\n");
+        for (stage, elapsed) in [
+            ("read", self.read),
+            ("parse", self.parse),
+            ("convert", self.convert),
+            ("format", self.format),
+            ("write", self.write),
+            ("total", self.total()),
+        ] {
+            out.push_str(&format!("  {stage:<10}{}\n", millis(elapsed)));
+        }
+        if !self.sheets.is_empty() {
+            out.push_str("Sheets:\n");
+            for (sheet, parse, convert) in &self.sheets {
+                out.push_str(&format!(
+                    "  {sheet}: parse {}, convert {}\n",
+                    millis(*parse),
+                    millis(*convert)
+                ));
+            }
+        }
+        out
+    }
+}
+
+fn millis(elapsed: Duration) -> String {
+    format!("{:.1} ms", elapsed.as_secs_f64() * 1000.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_timings() {
+        let timings = Timings {
+            read: Duration::from_millis(12),
+            parse: Duration::from_millis(3),
+            convert: Duration::from_micros(1500),
+            format: Duration::ZERO,
+            write: Duration::from_millis(1),
+            sheets: vec![(
+                "Sheet1".to_string(),
+                Duration::from_millis(3),
+                Duration::from_micros(1500),
+            )],
+        };
+        let rendered = timings.render();
+        assert!(rendered.contains("  read      12.0 ms\n"));
+        assert!(rendered.contains("  total     17.5 ms\n"));
+        assert!(rendered.contains("  Sheet1: parse 3.0 ms, convert 1.5 ms\n"));
+    }
+}
@@ -1,5 +1,90 @@
 use crate::errors::OutputError;
-use std::path::PathBuf;
+use crate::output::s3::S3Location;
+use clap::ValueEnum;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum LineEnding {
+    /// \n
+    #[default]
+    Lf,
+    /// \r\n, as Windows tools expect
+    Crlf,
+}
+
+/// How a format writes text values, which decides where its line breaks
+/// are part of a value rather than of the layout
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Quoting {
+    /// Values never hold a raw line break (JSON, XML, YAML, Ruby)
+    Escaped,
+    /// SQL: '...' literals and "..." or `...` identifiers with doubled quotes,
+    /// and -- comments
+    Sql,
+    /// CSV and TSV: "..." fields with doubled quotes
+    Csv,
+    /// PHP: '...' strings with backslash escapes, and /* */ comments
+    Php,
+}
+
+/// `content` ending in exactly one line break, with every `\n` of the layout
+/// that is not already part of a `\r\n` written as `line_ending`. Line breaks
+/// inside the quoted values of `quoting` are left as the cells hold them.
+pub fn normalize_line_endings(content: &str, line_ending: LineEnding, quoting: Quoting) -> String {
+    let content = content.trim_end_matches(['\r', '\n']);
+    if content.is_empty() {
+        return String::new();
+    }
+    match line_ending {
+        LineEnding::Lf => format!("{content}\n"),
+        LineEnding::Crlf => {
+            let quotes: &[char] = match quoting {
+                Quoting::Escaped => &[],
+                Quoting::Sql => &['\'', '"', '`'],
+                Quoting::Csv => &['"'],
+                Quoting::Php => &['\''],
+            };
+            let mut converted = String::with_capacity(content.len() + content.len() / 32);
+            let mut previous = None;
+            // The quote of the value being read, if any
+            let mut quote = None;
+            // Last character of the comment being read, if any
+            let mut comment = None;
+            let mut escaped = false;
+            let mut chars = content.chars().peekable();
+            while let Some(c) = chars.next() {
+                let next = chars.peek().copied();
+                match (quote, comment) {
+                    (Some(_), _) if escaped => escaped = false,
+                    (Some(_), _) if c == '\\' && quoting == Quoting::Php => escaped = true,
+                    // A doubled quote closes the value and opens it again
+                    (Some(open), _) if c == open => quote = None,
+                    (Some(_), _) => {}
+                    (None, Some('\n')) if c == '\n' => comment = None,
+                    (None, Some('/')) if c == '/' && previous == Some('*') => comment = None,
+                    (None, Some(_)) => {}
+                    (None, None) if quoting == Quoting::Sql && (c, next) == ('-', Some('-')) => {
+                        comment = Some('\n')
+                    }
+                    (None, None) if quoting == Quoting::Php && (c, next) == ('/', Some('*')) => {
+                        comment = Some('/')
+                    }
+                    (None, None) if quotes.contains(&c) => quote = Some(c),
+                    (None, None) => {}
+                }
+                if c == '\n' && quote.is_none() && previous != Some('\r') {
+                    converted.push('\r');
+                }
+                converted.push(c);
+                previous = Some(c);
+            }
+            converted.push_str("\r\n");
+            converted
+        }
+    }
+}
 
 pub trait OutputWriter {
     fn write(&self, content: &str, destination: &OutputDestination) -> Result<(), OutputError>;
@@ -8,6 +93,22 @@ pub trait OutputWriter {
 #[derive(Debug)]
 pub enum OutputDestination {
     File(PathBuf),
+    S3(S3Location),
+    Stdout,
+}
+
+impl OutputDestination {
+    /// `-` is standard output and an `s3://bucket/key` path an S3 object;
+    /// anything else is a local file
+    pub fn for_path(path: &Path) -> Self {
+        if path.as_os_str() == "-" {
+            return OutputDestination::Stdout;
+        }
+        match path.to_str().and_then(S3Location::parse) {
+            Some(location) => OutputDestination::S3(location),
+            None => OutputDestination::File(path.to_path_buf()),
+        }
+    }
 }
 
 pub struct FileOutputWriter;
@@ -16,15 +117,59 @@ impl OutputWriter for FileOutputWriter {
     fn write(&self, content: &str, destination: &OutputDestination) -> Result<(), OutputError> {
         match destination {
             OutputDestination::File(path) => {
-                std::fs::write(path, content).map_err(|e| {
+                write_atomically(path, content.as_bytes()).map_err(|e| {
                     OutputError::WriteError(format!("Failed to write to {}: {}", path.display(), e))
-                })?;
+                })?
+            }
+            #[cfg(feature = "s3")]
+            OutputDestination::S3(location) => {
+                crate::output::s3::put_object(location, content.as_bytes())?
+            }
+            #[cfg(not(feature = "s3"))]
+            OutputDestination::S3(_) => {
+                return Err(crate::errors::FeatureDisabled("s3").into());
+            }
+            OutputDestination::Stdout => {
+                let mut stdout = std::io::stdout().lock();
+                match stdout
+                    .write_all(content.as_bytes())
+                    .and_then(|_| stdout.flush())
+                {
+                    // The reader, e.g. `head`, has seen enough
+                    Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe => {}
+                    result => result?,
+                }
             }
         }
         Ok(())
     }
 }
 
+/// Write `content` to a temporary file next to `path`, then rename it into
+/// place, so `path` never holds a partly written file after a crash or a
+/// full disk
+pub fn write_atomically(path: &Path, content: &[u8]) -> std::io::Result<()> {
+    // Threads of one process writing the same path each get their own file
+    static TEMP_FILES: AtomicUsize = AtomicUsize::new(0);
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let temp_path = path.with_file_name(format!(
+        ".{file_name}.{}.{}.tmp",
+        std::process::id(),
+        TEMP_FILES.fetch_add(1, Ordering::Relaxed)
+    ));
+    let result = std::fs::File::create(&temp_path)
+        .and_then(|mut file| {
+            file.write_all(content)?;
+            file.sync_all()
+        })
+        .and_then(|_| std::fs::rename(&temp_path, path));
+    if result.is_err() {
+        // Nothing useful is left in it
+        let _ = std::fs::remove_file(&temp_path);
+    }
+    result
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -46,4 +191,78 @@ mod tests {
         let written_content = std::fs::read_to_string(temp_file.path()).unwrap();
         assert_eq!(written_content, content);
     }
+
+    #[test]
+    fn test_write_atomically() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("out.sql");
+        std::fs::write(&path, "old").unwrap();
+        write_atomically(&path, b"new").unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "new");
+        // Only the output is left behind
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+        let missing = dir.path().join("missing").join("out.sql");
+        assert!(write_atomically(&missing, b"new").is_err());
+    }
+
+    #[test]
+    fn test_normalize_line_endings() {
+        let content = "INSERT INTO t VALUES\r\n(1),\n(2);\n\n";
+        assert_eq!(
+            normalize_line_endings(content, LineEnding::Lf, Quoting::Sql),
+            "INSERT INTO t VALUES\r\n(1),\n(2);\n"
+        );
+        assert_eq!(
+            normalize_line_endings(content, LineEnding::Crlf, Quoting::Sql),
+            "INSERT INTO t VALUES\r\n(1),\r\n(2);\r\n"
+        );
+        assert_eq!(
+            normalize_line_endings("a", LineEnding::Lf, Quoting::Sql),
+            "a\n"
+        );
+        assert_eq!(
+            normalize_line_endings("\n\n", LineEnding::Crlf, Quoting::Sql),
+            ""
+        );
+    }
+
+    #[test]
+    fn test_line_endings_keep_values() {
+        let crlf = |content, quoting| normalize_line_endings(content, LineEnding::Crlf, quoting);
+        assert_eq!(
+            crlf(
+                "-- Bob's sheet\nVALUES\n('a\nb','it''s\n'),\n(\"x\ny\");",
+                Quoting::Sql
+            ),
+            "-- Bob's sheet\r\nVALUES\r\n('a\nb','it''s\n'),\r\n(\"x\ny\");\r\n"
+        );
+        assert_eq!(
+            crlf("id,note\n1,\"a\n\"\"b\"\"\"\n", Quoting::Csv),
+            "id,note\r\n1,\"a\n\"\"b\"\"\"\r\n"
+        );
+        assert_eq!(
+            crlf("/* Bob's\n */\n['a\\'\nb',\n'c']", Quoting::Php),
+            "/* Bob's\r\n */\r\n['a\\'\nb',\r\n'c']\r\n"
+        );
+        assert_eq!(
+            crlf("{\"a\":\"x\"}\n{}", Quoting::Escaped),
+            "{\"a\":\"x\"}\r\n{}\r\n"
+        );
+    }
+
+    #[test]
+    fn test_destination_for_path() {
+        assert!(matches!(
+            OutputDestination::for_path(Path::new("s3://bucket/out/data.sql")),
+            OutputDestination::S3(location) if location.key == "out/data.sql"
+        ));
+        assert!(matches!(
+            OutputDestination::for_path(Path::new("out/data.sql")),
+            OutputDestination::File(_)
+        ));
+        assert!(matches!(
+            OutputDestination::for_path(Path::new("-")),
+            OutputDestination::Stdout
+        ));
+    }
 }
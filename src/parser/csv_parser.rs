@@ -0,0 +1,294 @@
+use crate::errors::ParseError;
+use crate::input::file_handler::validate_file_exists;
+use crate::parser::data_model::{SheetData, WorkbookData};
+use crate::parser::TabularParser;
+use calamine::{Data, Range};
+use encoding_rs::Encoding;
+use std::path::Path;
+use std::time::Instant;
+
+/// Extensions read as delimited text without an explicit `--delimiter`
+pub const DELIMITED_EXTENSIONS: &[&str] = &["csv", "tsv", "tab"];
+
+#[derive(Debug, Clone, Copy)]
+pub struct CsvOptions {
+    /// Field delimiter; tab for .tsv/.tab files and comma otherwise when unset
+    pub delimiter: Option<u8>,
+    pub quote: u8,
+    /// Character encoding of the file; a UTF-8 or UTF-16 BOM takes precedence
+    pub encoding: &'static Encoding,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: None,
+            quote: b'"',
+            encoding: encoding_rs::UTF_8,
+        }
+    }
+}
+
+/// Reads a delimited text file as a single sheet named after the file
+#[derive(Default)]
+pub struct CsvParser {
+    pub options: CsvOptions,
+}
+
+impl CsvParser {
+    pub fn new(options: CsvOptions) -> Self {
+        Self { options }
+    }
+
+    fn delimiter_for(&self, file_path: &Path) -> u8 {
+        let extension = file_path
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_lowercase());
+        match (self.options.delimiter, extension.as_deref()) {
+            (Some(delimiter), _) => delimiter,
+            (None, Some("tsv" | "tab")) => b'\t',
+            (None, _) => b',',
+        }
+    }
+
+    fn parse_delimited(
+        &self,
+        name: String,
+        bytes: &[u8],
+        delimiter: u8,
+    ) -> Result<WorkbookData, ParseError> {
+        let started = Instant::now();
+        let (text, encoding, had_errors) = self.options.encoding.decode(bytes);
+        if had_errors {
+            return Err(ParseError::Encoding(encoding.name().to_string()));
+        }
+        let read_time = started.elapsed();
+
+        let started = Instant::now();
+        let mut sheet = self.parse_text(name, &text, delimiter)?;
+        sheet.parse_time = started.elapsed();
+
+        Ok(WorkbookData {
+            read_time,
+            ..WorkbookData::new(vec![sheet])
+        })
+    }
+
+    fn parse_text(&self, name: String, text: &str, delimiter: u8) -> Result<SheetData, ParseError> {
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(delimiter)
+            .quote(self.options.quote)
+            .has_headers(false)
+            .flexible(true)
+            .from_reader(text.as_bytes());
+
+        // Blank lines, which the reader skips, are kept as the empty rows a
+        // spreadsheet would show for them; a record's position points at the
+        // blank lines before it.
+        let bytes = text.as_bytes();
+        let mut rows: Vec<(u32, Vec<Data>)> = Vec::new();
+        for record in reader.records() {
+            let record = record?;
+            let blank_lines = record.position().map_or(0, |p| {
+                let mut start = p.byte() as usize;
+                // The \n of the previous record's \r\n terminator
+                if start > 0 && bytes[start - 1] == b'\r' && bytes.get(start) == Some(&b'\n') {
+                    start += 1;
+                }
+                let mut blank_lines = 0;
+                while let Some(b'\r' | b'\n') = bytes.get(start) {
+                    blank_lines += u32::from(bytes[start] == b'\n');
+                    start += 1;
+                }
+                blank_lines
+            });
+            let row_idx = rows.last().map_or(0, |(row_idx, _)| row_idx + 1) + blank_lines;
+            rows.push((row_idx, record.iter().map(cell_value).collect()));
+        }
+
+        let width = rows.iter().map(|(_, row)| row.len()).max().unwrap_or(0);
+        let (Some((first_row, _)), Some((last_row, _))) = (rows.first(), rows.last()) else {
+            return Ok(SheetData::new(name, Range::empty()));
+        };
+        if width == 0 {
+            return Ok(SheetData::new(name, Range::empty()));
+        }
+        let mut range = Range::new((*first_row, 0), (*last_row, width as u32 - 1));
+        for (row_idx, row) in rows {
+            for (col_idx, cell) in row.into_iter().enumerate() {
+                range.set_value((row_idx, col_idx as u32), cell);
+            }
+        }
+        Ok(SheetData::new(name, range))
+    }
+}
+
+impl TabularParser for CsvParser {
+    fn parse(&self, file_path: &Path) -> Result<WorkbookData, ParseError> {
+        validate_file_exists(file_path).map_err(|_| ParseError::InvalidFormat)?;
+
+        let started = Instant::now();
+        let bytes = std::fs::read(file_path).map_err(|_| ParseError::InvalidFormat)?;
+        let read_time = started.elapsed();
+
+        let name = file_path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let mut workbook = self.parse_delimited(name, &bytes, self.delimiter_for(file_path))?;
+        workbook.read_time += read_time;
+        Ok(workbook)
+    }
+
+    fn parse_bytes(&self, name: &str, bytes: &[u8]) -> Result<WorkbookData, ParseError> {
+        let path = Path::new(name);
+        let stem = path.file_stem().map_or_else(
+            || name.to_string(),
+            |stem| stem.to_string_lossy().into_owned(),
+        );
+        self.parse_delimited(stem, bytes, self.delimiter_for(path))
+    }
+}
+
+/// Parse a single-byte delimiter or quote character such as ';'
+pub fn parse_single_byte(arg: &str) -> Result<u8, String> {
+    match arg.as_bytes() {
+        [byte] => Ok(*byte),
+        _ => Err(format!("expected a single ASCII character, got '{arg}'")),
+    }
+}
+
+/// Parse a delimiter: a single character, or `tab` / `\t`, `pipe`, `semicolon`, `space`
+pub fn parse_delimiter(arg: &str) -> Result<u8, String> {
+    match arg.to_ascii_lowercase().as_str() {
+        "tab" | "\\t" => Ok(b'\t'),
+        "pipe" => Ok(b'|'),
+        "semicolon" => Ok(b';'),
+        "space" => Ok(b' '),
+        _ => parse_single_byte(arg),
+    }
+}
+
+/// Look up an encoding by its WHATWG label, e.g. "shift_jis" or "windows-1252"
+pub fn parse_encoding(label: &str) -> Result<&'static Encoding, String> {
+    Encoding::for_label(label.trim().as_bytes())
+        .ok_or_else(|| format!("unknown encoding '{label}'"))
+}
+
+/// Type a field the way a spreadsheet would: numbers become numeric cells,
+/// except codes with leading zeros such as "00123", which stay text.
+fn cell_value(field: &str) -> Data {
+    if field.is_empty() {
+        return Data::Empty;
+    }
+    let digits = field.strip_prefix('-').unwrap_or(field);
+    let leading_zero = digits.len() > 1 && digits.starts_with('0') && !digits.starts_with("0.");
+    if leading_zero || field.trim() != field {
+        return Data::String(field.to_string());
+    }
+    if let Ok(int) = field.parse::<i64>() {
+        return Data::Int(int);
+    }
+    match field.parse::<f64>() {
+        Ok(float) if float.is_finite() && digits.starts_with(|c: char| c.is_ascii_digit()) => {
+            Data::Float(float)
+        }
+        _ => Data::String(field.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_cell_value() {
+        assert_eq!(cell_value(""), Data::Empty);
+        assert_eq!(cell_value("42"), Data::Int(42));
+        assert_eq!(cell_value("-1.5"), Data::Float(-1.5));
+        assert_eq!(cell_value("00123"), Data::String("00123".to_string()));
+        assert_eq!(cell_value("0.5"), Data::Float(0.5));
+        assert_eq!(cell_value("inf"), Data::String("inf".to_string()));
+        assert_eq!(cell_value(" 7"), Data::String(" 7".to_string()));
+    }
+
+    #[test]
+    fn test_parse_csv_file() {
+        let mut file = tempfile::Builder::new().suffix(".csv").tempfile().unwrap();
+        write!(file, "id;name\n1;'Smith; John'\n2;\n").unwrap();
+        let parser = CsvParser::new(CsvOptions {
+            delimiter: Some(b';'),
+            quote: b'\'',
+            ..Default::default()
+        });
+        let workbook = parser.parse(file.path()).unwrap();
+        let sheet = &workbook.sheets[0];
+        assert_eq!(sheet.get_columns().unwrap(), ["id", "name"]);
+        let rows: Vec<_> = sheet.get_data_rows().collect();
+        assert_eq!(rows[0][1], Data::String("Smith; John".to_string()));
+        assert_eq!(rows[1], [Data::Int(2), Data::Empty]);
+    }
+
+    #[test]
+    fn test_rows_keep_line_numbers() {
+        let workbook = CsvParser::default()
+            .parse_bytes("report", b"Exported 2024-01-05\r\n\r\nid\r\n\"a\nb\"\r\n")
+            .unwrap();
+        let sheet = &workbook.sheets[0];
+        assert_eq!(sheet.range.start(), Some((0, 0)));
+        assert_eq!(sheet.range.height(), 4);
+        assert_eq!(sheet.window(2, None, 0).get_columns().unwrap(), ["id"]);
+    }
+
+    #[test]
+    fn test_tsv_defaults_to_tab() {
+        let mut file = tempfile::Builder::new().suffix(".tsv").tempfile().unwrap();
+        write!(file, "id\tname\n1\ta,b\n").unwrap();
+        let workbook = CsvParser::default().parse(file.path()).unwrap();
+        let rows: Vec<_> = workbook.sheets[0].get_data_rows().collect();
+        assert_eq!(rows[0], [Data::Int(1), Data::String("a,b".to_string())]);
+    }
+
+    #[test]
+    fn test_parse_bytes() {
+        let workbook = CsvParser::default()
+            .parse_bytes("stdin", b"id,name\n1,a\n")
+            .unwrap();
+        assert_eq!(workbook.sheets[0].name, "stdin");
+        assert_eq!(workbook.sheets[0].get_columns().unwrap(), ["id", "name"]);
+
+        let workbook = CsvParser::default()
+            .parse_bytes("orders.tsv", b"id\tname\n")
+            .unwrap();
+        assert_eq!(workbook.sheets[0].name, "orders");
+        assert_eq!(workbook.sheets[0].get_columns().unwrap(), ["id", "name"]);
+    }
+
+    #[test]
+    fn test_parse_delimiter() {
+        assert_eq!(parse_delimiter("tab"), Ok(b'\t'));
+        assert_eq!(parse_delimiter("\\t"), Ok(b'\t'));
+        assert_eq!(parse_delimiter("|"), Ok(b'|'));
+        assert_eq!(parse_delimiter("PIPE"), Ok(b'|'));
+        assert!(parse_delimiter("::").is_err());
+    }
+
+    #[test]
+    fn test_parse_shift_jis() {
+        let (bytes, _, _) = encoding_rs::SHIFT_JIS.encode("名前\n山田\n");
+        let mut file = tempfile::Builder::new().suffix(".csv").tempfile().unwrap();
+        file.write_all(&bytes).unwrap();
+
+        let parser = CsvParser::new(CsvOptions {
+            encoding: encoding_rs::SHIFT_JIS,
+            ..Default::default()
+        });
+        let workbook = parser.parse(file.path()).unwrap();
+        assert_eq!(workbook.sheets[0].get_columns().unwrap(), ["名前"]);
+        assert!(matches!(
+            CsvParser::default().parse(file.path()),
+            Err(ParseError::Encoding(_))
+        ));
+    }
+}
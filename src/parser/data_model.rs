@@ -1,15 +1,38 @@
 use calamine::{Data, Range};
 use chrono::NaiveDate;
+use std::time::Duration;
 
 #[derive(Debug, Clone)]
 pub struct WorkbookData {
     pub sheets: Vec<SheetData>,
+    /// Time spent opening the workbook before any sheet was parsed
+    pub read_time: Duration,
+    /// Workbook-defined names and the references they stand for, e.g.
+    /// `("SalesData", "Sheet1!$A$1:$D$20")`
+    pub defined_names: Vec<(String, String)>,
+    /// Excel Tables (ListObjects), when the parser was asked to read them
+    pub tables: Vec<TableDefinition>,
+}
+
+/// Where an Excel Table sits in its worksheet
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableDefinition {
+    pub name: String,
+    pub sheet: String,
+    /// Header row and data rows, without any totals row
+    pub area: CellArea,
 }
 
 #[derive(Debug, Clone)]
 pub struct SheetData {
     pub name: String,
+    /// Worksheet holding the cells; differs from `name` for named ranges and tables
+    pub worksheet: String,
     pub range: Range<Data>,
+    /// Zero-based position of the sheet in its workbook
+    pub index: usize,
+    /// Time spent parsing this sheet's cells
+    pub parse_time: Duration,
 }
 
 #[derive(Debug)]
@@ -17,6 +40,97 @@ pub struct SqlStatement {
     pub table_name: String,
     pub columns: Vec<String>,
     pub values: Vec<Vec<SqlValue>>,
+    pub warnings: Vec<String>,
+    pub stats: StatementStats,
+}
+
+/// Bookkeeping about how a statement was produced
+#[derive(Debug, Clone, Default)]
+pub struct StatementStats {
+    pub sheet: String,
+    /// Time spent converting the sheet's cells
+    pub convert_time: Duration,
+    /// Data rows left out by a row filter
+    pub filtered_rows: usize,
+    /// Data rows skipped for holding Excel error cells
+    pub error_rows: usize,
+    /// Data rows skipped under --keep-going for a value that failed to convert
+    pub failed_rows: usize,
+    /// Rows left out past --limit
+    pub limited_rows: usize,
+    /// Rows left out by --incremental as unchanged since the last run
+    pub unchanged_rows: usize,
+    /// Type declared for each column, as --column-type spells it; `None`
+    /// where values keep the type of their cells
+    pub declared_types: Vec<Option<String>>,
+}
+
+impl WorkbookData {
+    pub fn new(sheets: Vec<SheetData>) -> Self {
+        Self {
+            sheets,
+            read_time: Duration::ZERO,
+            defined_names: Vec::new(),
+            tables: Vec::new(),
+        }
+    }
+
+    /// Copy of the workbook keeping at most `rows` data rows per sheet
+    pub fn head(&self, rows: usize) -> WorkbookData {
+        WorkbookData {
+            sheets: self.sheets.iter().map(|s| s.head(rows)).collect(),
+            read_time: self.read_time,
+            defined_names: self.defined_names.clone(),
+            tables: self.tables.clone(),
+        }
+    }
+
+    /// Tables of the sheet named `sheet`, in workbook order
+    pub fn tables_in<'a>(&'a self, sheet: &'a str) -> impl Iterator<Item = SheetData> + 'a {
+        self.tables
+            .iter()
+            .filter(move |table| table.sheet == sheet)
+            .filter_map(|table| self.area(&table.name, &table.sheet, table.area))
+    }
+
+    pub fn defined_name(&self, name: &str) -> Option<&str> {
+        self.find_defined_name(name)
+            .map(|(_, reference)| reference.as_str())
+    }
+
+    fn find_defined_name(&self, name: &str) -> Option<&(String, String)> {
+        self.defined_names
+            .iter()
+            .find(|(defined, _)| defined.eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// The cells a defined name refers to, as a sheet named after it; `None`
+    /// when the name refers to anything but a single rectangle of one sheet
+    pub fn named_range(&self, name: &str) -> Option<SheetData> {
+        let (name, reference) = self.find_defined_name(name)?;
+        let (sheet_name, area) = parse_range_reference(reference)?;
+        self.area(name, &sheet_name, area)
+    }
+
+    /// Cells `(start, end)` of the sheet named `sheet_name`, as a sheet named
+    /// `name`; an area reaching past the used cells, such as `$A:$A`, ends
+    /// with them
+    fn area(&self, name: &str, sheet_name: &str, (start, end): CellArea) -> Option<SheetData> {
+        let sheet = self.sheets.iter().find(|s| s.name == sheet_name)?;
+        let range = match sheet.range.end() {
+            Some(used) if start.0 <= used.0 && start.1 <= used.1 => sheet
+                .range
+                .range(start, (end.0.min(used.0), end.1.min(used.1))),
+            _ => Range::empty(),
+        };
+        Some(SheetData {
+            name: name.to_string(),
+            worksheet: sheet.worksheet.clone(),
+            range,
+            index: sheet.index,
+            parse_time: sheet.parse_time,
+        })
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -24,11 +138,47 @@ pub enum SqlValue {
     Text(String),
     Number(f64),
     Integer(i64),
+    /// Numeric literal already rendered with a fixed number of decimals
+    Decimal(String),
     Boolean(bool),
     DateTime(String),
+    /// Validated JSON document
+    Json(String),
+    /// List of text elements
+    Array(Vec<String>),
+    Binary(Vec<u8>),
+    Uuid(String),
+    /// Elapsed time from an ISO 8601 or [h]:mm:ss duration cell
+    Duration(chrono::Duration),
     Null,
 }
 
+impl std::fmt::Display for SqlValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SqlValue::Text(s) | SqlValue::DateTime(s) | SqlValue::Json(s) | SqlValue::Uuid(s) => {
+                write!(f, "'{s}'")
+            }
+            SqlValue::Number(n) => write!(f, "{n}"),
+            SqlValue::Integer(i) => write!(f, "{i}"),
+            SqlValue::Decimal(d) => write!(f, "{d}"),
+            SqlValue::Boolean(b) => write!(f, "{b}"),
+            SqlValue::Array(items) => write!(f, "[{}]", items.join(", ")),
+            SqlValue::Binary(bytes) => {
+                write!(f, "X'")?;
+                for byte in bytes {
+                    write!(f, "{byte:02X}")?;
+                }
+                write!(f, "'")
+            }
+            SqlValue::Duration(d) => {
+                write!(f, "'{}'", crate::generator::duration::format_duration(d))
+            }
+            SqlValue::Null => write!(f, "NULL"),
+        }
+    }
+}
+
 impl From<&Data> for SqlValue {
     fn from(data: &Data) -> Self {
         match data {
@@ -37,25 +187,9 @@ impl From<&Data> for SqlValue {
             Data::Float(f) => SqlValue::Number(*f),
             Data::Int(i) => SqlValue::Integer(*i),
             Data::Bool(b) => SqlValue::Boolean(*b),
-            Data::DateTime(dt) => {
-                // Convert Excel datetime to SQL datetime format
-                // Excel dates start from 1900-01-01 (serial 1)
-                let excel_epoch = NaiveDate::from_ymd_opt(1899, 12, 30).unwrap(); // Excel epoch is 1899-12-30
-                let days = dt.as_f64() as i64;
-                let seconds = ((dt.as_f64() - days as f64) * 86400.0) as u32;
-
-                if let Some(date) = excel_epoch.checked_add_signed(chrono::Duration::days(days)) {
-                    if let Some(datetime) = date.and_hms_opt(0, 0, 0).and_then(|dt| {
-                        dt.checked_add_signed(chrono::Duration::seconds(seconds as i64))
-                    }) {
-                        SqlValue::DateTime(datetime.format("%Y-%m-%d %H:%M:%S").to_string())
-                    } else {
-                        SqlValue::Number(dt.as_f64())
-                    }
-                } else {
-                    SqlValue::Number(dt.as_f64())
-                }
-            }
+            Data::DateTime(dt) => format_excel_datetime(dt.as_f64(), 0)
+                .map(SqlValue::DateTime)
+                .unwrap_or(SqlValue::Number(dt.as_f64())),
             Data::Error(_) => SqlValue::Null,
             Data::DateTimeIso(dt) => SqlValue::DateTime(dt.clone()),
             Data::DurationIso(dur) => SqlValue::Text(dur.clone()),
@@ -63,7 +197,40 @@ impl From<&Data> for SqlValue {
     }
 }
 
+/// Render an Excel date serial as `YYYY-MM-DD HH:MM:SS` with `precision`
+/// fractional second digits (0-6); digits beyond the precision are truncated.
+pub fn format_excel_datetime(serial: f64, precision: usize) -> Option<String> {
+    // Excel dates start from 1900-01-01 (serial 1); the epoch is 1899-12-30
+    let excel_epoch = NaiveDate::from_ymd_opt(1899, 12, 30).unwrap();
+    let days = serial as i64;
+    // Round to whole microseconds first so float noise does not lose a second
+    let micros = ((serial - days as f64) * 86_400_000_000.0).round() as i64;
+    let unit = 10i64.pow(6 - precision.min(6) as u32);
+    let datetime = excel_epoch
+        .checked_add_signed(chrono::Duration::days(days))?
+        .and_hms_opt(0, 0, 0)?
+        .checked_add_signed(chrono::Duration::microseconds(micros / unit * unit))?;
+
+    let mut text = datetime.format("%Y-%m-%d %H:%M:%S").to_string();
+    if precision > 0 {
+        let fraction = format!("{:06}", datetime.and_utc().timestamp_subsec_micros());
+        text.push('.');
+        text.push_str(&fraction[..precision.min(6)]);
+    }
+    Some(text)
+}
+
 impl SheetData {
+    pub fn new(name: String, range: Range<Data>) -> Self {
+        Self {
+            worksheet: name.clone(),
+            name,
+            range,
+            index: 0,
+            parse_time: Duration::ZERO,
+        }
+    }
+
     pub fn get_columns(&self) -> Result<Vec<String>, crate::errors::ParseError> {
         if let Some(first_row) = self.range.rows().next() {
             let columns: Vec<String> = first_row
@@ -89,6 +256,146 @@ impl SheetData {
     pub fn get_data_rows(&self) -> impl Iterator<Item = &[Data]> {
         self.range.rows().skip(1) // Skip header row
     }
+
+    /// Copy of the sheet keeping the header row and at most `rows` data rows
+    pub fn head(&self, rows: usize) -> SheetData {
+        self.window(0, Some(rows), 0)
+    }
+
+    /// The sheet without its first `skip_rows` worksheet rows (the header is
+    /// the first row after them) and its last `skip_footer` rows, keeping at
+    /// most `max_rows` data rows. Cell references are unchanged.
+    pub fn window(
+        &self,
+        skip_rows: usize,
+        max_rows: Option<usize>,
+        skip_footer: usize,
+    ) -> SheetData {
+        let range = match (self.range.start(), self.range.end()) {
+            (Some(start), Some(end)) => {
+                let first_row = start.0.max(skip_rows as u32);
+                let mut last_row = end.0 as i64 - skip_footer as i64;
+                if let Some(rows) = max_rows {
+                    last_row = last_row.min(first_row as i64 + rows as i64);
+                }
+                if last_row < first_row as i64 {
+                    Range::empty()
+                } else {
+                    self.range
+                        .range((first_row, start.1), (last_row as u32, end.1))
+                }
+            }
+            _ => self.range.clone(),
+        };
+        SheetData {
+            name: self.name.clone(),
+            worksheet: self.worksheet.clone(),
+            range,
+            index: self.index,
+            parse_time: self.parse_time,
+        }
+    }
+
+    /// A1-style reference (e.g. `Sheet1!B3`) for a cell of `get_data_rows`
+    pub fn cell_reference(&self, data_row: usize, col: usize) -> String {
+        let (start_row, start_col) = self.range.start().unwrap_or((0, 0));
+        format!(
+            "{}!{}{}",
+            self.worksheet,
+            column_letter(start_col as usize + col),
+            start_row as usize + data_row + 2
+        )
+    }
+}
+
+/// Convert a zero-based column index into Excel letters (0 -> A, 26 -> AA)
+pub fn column_letter(index: usize) -> String {
+    let mut letters = Vec::new();
+    let mut n = index + 1;
+    while n > 0 {
+        let rem = (n - 1) % 26;
+        letters.push(b'A' + rem as u8);
+        n = (n - 1) / 26;
+    }
+    letters.reverse();
+    String::from_utf8(letters).unwrap()
+}
+
+/// Zero-based `(row, column)` of the top-left and bottom-right cells
+pub type CellArea = ((u32, u32), (u32, u32));
+
+/// Split a reference such as `'My Sheet'!$A$1:$D$20` into the sheet name
+/// and the area it covers
+pub fn parse_range_reference(reference: &str) -> Option<(String, CellArea)> {
+    let reference = reference.trim().trim_start_matches('=');
+    let (sheet, cells) = match reference.strip_prefix('\'') {
+        // Quoted names may contain '!'; a quote inside is doubled
+        Some(quoted) => {
+            let (sheet, cells) = quoted.rsplit_once("'!")?;
+            (sheet.replace("''", "'"), cells)
+        }
+        None => {
+            let (sheet, cells) = reference.split_once('!')?;
+            if sheet.contains(['(', ')', ',', ' ']) {
+                return None;
+            }
+            (sheet.to_string(), cells)
+        }
+    };
+    let (start, end) = cells.split_once(':').unwrap_or((cells, cells));
+    let (start, end) = (parse_area_bound(start)?, parse_area_bound(end)?);
+    Some((
+        sheet,
+        (
+            (start.0 .0.min(end.0 .0), start.0 .1.min(end.0 .1)),
+            (start.1 .0.max(end.1 .0), start.1 .1.max(end.1 .1)),
+        ),
+    ))
+}
+
+/// Last row and column of a worksheet, which whole-column (`$A:$A`) and
+/// whole-row (`$1:$1`) references reach
+const LAST_CELL: (u32, u32) = (1_048_575, 16_383);
+
+/// The area one side of a range covers: a cell, a whole column or a whole row
+fn parse_area_bound(bound: &str) -> Option<CellArea> {
+    if let Some(cell) = parse_cell_reference(bound) {
+        return Some((cell, cell));
+    }
+    let bound = bound.replace('$', "");
+    match bound.parse::<u32>() {
+        Ok(row) => {
+            let row = row.checked_sub(1)?;
+            Some(((row, 0), (row, LAST_CELL.1)))
+        }
+        Err(_) => {
+            let column = column_index(&bound)? as u32;
+            Some(((0, column), (LAST_CELL.0, column)))
+        }
+    }
+}
+
+/// Parse `$B$3` or `B3` into a zero-based `(row, column)`
+fn parse_cell_reference(cell: &str) -> Option<(u32, u32)> {
+    let cell = cell.replace('$', "");
+    let digits = cell.find(|c: char| c.is_ascii_digit())?;
+    let column = column_index(&cell[..digits])?;
+    let row: u32 = cell[digits..].parse().ok()?;
+    Some((row.checked_sub(1)?, column as u32))
+}
+
+/// Convert Excel column letters into a zero-based index (A -> 0, AA -> 26)
+pub fn column_index(letters: &str) -> Option<usize> {
+    if letters.is_empty() || letters.len() > 3 {
+        return None;
+    }
+    letters
+        .chars()
+        .try_fold(0usize, |index, c| {
+            c.is_ascii_alphabetic()
+                .then(|| index * 26 + (c.to_ascii_uppercase() as u8 - b'A') as usize + 1)
+        })
+        .map(|n| n - 1)
 }
 
 // Tests will be added later with proper test data
@@ -98,7 +405,126 @@ mod tests {
 
     #[test]
     fn test_workbook_data_creation() {
-        let workbook = WorkbookData { sheets: vec![] };
+        let workbook = WorkbookData::new(vec![]);
         assert!(workbook.sheets.is_empty());
     }
+
+    #[test]
+    fn test_format_excel_datetime() {
+        // 2024-01-05 12:34:56.789
+        let serial = 45296.0 + (12.0 * 3600.0 + 34.0 * 60.0 + 56.789) / 86400.0;
+        assert_eq!(
+            format_excel_datetime(serial, 0),
+            Some("2024-01-05 12:34:56".to_string())
+        );
+        assert_eq!(
+            format_excel_datetime(serial, 3),
+            Some("2024-01-05 12:34:56.789".to_string())
+        );
+        assert_eq!(
+            format_excel_datetime(serial, 6),
+            Some("2024-01-05 12:34:56.789000".to_string())
+        );
+    }
+
+    #[test]
+    fn test_column_letter() {
+        assert_eq!(column_letter(0), "A");
+        assert_eq!(column_letter(25), "Z");
+        assert_eq!(column_letter(26), "AA");
+        assert_eq!(column_letter(701), "ZZ");
+        assert_eq!(column_letter(702), "AAA");
+    }
+
+    #[test]
+    fn test_parse_range_reference() {
+        assert_eq!(
+            parse_range_reference("Sheet1!$A$1:$D$20"),
+            Some(("Sheet1".to_string(), ((0, 0), (19, 3))))
+        );
+        assert_eq!(
+            parse_range_reference("='Bob''s data'!B3"),
+            Some(("Bob's data".to_string(), ((2, 1), (2, 1))))
+        );
+        assert_eq!(
+            parse_range_reference("Sheet1!$B:$C"),
+            Some(("Sheet1".to_string(), ((0, 1), (1_048_575, 2))))
+        );
+        assert_eq!(
+            parse_range_reference("Sheet1!$2:$3"),
+            Some(("Sheet1".to_string(), ((1, 0), (2, 16_383))))
+        );
+        assert_eq!(parse_range_reference("Sheet1!$A$1:$A$2,Sheet1!$C$1"), None);
+        assert_eq!(parse_range_reference("OFFSET(Sheet1!$A$1,0,0)"), None);
+        assert_eq!(parse_range_reference("0.08"), None);
+    }
+
+    #[test]
+    fn test_named_range() {
+        let mut range = Range::new((0, 0), (3, 3));
+        range.set_value((1, 1), Data::String("id".to_string()));
+        range.set_value((2, 1), Data::Int(7));
+        let mut data = WorkbookData::new(vec![SheetData::new("Sheet1".to_string(), range)]);
+        data.defined_names
+            .push(("SalesData".to_string(), "Sheet1!$B$2:$C$3".to_string()));
+
+        let sheet = data.named_range("salesdata").unwrap();
+        assert_eq!(sheet.name, "SalesData");
+        assert_eq!(sheet.get_columns().unwrap(), ["id", ""]);
+        assert_eq!(sheet.cell_reference(0, 0), "Sheet1!B3");
+        assert!(data.named_range("Missing").is_none());
+
+        // Whole columns end with the used cells
+        data.defined_names
+            .push(("Ids".to_string(), "Sheet1!$B:$B".to_string()));
+        let sheet = data.named_range("Ids").unwrap();
+        assert_eq!(sheet.range.end(), Some((3, 1)));
+        assert_eq!(sheet.range.get_value((2, 1)), Some(&Data::Int(7)));
+        data.defined_names
+            .push(("Beyond".to_string(), "Sheet1!$F$9:$G$12".to_string()));
+        assert!(data.named_range("Beyond").unwrap().range.is_empty());
+    }
+
+    #[test]
+    fn test_column_index() {
+        assert_eq!(column_index("A"), Some(0));
+        assert_eq!(column_index("z"), Some(25));
+        assert_eq!(column_index("AA"), Some(26));
+        assert_eq!(column_index("ZZ"), Some(701));
+        assert_eq!(column_index(""), None);
+        assert_eq!(column_index("A1"), None);
+    }
+
+    #[test]
+    fn test_head_keeps_header() {
+        let mut range = Range::new((0, 0), (9, 0));
+        range.set_value((0, 0), Data::String("id".to_string()));
+        let sheet = SheetData::new("Sheet1".to_string(), range);
+        assert_eq!(sheet.head(3).range.height(), 4);
+        assert_eq!(sheet.head(100).range.height(), 10);
+    }
+
+    #[test]
+    fn test_window_skips_preamble_and_footer() {
+        let mut range = Range::new((0, 0), (9, 0));
+        range.set_value((2, 0), Data::String("id".to_string()));
+        range.set_value((9, 0), Data::String("Total".to_string()));
+        let sheet = SheetData::new("Sheet1".to_string(), range);
+
+        let window = sheet.window(2, None, 1);
+        assert_eq!(window.get_columns().unwrap(), ["id"]);
+        assert_eq!(window.range.height(), 7);
+        assert_eq!(window.cell_reference(0, 0), "Sheet1!A4");
+        assert_eq!(sheet.window(2, Some(3), 1).range.height(), 4);
+        assert!(sheet.window(20, None, 0).range.is_empty());
+    }
+
+    #[test]
+    fn test_cell_reference_accounts_for_header_and_offset() {
+        let mut range = Range::new((2, 1), (4, 2));
+        range.set_value((2, 1), Data::String("id".to_string()));
+        let sheet = SheetData::new("Sheet1".to_string(), range);
+        assert_eq!(sheet.cell_reference(0, 0), "Sheet1!B4");
+        assert_eq!(sheet.cell_reference(1, 1), "Sheet1!C5");
+    }
 }
@@ -1,4 +1,72 @@
+pub mod csv_parser;
 pub mod data_model;
 pub mod xlsx_parser;
 
+pub use csv_parser::*;
 pub use xlsx_parser::*;
+
+use crate::errors::ParseError;
+use crate::input::file_handler::SPREADSHEET_EXTENSIONS;
+use crate::parser::data_model::WorkbookData;
+use std::path::Path;
+
+/// Reads a file of rows and columns into sheets
+pub trait TabularParser {
+    fn parse(&self, file_path: &Path) -> Result<WorkbookData, ParseError>;
+
+    /// Parse file contents that did not come from a file, e.g. standard input;
+    /// `name` stands in for the file name where sheets are named after it.
+    fn parse_bytes(&self, name: &str, bytes: &[u8]) -> Result<WorkbookData, ParseError>;
+}
+
+/// Options for whichever parser an input ends up with
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ParseOptions {
+    pub csv: CsvOptions,
+    /// Read the position of Excel Tables (xlsx only)
+    pub tables: bool,
+    /// Copy the value of merged cells into every cell they cover (xlsx only)
+    pub fill_merged: bool,
+}
+
+/// Warn about the options only .xlsx files have, which `format` input goes
+/// without
+pub(crate) fn warn_xlsx_only(tables: bool, fill_merged: bool, format: &str) {
+    if fill_merged {
+        log::warn!("--fill-merged applies to .xlsx files only; ignored for {format} input");
+    }
+    if tables {
+        log::warn!("--tables applies to .xlsx files only; ignored for {format} input");
+    }
+}
+
+/// Pick the parser for `file_path` by its extension; with an explicit
+/// delimiter, files that are not spreadsheets are read as delimited text.
+pub fn parser_for(file_path: &Path, options: ParseOptions) -> Box<dyn TabularParser> {
+    let extension = file_path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let spreadsheet = SPREADSHEET_EXTENSIONS.contains(&extension.as_str());
+    if DELIMITED_EXTENSIONS.contains(&extension.as_str())
+        || (!spreadsheet && options.csv.delimiter.is_some())
+    {
+        warn_xlsx_only(options.tables, options.fill_merged, "delimited text");
+        Box::new(CsvParser::new(options.csv))
+    } else {
+        Box::new(CalamineXlsxParser::new(options))
+    }
+}
+
+/// Pick the parser for file contents by their signature: ZIP (xlsx, xlsb,
+/// ods) and OLE (xls) containers are workbooks, anything else delimited text.
+pub fn parser_for_bytes(bytes: &[u8], options: ParseOptions) -> Box<dyn TabularParser> {
+    const ZIP_MAGIC: &[u8] = b"PK\x03\x04";
+    const OLE_MAGIC: &[u8] = &[0xd0, 0xcf, 0x11, 0xe0];
+    if bytes.starts_with(ZIP_MAGIC) || bytes.starts_with(OLE_MAGIC) {
+        Box::new(CalamineXlsxParser::new(options))
+    } else {
+        warn_xlsx_only(options.tables, options.fill_merged, "delimited text");
+        Box::new(CsvParser::new(options.csv))
+    }
+}
@@ -1,40 +1,146 @@
 use crate::errors::ParseError;
 use crate::input::file_handler::{validate_file_exists, validate_file_format};
-use crate::parser::data_model::{SheetData, WorkbookData};
-use calamine::{open_workbook_auto, Reader};
+use crate::parser::data_model::{SheetData, TableDefinition, WorkbookData};
+use crate::parser::{warn_xlsx_only, ParseOptions, TabularParser};
+use calamine::{
+    open_workbook_auto, open_workbook_auto_from_rs, Data, Dimensions, Range, Reader, Sheets, Xlsx,
+};
+use std::io::{Cursor, Read, Seek};
 use std::path::Path;
+use std::time::{Duration, Instant};
 
-pub trait XlsxParser {
-    fn parse(&self, file_path: &Path) -> Result<WorkbookData, ParseError>;
+#[derive(Default)]
+pub struct CalamineXlsxParser {
+    /// Also locate Excel Tables, which costs a second read of sheets holding one
+    pub tables: bool,
+    /// Copy the value of each merged region into all of its cells
+    pub fill_merged: bool,
 }
 
-pub struct CalamineXlsxParser;
-
-impl XlsxParser for CalamineXlsxParser {
-    fn parse(&self, file_path: &Path) -> Result<WorkbookData, ParseError> {
-        // Validate file exists and format
-        validate_file_exists(file_path).map_err(|_| ParseError::InvalidFormat)?;
-        validate_file_format(file_path).map_err(|_| ParseError::InvalidFormat)?;
+impl CalamineXlsxParser {
+    pub fn new(options: ParseOptions) -> Self {
+        Self {
+            tables: options.tables,
+            fill_merged: options.fill_merged,
+        }
+    }
 
-        let mut workbook = open_workbook_auto(file_path)?;
+    fn read_sheets<RS: Read + Seek>(
+        &self,
+        mut workbook: Sheets<RS>,
+        read_time: Duration,
+    ) -> Result<WorkbookData, ParseError> {
         let sheet_names = workbook.sheet_names().to_owned();
+        let defined_names = workbook.defined_names().to_owned();
 
         if sheet_names.is_empty() {
             return Err(ParseError::InvalidFormat);
         }
+        let format = match &workbook {
+            Sheets::Xlsx(_) => None,
+            Sheets::Xls(_) => Some(".xls"),
+            Sheets::Xlsb(_) => Some(".xlsb"),
+            Sheets::Ods(_) => Some(".ods"),
+        };
+        if let Some(format) = format {
+            warn_xlsx_only(self.tables, self.fill_merged, format);
+        }
+
+        let merged_regions = match &mut workbook {
+            Sheets::Xlsx(xlsx) if self.fill_merged => {
+                xlsx.load_merged_regions().map_err(calamine::Error::Xlsx)?;
+                xlsx.merged_regions().clone()
+            }
+            _ => Vec::new(),
+        };
 
         let mut sheets = Vec::new();
-        for sheet_name in sheet_names {
-            if let Ok(range) = workbook.worksheet_range(&sheet_name) {
-                sheets.push(SheetData {
-                    name: sheet_name,
-                    range,
-                });
+        for (index, sheet_name) in sheet_names.into_iter().enumerate() {
+            let started = Instant::now();
+            if let Ok(mut range) = workbook.worksheet_range(&sheet_name) {
+                for (_, _, region) in merged_regions.iter().filter(|(s, ..)| *s == sheet_name) {
+                    fill_region(&mut range, region);
+                }
+                let mut sheet = SheetData::new(sheet_name, range);
+                sheet.index = index;
+                sheet.parse_time = started.elapsed();
+                sheets.push(sheet);
             }
         }
 
-        Ok(WorkbookData { sheets })
+        let tables = match &mut workbook {
+            Sheets::Xlsx(xlsx) if self.tables => read_tables(xlsx)?,
+            _ => Vec::new(),
+        };
+
+        Ok(WorkbookData {
+            sheets,
+            read_time,
+            defined_names,
+            tables,
+        })
+    }
+}
+
+impl TabularParser for CalamineXlsxParser {
+    fn parse(&self, file_path: &Path) -> Result<WorkbookData, ParseError> {
+        // Validate file exists and format
+        validate_file_exists(file_path).map_err(|_| ParseError::InvalidFormat)?;
+        validate_file_format(file_path).map_err(|_| ParseError::InvalidFormat)?;
+
+        let started = Instant::now();
+        let workbook = open_workbook_auto(file_path)?;
+        self.read_sheets(workbook, started.elapsed())
+    }
+
+    fn parse_bytes(&self, _name: &str, bytes: &[u8]) -> Result<WorkbookData, ParseError> {
+        let started = Instant::now();
+        let workbook = open_workbook_auto_from_rs(Cursor::new(bytes))?;
+        self.read_sheets(workbook, started.elapsed())
+    }
+}
+
+/// Names of the sheets of a workbook, without reading their cells; file
+/// contents are given as `bytes`
+pub fn sheet_names(file_path: &Path, bytes: Option<&[u8]>) -> Result<Vec<String>, ParseError> {
+    let workbook = match bytes {
+        Some(bytes) => open_workbook_auto_from_rs(Cursor::new(bytes))?.sheet_names(),
+        None => open_workbook_auto(file_path)?.sheet_names(),
+    };
+    Ok(workbook)
+}
+
+/// Give every cell of a merged region the value of its top-left cell
+fn fill_region(range: &mut Range<Data>, region: &Dimensions) {
+    let Some(value) = range.get_value(region.start).cloned() else {
+        return;
+    };
+    if value == Data::Empty {
+        return;
+    }
+    for row in region.start.0..=region.end.0 {
+        for col in region.start.1..=region.end.1 {
+            range.set_value((row, col), value.clone());
+        }
+    }
+}
+
+fn read_tables<RS: Read + Seek>(xlsx: &mut Xlsx<RS>) -> Result<Vec<TableDefinition>, ParseError> {
+    xlsx.load_tables().map_err(calamine::Error::Xlsx)?;
+    let names: Vec<String> = xlsx.table_names().into_iter().cloned().collect();
+    let mut tables = Vec::new();
+    for name in names {
+        let table = xlsx.table_by_name(&name).map_err(calamine::Error::Xlsx)?;
+        // The table's data starts below its header row
+        if let (Some(start), Some(end)) = (table.data().start(), table.data().end()) {
+            tables.push(TableDefinition {
+                name,
+                sheet: table.sheet_name().to_string(),
+                area: ((start.0.saturating_sub(1), start.1), end),
+            });
+        }
     }
+    Ok(tables)
 }
 
 #[cfg(test)]
@@ -42,10 +148,36 @@ mod tests {
     use super::*;
     use std::path::Path;
 
+    #[test]
+    fn test_fill_region() {
+        let mut range = Range::new((0, 0), (2, 1));
+        range.set_value((0, 0), Data::String("EMEA".to_string()));
+        range.set_value((2, 1), Data::Int(3));
+        fill_region(
+            &mut range,
+            &Dimensions {
+                start: (0, 0),
+                end: (1, 1),
+            },
+        );
+        assert_eq!(
+            range.get_value((1, 1)),
+            Some(&Data::String("EMEA".to_string()))
+        );
+        assert_eq!(range.get_value((2, 0)), Some(&Data::Empty));
+        assert_eq!(range.get_value((2, 1)), Some(&Data::Int(3)));
+    }
+
     #[test]
     fn test_parse_nonexistent_file() {
-        let parser = CalamineXlsxParser;
+        let parser = CalamineXlsxParser::default();
         let result = parser.parse(Path::new("nonexistent.xlsx"));
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_sheet_names_of_non_workbooks() {
+        assert!(sheet_names(Path::new("nonexistent.xlsx"), None).is_err());
+        assert!(sheet_names(Path::new("-"), Some(b"id,name\n1,a\n")).is_err());
+    }
 }